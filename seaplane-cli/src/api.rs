@@ -3,11 +3,13 @@
 mod formations;
 mod locks;
 mod metadata;
+mod registry;
 mod restrict;
 
 pub use formations::FormationsReq;
 pub use locks::LocksReq;
 pub use metadata::MetadataReq;
+pub use registry::RegistryReq;
 use reqwest::Url;
 pub use restrict::RestrictReq;
 use seaplane::api::identity::v0::{AccessToken, TokenRequest};
@@ -38,13 +40,32 @@ pub fn request_token_jwt(
 ///
 /// **WARNING**: `allow_insecure` enables using HTTP endpoints, but only has an affect when
 /// compiled with feature `allow_insecure_urls`. It is ignored otherwise, it is ignored otherwise.
-#[cfg_attr(not(feature = "allow_insecure_urls"), allow(unused_variables))]
 pub fn request_token(
     api_key: &str,
     identity_url: Option<&Url>,
     allow_insecure: bool,
     allow_invalid_certs: bool,
 ) -> Result<AccessToken> {
+    token_request(api_key, identity_url, allow_insecure, allow_invalid_certs)
+        .map_err(CliError::from)
+        .context("Context: failed to build Access Token request\n")?
+        .access_token_json()
+        .map_err(CliError::from)
+        .context("Context: failed to retrieve an Access Token\n")
+}
+
+/// Builds the request against the `/token` endpoint of FlightDeck used by `request_token`, for
+/// when the SDK error is needed as is
+///
+/// **WARNING**: `allow_insecure` enables using HTTP endpoints, but only has an affect when
+/// compiled with feature `allow_insecure_urls`. It is ignored otherwise.
+#[cfg_attr(not(feature = "allow_insecure_urls"), allow(unused_variables))]
+pub fn token_request(
+    api_key: &str,
+    identity_url: Option<&Url>,
+    allow_insecure: bool,
+    allow_invalid_certs: bool,
+) -> seaplane::error::Result<TokenRequest> {
     let mut builder = TokenRequest::builder().api_key(api_key);
 
     #[cfg(feature = "allow_insecure_urls")]
@@ -59,11 +80,5 @@ pub fn request_token(
         builder = builder.base_url(url);
     }
 
-    builder
-        .build()
-        .map_err(CliError::from)
-        .context("Context: failed to build Access Token request\n")?
-        .access_token_json()
-        .map_err(CliError::from)
-        .context("Context: failed to retrieve an Access Token\n")
+    builder.build()
 }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use reqwest::Url;
 use seaplane::{
     api::{
@@ -9,11 +11,11 @@ use seaplane::{
         shared::v1::{Directory, RangeQueryContext},
         ApiErrorKind,
     },
-    error::SeaplaneError,
+    error::{Result as SeaplaneResult, SeaplaneError},
 };
 
 use crate::{
-    api::request_token,
+    api::{request_token, token_request},
     context::Ctx,
     error::{CliError, Result},
 };
@@ -69,6 +71,14 @@ impl LocksReq {
         self.refresh_inner()
     }
 
+    /// Returns the inner SDK request, building it first if required
+    pub fn inner(&mut self) -> Result<&LocksRequest> {
+        if self.inner.is_none() {
+            self.refresh_inner()?;
+        }
+        Ok(self.inner.as_ref().unwrap())
+    }
+
     /// Request a new Access Token
     pub fn refresh_token(&mut self) -> Result<()> {
         self.token = Some(request_token(
@@ -84,7 +94,45 @@ impl LocksReq {
     /// different Lock than the original request was pointed at (i.e. via `set_name`). This
     /// method will also refresh the access token, only if required.
     fn refresh_inner(&mut self) -> Result<()> {
-        let mut builder = LocksRequest::builder().token(self.token_or_refresh()?);
+        let token = self.token_or_refresh()?.to_owned();
+        self.inner = Some(self.build_request(&token)?);
+        Ok(())
+    }
+
+    /// Returns a function building a new `LocksRequest` for the lock, each with a new access
+    /// token (except the first, which reuses the current one if any). This is meant for SDK calls
+    /// which keep making requests for longer than an access token is valid (i.e.
+    /// `LeaderElection` or waiting to acquire a lock).
+    pub fn request_factory(&self) -> impl FnMut() -> SeaplaneResult<LocksRequest> + Send + 'static {
+        let mut this = Self {
+            api_key: self.api_key.clone(),
+            lock_id: self.lock_id.clone(),
+            name: self.name.clone(),
+            token: self.token.clone(),
+            inner: None,
+            identity_url: self.identity_url.clone(),
+            locks_url: self.locks_url.clone(),
+            insecure_urls: self.insecure_urls,
+            invalid_certs: self.invalid_certs,
+        };
+        move || {
+            let token = match this.token.take() {
+                Some(token) => token.token,
+                None => token_request(
+                    &this.api_key,
+                    this.identity_url.as_ref(),
+                    this.insecure_urls,
+                    this.invalid_certs,
+                )?
+                .access_token()?,
+            };
+            this.build_request(&token)
+        }
+    }
+
+    // Builds a `LocksRequest` for the lock using the given access token
+    fn build_request(&self, token: &str) -> SeaplaneResult<LocksRequest> {
+        let mut builder = LocksRequest::builder().token(token);
 
         #[cfg(feature = "allow_insecure_urls")]
         {
@@ -114,8 +162,7 @@ impl LocksReq {
             builder = builder.base_url(url);
         }
 
-        self.inner = Some(builder.build().map_err(CliError::from)?);
-        Ok(())
+        builder.build()
     }
 
     /// Retrieves the JWT access token, requesting a new one if required.
@@ -189,6 +236,21 @@ impl LocksReq {
     pub fn acquire(&mut self, ttl: u32, client_id: &str) -> Result<HeldLockModel> {
         maybe_retry!(self.acquire(ttl, client_id))
     }
+    /// Waiting may outlast the access token, so the SDK is handed a way to build requests with new
+    /// ones rather than retrying once here
+    pub fn acquire_blocking<F>(
+        &mut self,
+        ttl: u32,
+        client_id: &str,
+        timeout: Option<Duration>,
+        on_held: F,
+    ) -> Result<HeldLockModel>
+    where
+        F: FnMut(&LockInfoModel),
+    {
+        LocksRequest::acquire_blocking(self.request_factory(), ttl, client_id, timeout, on_held)
+            .map_err(CliError::from)
+    }
     pub fn release(&mut self) -> Result<()> { maybe_retry!(self.release()) }
     pub fn renew(&mut self, ttl: u32) -> Result<()> { maybe_retry!(self.renew(ttl)) }
     pub fn get_lock_info(&mut self) -> Result<LockInfoModel> { maybe_retry!(self.get_lock_info()) }
@@ -0,0 +1,142 @@
+use std::env;
+
+use seaplane::api::{
+    compute::v1::ImageReference,
+    registry::v2::{ImageManifest, RegistryRequest},
+};
+
+use crate::{
+    context::Ctx,
+    error::{CliError, CliErrorKind, Context, Result},
+    printer::Color,
+};
+
+/// Environment variable which may hold credentials for container image registries, as comma
+/// separated DOMAIN=USERNAME:PASSWORD entries
+pub const REGISTRY_CREDENTIALS_ENV: &str = "SEAPLANE_REGISTRY_CREDENTIALS";
+
+/// Wraps an SDK `RegistryRequest` where we apply the CLI's URL settings, and map errors
+/// appropriately.
+///
+/// Registries on the local machine are always reachable over plain HTTP (as is the case with
+/// `docker`) so that a local registry can be used without further configuration.
+///
+/// Credentials are only sent to the registry they were given for in the
+/// `SEAPLANE_REGISTRY_CREDENTIALS` environment variable, otherwise access is anonymous.
+#[derive(Debug)]
+pub struct RegistryReq {
+    reference: ImageReference,
+    inner: RegistryRequest,
+}
+
+impl RegistryReq {
+    #[cfg_attr(
+        not(any(feature = "allow_insecure_urls", feature = "allow_invalid_certs")),
+        allow(unused_mut, unused_variables)
+    )]
+    pub fn new(ctx: &Ctx, reference: &ImageReference) -> Result<Self> {
+        let mut builder = RegistryRequest::builder().reference(reference.clone());
+        if let Ok(entries) = env::var(REGISTRY_CREDENTIALS_ENV) {
+            if let Some((username, password)) = credentials(&entries, reference.domain())? {
+                builder = builder.credentials(username, password);
+            }
+        }
+
+        #[cfg(feature = "allow_insecure_urls")]
+        {
+            builder = builder.allow_http(ctx.insecure_urls || is_local(reference.domain()));
+        }
+        #[cfg(feature = "allow_invalid_certs")]
+        {
+            builder = builder.allow_invalid_certs(ctx.invalid_certs);
+        }
+
+        Ok(Self { reference: reference.clone(), inner: builder.build().map_err(CliError::from)? })
+    }
+
+    fn context<T>(&self, res: seaplane::error::Result<T>) -> Result<T> {
+        res.map_err(CliError::from)
+            .context("\timage: ")
+            .with_color_context(|| (Color::Yellow, format!("{}\n", self.reference)))
+    }
+
+    /// Returns the digest of the manifest the image reference currently points to
+    pub fn resolve_digest(&mut self) -> Result<String> {
+        let res = self.inner.resolve_digest();
+        self.context(res)
+    }
+
+    /// Returns all tags in the repository of the image reference
+    pub fn get_tags(&mut self) -> Result<Vec<String>> {
+        let res = self.inner.get_tags();
+        self.context(res)
+    }
+
+    /// Returns the manifest of the image reference, including the platforms it is available for
+    pub fn get_manifest(&mut self) -> Result<ImageManifest> {
+        let res = self.inner.get_manifest();
+        self.context(res)
+    }
+}
+
+/// Finds the username and password for the registry `domain` in `entries` of the form
+/// DOMAIN=USERNAME:PASSWORD separated by commas
+fn credentials<'a>(entries: &'a str, domain: &str) -> Result<Option<(&'a str, &'a str)>> {
+    for entry in entries.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        // The entry itself is never shown as it contains a password
+        let (registry, username, password) = entry
+            .split_once('=')
+            .and_then(|(registry, creds)| {
+                creds
+                    .split_once(':')
+                    .map(|(user, pass)| (registry, user, pass))
+            })
+            .ok_or_else(|| {
+                CliErrorKind::OneOff(format!(
+                    "invalid entry in {REGISTRY_CREDENTIALS_ENV}, expected DOMAIN=USERNAME:PASSWORD"
+                ))
+                .into_err()
+            })?;
+        if registry.eq_ignore_ascii_case(domain) {
+            return Ok(Some((username, password)));
+        }
+    }
+    Ok(None)
+}
+
+/// Whether the registry domain (which may include a port) is on the local machine
+fn is_local(domain: &str) -> bool {
+    let host = match domain.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => host,
+        _ => domain,
+    };
+    host == "localhost" || host == "127.0.0.1" || host == "[::1]"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_registries() {
+        assert!(is_local("localhost"));
+        assert!(is_local("localhost:5000"));
+        assert!(is_local("127.0.0.1:5000"));
+        assert!(is_local("[::1]:5000"));
+        assert!(is_local("[::1]"));
+        assert!(!is_local("registry.cplane.cloud"));
+        assert!(!is_local("localhost.example.com:5000"));
+    }
+
+    #[test]
+    fn registry_credentials() {
+        let entries = "registry.cplane.cloud=ahab:whale, localhost:5000=ishmael:pass:word";
+        assert_eq!(credentials(entries, "registry.cplane.cloud").unwrap(), Some(("ahab", "whale")));
+        assert_eq!(credentials(entries, "Registry.Cplane.Cloud").unwrap(), Some(("ahab", "whale")));
+        assert_eq!(credentials(entries, "localhost:5000").unwrap(), Some(("ishmael", "pass:word")));
+        assert_eq!(credentials(entries, "localhost").unwrap(), None);
+        assert_eq!(credentials("", "localhost").unwrap(), None);
+        assert!(credentials("registry.cplane.cloud=ahab", "localhost").is_err());
+        assert!(credentials("registry.cplane.cloud", "localhost").is_err());
+    }
+}
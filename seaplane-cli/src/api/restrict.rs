@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use reqwest::Url;
 use seaplane::{
     api::{
@@ -8,11 +10,11 @@ use seaplane::{
         shared::v1::RangeQueryContext,
         ApiErrorKind,
     },
-    error::SeaplaneError,
+    error::{Result as SeaplaneResult, SeaplaneError},
 };
 
 use crate::{
-    api::request_token,
+    api::{request_token, token_request},
     context::Ctx,
     error::{CliError, Result},
 };
@@ -93,7 +95,47 @@ impl RestrictReq {
     /// pointed at. This method will also refresh the access token, only if
     /// required.
     fn refresh_inner(&mut self) -> Result<()> {
-        let mut builder = RestrictRequest::builder().token(self.token_or_refresh()?);
+        let token = self.token_or_refresh()?.to_owned();
+        self.inner = Some(self.build_request(&token)?);
+        Ok(())
+    }
+
+    /// Returns a function building a new `RestrictRequest` for the restriction, each with a new
+    /// access token (except the first, which reuses the current one if any). This is meant for
+    /// SDK calls which keep making requests for longer than an access token is valid (i.e.
+    /// waiting for a restriction to be enforced).
+    pub fn request_factory(&self) -> impl FnMut() -> SeaplaneResult<RestrictRequest> {
+        let mut this = Self {
+            api_key: self.api_key.clone(),
+            api: self.api.clone(),
+            directory: self.directory.clone(),
+            from_api: self.from_api.clone(),
+            from_dir: self.from_dir.clone(),
+            token: self.token.clone(),
+            inner: None,
+            identity_url: self.identity_url.clone(),
+            metadata_url: self.metadata_url.clone(),
+            insecure_urls: self.insecure_urls,
+            invalid_certs: self.invalid_certs,
+        };
+        move || {
+            let token = match this.token.take() {
+                Some(token) => token.token,
+                None => token_request(
+                    &this.api_key,
+                    this.identity_url.as_ref(),
+                    this.insecure_urls,
+                    this.invalid_certs,
+                )?
+                .access_token()?,
+            };
+            this.build_request(&token)
+        }
+    }
+
+    // Builds a `RestrictRequest` for the restriction(s) using the given access token
+    fn build_request(&self, token: &str) -> SeaplaneResult<RestrictRequest> {
+        let mut builder = RestrictRequest::builder().token(token);
 
         #[cfg(feature = "allow_insecure_urls")]
         {
@@ -127,8 +169,7 @@ impl RestrictReq {
             [..] => {}
         };
 
-        self.inner = Some(builder.build().map_err(CliError::from)?);
-        Ok(())
+        builder.build()
     }
 
     /// Retrieves the JWT access token, requesting a new one if required.
@@ -145,6 +186,12 @@ impl RestrictReq {
     pub fn get_restriction(&mut self) -> Result<Restriction> {
         maybe_retry!(self.get_restriction())
     }
+    /// Waiting may outlast the access token, so the SDK is handed a way to build requests with new
+    /// ones rather than retrying once here
+    pub fn wait_until_enforced(&mut self, timeout: Option<Duration>) -> Result<Restriction> {
+        RestrictRequest::wait_until_enforced(self.request_factory(), timeout)
+            .map_err(CliError::from)
+    }
 
     pub fn set_restriction(&mut self, details: RestrictionDetails) -> Result<()> {
         maybe_retry_cloned!(self.set_restriction(details))
@@ -108,6 +108,7 @@ impl Seaplane {
             .subcommand(SeaplaneAccount::command())
             .subcommand(SeaplaneFlight::command())
             .subcommand(SeaplaneFormation::command())
+            .subcommand(SeaplaneImage::command())
             .subcommand(SeaplaneInit::command())
             .subcommand(SeaplaneLicense::command())
             .subcommand(SeaplaneMetadata::command())
@@ -117,9 +118,7 @@ impl Seaplane {
 
         #[cfg(feature = "unstable")]
         {
-            app = app
-                .subcommand(SeaplaneConfig::command())
-                .subcommand(SeaplaneImage::command());
+            app = app.subcommand(SeaplaneConfig::command());
         }
 
         #[cfg(feature = "ui_tests")]
@@ -195,6 +194,7 @@ impl CliCommand for Seaplane {
             Some(("account", m)) => Some((Box::new(SeaplaneAccount), m)),
             Some(("flight", m)) => Some((Box::new(SeaplaneFlight), m)),
             Some(("formation", m)) => Some((Box::new(SeaplaneFormation), m)),
+            Some(("image", m)) => Some((Box::new(SeaplaneImage), m)),
             Some(("init", m)) => Some((Box::new(SeaplaneInit), m)),
             Some(("metadata", m)) => Some((Box::new(SeaplaneMetadata), m)),
             Some(("locks", m)) => Some((Box::new(SeaplaneLocks), m)),
@@ -202,8 +202,6 @@ impl CliCommand for Seaplane {
             Some(("shell-completion", m)) => Some((Box::new(SeaplaneShellCompletion), m)),
             Some(("license", m)) => Some((Box::new(SeaplaneLicense), m)),
             #[cfg(feature = "unstable")]
-            Some(("image", m)) => Some((Box::new(SeaplaneImage), m)),
-            #[cfg(feature = "unstable")]
             Some(("config", m)) => Some((Box::new(SeaplaneConfig), m)),
             _ => None, // TODO: handle external plugins
         }
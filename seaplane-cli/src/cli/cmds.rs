@@ -4,24 +4,24 @@ mod completion;
 mod config;
 pub mod flight;
 pub mod formation;
-#[cfg(feature = "unstable")]
-mod image;
+pub mod image;
 mod init;
 mod license;
 pub mod locks;
 pub mod metadata;
 pub mod restrict;
 
+#[cfg(feature = "unstable")]
+pub use self::config::SeaplaneConfig;
 pub use self::{
     account::SeaplaneAccount,
     completion::SeaplaneShellCompletion,
     flight::SeaplaneFlight,
     formation::{Provider, Region, SeaplaneFormation},
+    image::SeaplaneImage,
     init::SeaplaneInit,
     license::SeaplaneLicense,
     locks::SeaplaneLocks,
     metadata::SeaplaneMetadata,
     restrict::SeaplaneRestrict,
 };
-#[cfg(feature = "unstable")]
-pub use self::{config::SeaplaneConfig, image::SeaplaneImage};
@@ -12,6 +12,7 @@ use crate::{
     },
     context::{Ctx, FlightCtx},
     error::Result,
+    ops::image::{check_local_flight, pin_local_flight},
 };
 
 #[derive(Copy, Clone, Debug)]
@@ -32,6 +33,9 @@ impl SeaplaneFlightEdit {
                     .validator(validator),
             )
             .arg(arg!(--exact - ('x')).help("The given name or ID must be an exact match"))
+            .arg(arg!(--("pin-digest")).help("Resolve the image tag to the digest it currently points to and store the pinned image reference"))
+            .arg(arg!(--("check-arch")).help("Fail if the image is not published for every declared architecture"))
+            .arg(arg!(--("infer-arch")).conflicts_with("architecture").help("Replace the declared architectures with those the image is published for"))
             .args(common::args(false))
     }
 }
@@ -55,12 +59,21 @@ impl CliCommand for SeaplaneFlightEdit {
 
         // Now we just edit the newly copied Flight to match the given CLI params...
         // name_id cannot be None in `flight edit`
-        if let Err(e) = flights.update_flight(
+        let id = match flights.update_flight(
             ctx.args.name_id.as_ref().unwrap(),
             ctx.args.exact,
             ctx.flight_ctx.get_or_init(),
         ) {
-            return wrap_cli_context(e, ctx.args.exact, false);
+            Ok(id) => id,
+            Err(e) => return wrap_cli_context(e, ctx.args.exact, false),
+        };
+
+        if ctx.flight_ctx.get_or_init().pin_digest {
+            pin_local_flight(ctx, &id)?;
+        }
+        let infer_arch = ctx.flight_ctx.get_or_init().infer_arch;
+        if infer_arch || ctx.flight_ctx.get_or_init().check_arch {
+            check_local_flight(ctx, &id, infer_arch)?;
         }
 
         ctx.persist_flights()?;
@@ -75,8 +88,12 @@ impl CliCommand for SeaplaneFlightEdit {
     fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
         // clap will not let "source" be None
         ctx.args.name_id = matches.get_one::<String>("name_id").map(ToOwned::to_owned);
-        ctx.flight_ctx
-            .init(FlightCtx::from_flight_common(&SeaplaneFlightCommonArgMatches(matches), ctx)?);
+        let mut fctx =
+            FlightCtx::from_flight_common(&SeaplaneFlightCommonArgMatches(matches), ctx)?;
+        fctx.pin_digest = matches.contains_id("pin-digest");
+        fctx.check_arch = matches.contains_id("check-arch");
+        fctx.infer_arch = matches.contains_id("infer-arch");
+        ctx.flight_ctx.init(fctx);
         Ok(())
     }
 }
@@ -13,7 +13,10 @@ use crate::{
     },
     context::FlightCtx,
     error::{CliErrorKind, Context, Result},
-    ops::flight::Flight,
+    ops::{
+        flight::Flight,
+        image::{check_local_flight, pin_local_flight},
+    },
     printer::Color,
     Ctx,
 };
@@ -31,6 +34,9 @@ impl SeaplaneFlightPlan {
             .about("Make a new local Flight Plan that Formations can include and reference")
             .arg(arg!(--force - ('f')).help("Override any existing Flights Plans with the same NAME"))
             .arg(arg!(--fetch|sync|synchronize - ('F')).help("Fetch and synchronize remote Formation Instances (which reference Flight Plans) prior to creating this plan to check for conflicts (by default only local plans are checked)"))
+            .arg(arg!(--("pin-digest")).help("Resolve the image tag to the digest it currently points to and store the pinned image reference"))
+            .arg(arg!(--("check-arch")).help("Fail if the image is not published for every declared architecture"))
+            .arg(arg!(--("infer-arch")).conflicts_with("architecture").help("Replace the declared architectures with those the image is published for"))
             .args(common::args(true))
     }
 }
@@ -90,6 +96,14 @@ impl CliCommand for SeaplaneFlightPlan {
         let id = new_flight.id;
         ctx.db.flights.add_flight(new_flight);
 
+        if ctx.flight_ctx.get_or_init().pin_digest {
+            pin_local_flight(ctx, &id)?;
+        }
+        let infer_arch = ctx.flight_ctx.get_or_init().infer_arch;
+        if infer_arch || ctx.flight_ctx.get_or_init().check_arch {
+            check_local_flight(ctx, &id, infer_arch)?;
+        }
+
         ctx.persist_flights()?;
 
         cli_print!("Successfully created Flight Plan '");
@@ -102,8 +116,12 @@ impl CliCommand for SeaplaneFlightPlan {
     }
 
     fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
-        ctx.flight_ctx
-            .init(FlightCtx::from_flight_common(&SeaplaneFlightCommonArgMatches(matches), ctx)?);
+        let mut fctx =
+            FlightCtx::from_flight_common(&SeaplaneFlightCommonArgMatches(matches), ctx)?;
+        fctx.pin_digest = matches.contains_id("pin-digest");
+        fctx.check_arch = matches.contains_id("check-arch");
+        fctx.infer_arch = matches.contains_id("infer-arch");
+        ctx.flight_ctx.init(fctx);
         ctx.args.force = matches.contains_id("force");
         ctx.args.fetch = matches.contains_id("fetch");
         Ok(())
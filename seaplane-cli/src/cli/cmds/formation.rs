@@ -1,34 +1,48 @@
+mod clone;
 pub mod common;
 #[cfg(feature = "unstable")]
 mod configuration;
 #[cfg(feature = "unstable")]
 mod container_stats;
 mod delete;
+mod edit;
+mod endpoint;
 mod fetch;
+mod history;
 mod land;
 mod launch;
 mod list;
 #[cfg(feature = "unstable")]
 mod load_balance;
+mod placement;
 mod plan;
+mod rollback;
+#[cfg(feature = "unstable")]
+mod rollout;
 mod status;
 #[cfg(feature = "unstable")]
 mod template;
+mod wait;
 
 use clap::{ArgMatches, Command};
 pub use common::{Provider, Region};
 pub use plan::SeaplaneFormationPlanArgMatches;
 
+pub use self::{
+    clone::SeaplaneFormationClone, delete::SeaplaneFormationDelete, edit::SeaplaneFormationEdit,
+    endpoint::SeaplaneFormationEndpoint, fetch::SeaplaneFormationFetch,
+    history::SeaplaneFormationHistory, land::SeaplaneFormationLand,
+    launch::SeaplaneFormationLaunch, list::SeaplaneFormationList,
+    placement::SeaplaneFormationPlacement, plan::SeaplaneFormationPlan,
+    rollback::SeaplaneFormationRollback, status::SeaplaneFormationStatus,
+    wait::SeaplaneFormationWait,
+};
 #[cfg(feature = "unstable")]
 use self::{
     configuration::SeaplaneFormationConfiguration,
     container_stats::SeaplaneFormationContainerStatistics,
-    load_balance::SeaplaneFormationLoadBalance, template::SeaplaneFormationTemplate,
-};
-pub use self::{
-    delete::SeaplaneFormationDelete, fetch::SeaplaneFormationFetch, land::SeaplaneFormationLand,
-    launch::SeaplaneFormationLaunch, list::SeaplaneFormationList, plan::SeaplaneFormationPlan,
-    status::SeaplaneFormationStatus,
+    load_balance::SeaplaneFormationLoadBalance, rollout::SeaplaneFormationRollout,
+    template::SeaplaneFormationTemplate,
 };
 use crate::{cli::CliCommand, error::Result, Ctx};
 
@@ -45,12 +59,19 @@ impl SeaplaneFormation {
             .subcommand_required(true)
             .arg_required_else_help(true)
             .subcommand(SeaplaneFormationPlan::command())
+            .subcommand(SeaplaneFormationClone::command())
             .subcommand(SeaplaneFormationDelete::command())
+            .subcommand(SeaplaneFormationEdit::command())
+            .subcommand(SeaplaneFormationEndpoint::command())
             .subcommand(SeaplaneFormationFetch::command())
+            .subcommand(SeaplaneFormationHistory::command())
             .subcommand(SeaplaneFormationLand::command())
             .subcommand(SeaplaneFormationLaunch::command())
             .subcommand(SeaplaneFormationList::command())
-            .subcommand(SeaplaneFormationStatus::command());
+            .subcommand(SeaplaneFormationPlacement::command())
+            .subcommand(SeaplaneFormationRollback::command())
+            .subcommand(SeaplaneFormationStatus::command())
+            .subcommand(SeaplaneFormationWait::command());
 
         #[cfg(feature = "unstable")]
         {
@@ -58,6 +79,7 @@ impl SeaplaneFormation {
                 .subcommand(SeaplaneFormationConfiguration::command())
                 .subcommand(SeaplaneFormationContainerStatistics::command())
                 .subcommand(SeaplaneFormationLoadBalance::command())
+                .subcommand(SeaplaneFormationRollout::command())
                 .subcommand(SeaplaneFormationTemplate::command())
         }
 
@@ -72,12 +94,19 @@ impl CliCommand for SeaplaneFormation {
     ) -> Option<(Box<dyn CliCommand>, &'a ArgMatches)> {
         match &matches.subcommand() {
             Some(("plan", m)) => Some((Box::new(SeaplaneFormationPlan), m)),
+            Some(("clone", m)) => Some((Box::new(SeaplaneFormationClone), m)),
             Some(("delete", m)) => Some((Box::new(SeaplaneFormationDelete), m)),
+            Some(("edit", m)) => Some((Box::new(SeaplaneFormationEdit), m)),
+            Some(("endpoint", m)) => Some((Box::new(SeaplaneFormationEndpoint), m)),
             Some(("fetch-remote", m)) => Some((Box::new(SeaplaneFormationFetch), m)),
+            Some(("history", m)) => Some((Box::new(SeaplaneFormationHistory), m)),
             Some(("land", m)) => Some((Box::new(SeaplaneFormationLand), m)),
             Some(("launch", m)) => Some((Box::new(SeaplaneFormationLaunch), m)),
             Some(("list", m)) => Some((Box::new(SeaplaneFormationList), m)),
+            Some(("placement", m)) => Some((Box::new(SeaplaneFormationPlacement), m)),
+            Some(("rollback", m)) => Some((Box::new(SeaplaneFormationRollback), m)),
             Some(("status", m)) => Some((Box::new(SeaplaneFormationStatus), m)),
+            Some(("wait", m)) => Some((Box::new(SeaplaneFormationWait), m)),
             #[cfg(feature = "unstable")]
             Some(("configuration", m)) => Some((Box::new(SeaplaneFormationConfiguration), m)),
             #[cfg(feature = "unstable")]
@@ -87,6 +116,8 @@ impl CliCommand for SeaplaneFormation {
             #[cfg(feature = "unstable")]
             Some(("load-balance", m)) => Some((Box::new(SeaplaneFormationLoadBalance), m)),
             #[cfg(feature = "unstable")]
+            Some(("rollout", m)) => Some((Box::new(SeaplaneFormationRollout), m)),
+            #[cfg(feature = "unstable")]
             Some(("template", m)) => Some((Box::new(SeaplaneFormationTemplate), m)),
             _ => None,
         }
@@ -0,0 +1,173 @@
+use clap::{ArgMatches, Command};
+use uuid::Uuid;
+
+use crate::{
+    api::FormationsReq,
+    cli::{
+        errors,
+        validator::{validate_formation_name, validate_name_id},
+        CliCommand,
+    },
+    context::Ctx,
+    error::{CliErrorKind, Context, Result},
+    ops::formation::{Formation, FormationConfiguration},
+    printer::{Color, Pb},
+};
+
+static LONG_ABOUT: &str = "Clone a Formation into a new Formation with a different name
+
+By default the remote Formation Instance SOURCE is cloned by the Seaplane Cloud into a new remote
+Formation Instance DEST, which is recorded in the local Formation Plans along with its
+configurations. The new Formation Instance is Grounded unless '--active' is used.
+
+With '--local' the local Formation Plan SOURCE is duplicated into a new local Formation Plan DEST
+instead, without contacting the Seaplane Cloud. The copied configurations receive new local IDs
+and can be launched independently of the original.
+
+Cloning is useful for short lived copies of a Formation, such as per-branch preview
+environments.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationClone;
+
+impl SeaplaneFormationClone {
+    pub fn command() -> Command<'static> {
+        let validator = |s: &str| validate_name_id(validate_formation_name, s);
+        Command::new("clone")
+            .visible_alias("copy")
+            .about("Clone a Formation into a new Formation with a different name")
+            .long_about(LONG_ABOUT)
+            .arg(
+                arg!(source =["SOURCE"] required)
+                    .validator(validator)
+                    .help("The name of the Formation to clone (or the name or ID of a local Formation Plan with '--local')"),
+            )
+            .arg(
+                arg!(dest =["DEST"] required)
+                    .validator(validate_formation_name)
+                    .help("The name of the new Formation"),
+            )
+            .arg(
+                arg!(--active)
+                    .help("Set the configurations of the new remote Formation Instance to active"),
+            )
+            .arg(
+                arg!(--local)
+                    .conflicts_with("active")
+                    .help("Duplicate a local Formation Plan without contacting the Seaplane Cloud"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneFormationClone {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let src = ctx.args.name_id.as_ref().unwrap().clone();
+        let fctx = ctx.formation_ctx.get_or_init();
+        let dest = fctx.name_id.clone();
+        let (local, active) = (fctx.local, !fctx.grounded);
+
+        if ctx.db.formations.contains_name(&dest) {
+            return Err(CliErrorKind::DuplicateName(dest)
+                .into_err()
+                .context("(hint: choose a different name for the new Formation)\n"));
+        }
+
+        if local {
+            let indices = ctx.db.formations.formation_indices_of_matches(&src);
+            match indices.len() {
+                0 => errors::no_matching_item(src.clone(), false, false)?,
+                1 => (),
+                _ => errors::ambiguous_item(src.clone(), false)?,
+            }
+
+            // re unwrap: the index came from Formations so it has to be valid
+            let id = ctx
+                .db
+                .formations
+                .clone_formation(indices[0], &dest)
+                .unwrap();
+            ctx.persist_formations()?;
+
+            cli_print!("Successfully cloned local Formation Plan '");
+            cli_print!(@Green, "{src}");
+            cli_print!("' to '");
+            cli_print!(@Green, "{dest}");
+            cli_print!("' with ID '");
+            cli_print!(@Green, "{}", &id.to_string()[..8]);
+            cli_println!("'");
+            return Ok(());
+        }
+
+        let pb = Pb::new(ctx);
+        pb.set_message(format!("Cloning Formation Instance {src}..."));
+        let mut req = FormationsReq::new_delay_token(ctx)?;
+        req.set_name(&dest)?;
+        let cfg_uuids = req
+            .clone_from(&src, active)
+            .context("Context: failed to clone Formation Instance\n\tsource: ")
+            .with_color_context(|| (Color::Yellow, format!("{src}\n")))?;
+
+        // Record the new Formation and its configurations just like a fetch would
+        pb.set_message(format!("Syncing Formation {dest} Configurations..."));
+        let mut formation = Formation::new(&dest);
+        for uuid in &cfg_uuids {
+            let cfg_model = req
+                .get_configuration(*uuid)
+                .context("Context: failed to retrieve Formation Configuration\n\tUUID: ")
+                .with_color_context(|| (Color::Yellow, format!("{uuid}\n")))?;
+            for flight in cfg_model.flights() {
+                ctx.db.flights.update_or_create_flight(flight);
+            }
+
+            let cfg = FormationConfiguration::with_uuid(*uuid, cfg_model);
+            formation.local.insert(cfg.id);
+            if active {
+                formation.in_air.insert(cfg.id);
+            } else {
+                formation.grounded.insert(cfg.id);
+            }
+            ctx.db.formations.configurations.push(cfg);
+        }
+        ctx.db.formations.update_or_create_formation(formation);
+        if active {
+            let active_cfgs: Vec<(Uuid, f32)> = cfg_uuids.iter().map(|uuid| (*uuid, 1.0)).collect();
+            ctx.db
+                .formations
+                .record_active_configurations(&dest, &active_cfgs);
+        }
+        pb.finish_and_clear();
+
+        ctx.persist_flights()?;
+        ctx.persist_formations()?;
+
+        cli_print!("Successfully cloned remote Formation Instance '");
+        cli_print!(@Green, "{src}");
+        cli_print!("' to '");
+        cli_print!(@Green, "{dest}");
+        if cfg_uuids.is_empty() {
+            cli_println!("'");
+        } else {
+            cli_println!("' with Configuration UUIDs:");
+            for uuid in &cfg_uuids {
+                cli_println!(@Green, "{uuid}");
+            }
+        }
+        if !active {
+            cli_print!("(hint: the new Formation Instance is Grounded, start it with '");
+            cli_print!(@Green, "seaplane formation launch {dest}");
+            cli_println!("')");
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.args.name_id = matches.get_one::<String>("source").map(ToOwned::to_owned);
+        let fctx = ctx.formation_ctx.get_mut_or_init();
+        // clap will not let "dest" be None
+        fctx.name_id = matches.get_one::<String>("dest").unwrap().to_owned();
+        fctx.local = matches.contains_id("local");
+        fctx.grounded = !matches.contains_id("active");
+        Ok(())
+    }
+}
@@ -0,0 +1,306 @@
+use std::{env, fs, io::Write, process};
+
+use clap::{value_parser, ArgMatches, Command, ValueEnum};
+use const_format::concatcp;
+use seaplane::api::compute::v1::FormationConfiguration as FormationConfigurationModel;
+
+use crate::{
+    cli::{
+        cmds::formation::{
+            common::{self, Provider, Region},
+            plan::create_included_flights,
+            SeaplaneFormationLaunch, SeaplaneFormationPlanArgMatches,
+        },
+        errors,
+        specs::{FLIGHT_SPEC, REGION_SPEC},
+        validator::{
+            validate_endpoint_src, validate_flight_name, validate_formation_name, validate_name_id,
+        },
+        CliCommand,
+    },
+    context::Ctx,
+    error::{CliError, CliErrorKind, Context, Result},
+    ops::formation::{validate_configuration, EndpointSrc, FormationConfiguration},
+    printer::Color,
+};
+
+static LONG_ABOUT: &str = "Edit a local Formation Plan
+
+The Formation Plan's configuration is copied, the requested items are removed from the copy, and
+then the requested items are added to it. The result is saved as a new local configuration of the
+Formation Plan which will be used by the next 'seaplane formation launch'. The previous
+configuration is kept, and any remote Formation Instances using it are left unchanged.
+
+Flights of the configuration are taken from the current local Flight Plans of the same name, so
+editing a Formation Plan also picks up any changes made with 'seaplane flight edit'. Flights without
+a local Flight Plan keep their current definition.
+
+With '--editor' the resulting configuration is opened as JSON in $VISUAL or $EDITOR (or 'vi' if
+neither is set) before it is saved. The edited configuration is validated and nothing is saved if
+it is invalid.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationEdit;
+
+impl SeaplaneFormationEdit {
+    pub fn command() -> Command<'static> {
+        let validator = |s: &str| validate_name_id(validate_formation_name, s);
+        let flight_validator = |s: &str| validate_name_id(validate_flight_name, s);
+        let hide = cfg!(not(feature = "unstable"));
+
+        Command::new("edit")
+            .after_help(concatcp!(FLIGHT_SPEC, "\n\n", REGION_SPEC))
+            .override_usage("seaplane formation edit <NAME|ID> [OPTIONS]")
+            .about("Edit a local Formation Plan")
+            .long_about(LONG_ABOUT)
+            .arg(
+                arg!(name_id required =["NAME|ID"])
+                    .help("The name or ID of the Formation Plan to edit")
+                    .validator(validator),
+            )
+            // The name of a Formation cannot be changed, it is how remote instances are found
+            .args(
+                common::args()
+                    .into_iter()
+                    .filter(|a| a.get_id() != "name_id")
+                    .map(|a| {
+                        if a.get_id() == "include-flight-plan" {
+                            a.required(false)
+                        } else {
+                            a
+                        }
+                    }),
+            )
+            .arg(arg!(--("remove-flight-plan")|("remove-flight-plans") =["NAME|ID"]...)
+                .help("Remove a Flight Plan from this Formation (supports SEMICOLON (';') separated list, or multiple uses)")
+                .value_delimiter(';')
+                .validator(flight_validator))
+            .arg(arg!(--("remove-provider")|("remove-providers") =["PROVIDER"]... ignore_case)
+                .help("Remove a provider from those this Formation's Flights are permitted to run on (supports comma separated list, or multiple uses)")
+                .value_parser(value_parser!(Provider)))
+            .arg(arg!(--("remove-exclude-provider")|("remove-exclude-providers") =["PROVIDER"]... ignore_case)
+                .help("Remove a provider from those this Formation's Flights are *NOT* permitted to run on (supports comma separated list, or multiple uses)")
+                .value_parser(value_parser!(Provider)))
+            .arg(arg!(--("remove-region")|("remove-regions") =["REGION"]... ignore_case)
+                .help("Remove a region from those this Formation's Flights are allowed to run in (supports comma separated list, or multiple uses) (See REGION SPEC below)")
+                .value_parser(value_parser!(Region)))
+            .arg(arg!(--("remove-exclude-region")|("remove-exclude-regions") =["REGION"]... ignore_case)
+                .help("Remove a region from those this Formation's Flights are *NOT* allowed to run in (supports comma separated list, or multiple uses) (See REGION SPEC below)")
+                .value_parser(value_parser!(Region)))
+            .arg(arg!(--("remove-public-endpoint")|("remove-public-endpoints") =["ROUTE"]...)
+                .help("Remove the public endpoint with the given route (supports comma separated list, or multiple uses)")
+                .validator(validate_endpoint_src))
+            .arg(arg!(--("remove-flight-endpoint")|("remove-flight-endpoints") =["PROTO:TARGET"]...)
+                .help("Remove the Flight endpoint with the given source (supports comma separated list, or multiple uses)")
+                .validator(validate_endpoint_src))
+            .arg(arg!(--("remove-affinity")|("remove-affinities") =["NAME|ID"]...)
+                .help("Remove a Formation from those this Formation has an affinity for (supports comma separated list, or multiple uses)")
+                .validator(validator)
+                .hide(hide)) // Hidden on feature = unstable
+            .arg(arg!(--("remove-connection")|("remove-connections") =["NAME|ID"]...)
+                .help("Remove a Formation from those this Formation is connected to (supports comma separated list, or multiple uses)")
+                .validator(validator)
+                .hide(hide)) // Hidden on feature = unstable
+            .arg(arg!(--("remove-formation-endpoint")|("remove-formation-endpoints") =["PROTO:TARGET"]...)
+                .help("Remove the Formation endpoint with the given source (supports comma separated list, or multiple uses)")
+                .validator(validate_endpoint_src)
+                .hide(hide)) // Hidden on feature = unstable
+            .arg(arg!(--editor - ('e'))
+                .help("Open the resulting configuration in $VISUAL or $EDITOR for further changes before saving it"))
+    }
+}
+
+impl CliCommand for SeaplaneFormationEdit {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        // name_id cannot be None in `formation edit`
+        let name = ctx.args.name_id.as_ref().unwrap().clone();
+        let indices = ctx.db.formations.formation_indices_of_matches(&name);
+        match indices.len() {
+            0 => errors::no_matching_item(name.clone(), false, false)?,
+            1 => (),
+            _ => errors::ambiguous_item(name.clone(), false)?,
+        }
+        let idx = indices[0];
+
+        // re unwrap: the index came from Formations so it has to be valid
+        let formation = ctx.db.formations.get_formation(idx).unwrap();
+        let formation_name = formation.name.clone().unwrap_or(name);
+
+        // The configuration being edited is the one the next launch would use
+        let old_id = ctx.db.formations.editable_configuration(idx)?;
+        // re unwrap: the ID came from our own Formation
+        let base = ctx
+            .db
+            .formations
+            .get_configuration(&old_id)
+            .unwrap()
+            .model
+            .clone();
+
+        let formation_ctx = ctx.formation_ctx.get_or_init();
+        let mut model = formation_ctx.edited_configuration_model(ctx, &base)?;
+        if formation_ctx.editor {
+            model = edit_in_editor(&model)?;
+        }
+        validate_configuration(&model).context("(hint: the Formation Plan was not changed)\n")?;
+
+        if model == base {
+            cli_print!("Formation Plan '");
+            cli_print!(@Green, "{formation_name}");
+            cli_println!("' is unchanged");
+            return Ok(());
+        }
+
+        if ctx.db.needs_persist {
+            // Any flights we created in update_ctx can now be persisted
+            ctx.persist_flights()?;
+            ctx.db.needs_persist = false;
+        }
+
+        let new_id = ctx.db.formations.replace_local_configuration(
+            idx,
+            &old_id,
+            FormationConfiguration::new(model),
+        );
+
+        ctx.persist_formations()?;
+
+        cli_print!("Successfully edited Formation Plan '");
+        cli_print!(@Green, "{formation_name}");
+        cli_print!("' with new Configuration ID '");
+        cli_print!(@Green, "{}", &new_id.to_string()[..8]);
+        cli_println!("'");
+
+        // Equivalent of doing 'seaplane formation launch NAME --exact'
+        if formation_ctx.launch || formation_ctx.grounded {
+            ctx.args.name_id = Some(formation_name);
+            ctx.args.exact = true;
+            ctx.args.fetch = false;
+            SeaplaneFormationLaunch.run(ctx)?;
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        // clap will not let "name_id" be None
+        ctx.args.name_id = matches.get_one::<String>("name_id").map(ToOwned::to_owned);
+
+        // Any flights we created will be persisted during `run` as we could still hit an error
+        // such as an invalid configuration and would need to roll them back
+        create_included_flights(matches, ctx)?;
+
+        let fctx = ctx.formation_ctx.get_mut_or_init();
+        fctx.update_from_formation_plan(
+            &SeaplaneFormationPlanArgMatches(matches),
+            &ctx.db.flights,
+        )?;
+        fctx.editor = matches.contains_id("editor");
+
+        let removals = &mut fctx.cfg_removals;
+        removals.flights = matches
+            .get_many::<String>("remove-flight-plan")
+            .unwrap_or_default()
+            .map(|flight| {
+                ctx.db
+                    .flights
+                    .find_name_or_partial_id(flight)
+                    .map(|f| f.model.name().to_owned())
+                    .unwrap_or_else(|| flight.to_owned())
+            })
+            .collect();
+        removals.affinities = matches
+            .get_many::<String>("remove-affinity")
+            .unwrap_or_default()
+            .map(ToOwned::to_owned)
+            .collect();
+        removals.connections = matches
+            .get_many::<String>("remove-connection")
+            .unwrap_or_default()
+            .map(ToOwned::to_owned)
+            .collect();
+        removals.providers_allowed = removed_values::<Provider>(matches, "remove-provider")
+            .iter()
+            .filter_map(Provider::into_model)
+            .collect();
+        removals.providers_denied = removed_values::<Provider>(matches, "remove-exclude-provider")
+            .iter()
+            .filter_map(Provider::into_model)
+            .collect();
+        removals.regions_allowed = removed_values::<Region>(matches, "remove-region")
+            .iter()
+            .filter_map(Region::into_model)
+            .collect();
+        removals.regions_denied = removed_values::<Region>(matches, "remove-exclude-region")
+            .iter()
+            .filter_map(Region::into_model)
+            .collect();
+        removals.public_endpoints = removed_endpoints(matches, "remove-public-endpoint");
+        removals.flight_endpoints = removed_endpoints(matches, "remove-flight-endpoint");
+        removals.formation_endpoints = removed_endpoints(matches, "remove-formation-endpoint");
+
+        Ok(())
+    }
+}
+
+/// Returns the Providers or Regions to remove, where 'all' expands to every known value
+fn removed_values<T>(matches: &ArgMatches, id: &str) -> Vec<T>
+where
+    T: ValueEnum + Copy + Send + Sync + 'static,
+{
+    let values: Vec<T> = matches
+        .get_many::<T>(id)
+        .unwrap_or_default()
+        .copied()
+        .collect();
+    if values
+        .iter()
+        .any(|v| v.to_possible_value().map(|pv| pv.get_name()) == Some("all"))
+    {
+        T::value_variants().to_vec()
+    } else {
+        values
+    }
+}
+
+fn removed_endpoints(matches: &ArgMatches, id: &str) -> Vec<EndpointSrc> {
+    matches
+        .get_many::<String>(id)
+        .unwrap_or_default()
+        .filter_map(|val| val.parse::<EndpointSrc>().ok())
+        .collect()
+}
+
+/// Opens the configuration as JSON in the user's editor and returns the edited configuration
+fn edit_in_editor(model: &FormationConfigurationModel) -> Result<FormationConfigurationModel> {
+    let mut file = tempfile::Builder::new()
+        .prefix("seaplane-formation-")
+        .suffix(".json")
+        .tempfile()?;
+    serde_json::to_writer_pretty(&mut file, model)?;
+    writeln!(file)?;
+    file.flush()?;
+
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".into());
+    // Allow editors that need arguments such as 'code --wait'
+    let mut argv = editor.split_whitespace();
+    let status = process::Command::new(argv.next().unwrap_or("vi"))
+        .args(argv)
+        .arg(file.path())
+        .status()
+        .map_err(CliError::from)
+        .context("Context: failed to run the editor\n\teditor: ")
+        .with_color_context(|| (Color::Yellow, format!("{editor}\n")))?;
+    if !status.success() {
+        return Err(CliErrorKind::ChildFailed(status.code())
+            .into_err()
+            .context("(hint: the Formation Plan was not changed)\n"));
+    }
+
+    let json = fs::read_to_string(file.path())?;
+    serde_json::from_str(&json)
+        .map_err(CliError::from)
+        .context("(hint: the Formation Plan was not changed)\n")
+}
@@ -0,0 +1,107 @@
+mod add;
+mod list;
+mod remove;
+
+use clap::{Arg, ArgMatches, Command};
+
+pub use self::{
+    add::SeaplaneFormationEndpointAdd, list::SeaplaneFormationEndpointList,
+    remove::SeaplaneFormationEndpointRemove,
+};
+use crate::{
+    cli::{
+        errors,
+        validator::{validate_formation_name, validate_name_id},
+        CliCommand,
+    },
+    context::Ctx,
+    error::Result,
+    ops::{
+        formation::{EndpointKind, FormationConfiguration},
+        Id,
+    },
+};
+
+static LONG_ABOUT: &str = "Operate on the endpoints of local Formation Plans
+
+Endpoints come in three kinds. Public endpoints are exposed on the Formation's domain to the
+public internet, formation endpoints are exposed privately to other Formations, and flight
+endpoints are exposed privately to the Flights of the same Formation.
+
+Changing the endpoints saves a new local configuration of the Formation Plan which will be used by
+the next 'seaplane formation launch', just like 'seaplane formation edit'.";
+
+pub fn formation() -> Arg<'static> {
+    let validator = |s: &str| validate_name_id(validate_formation_name, s);
+    arg!(name_id =["NAME|ID"] required)
+        .help("The name or ID of the Formation Plan")
+        .validator(validator)
+}
+
+/// Returns the index of the single local Formation matching `ctx.args.name_id`
+fn formation_index(ctx: &Ctx) -> Result<usize> {
+    // name_id cannot be None in `formation endpoint` commands
+    let name = ctx.args.name_id.as_ref().unwrap();
+    let indices = ctx.db.formations.formation_indices_of_matches(name);
+    match indices.len() {
+        0 => errors::no_matching_item(name.to_owned(), false, false)?,
+        1 => (),
+        _ => errors::ambiguous_item(name.to_owned(), false)?,
+    }
+    Ok(indices[0])
+}
+
+/// Returns the index of the Formation along with its configuration the endpoints apply to
+fn editable_configuration(ctx: &Ctx) -> Result<(usize, &FormationConfiguration)> {
+    let idx = formation_index(ctx)?;
+    let id = ctx.db.formations.editable_configuration(idx)?;
+    // re unwrap: the ID came from our own Formation
+    Ok((idx, ctx.db.formations.get_configuration(&id).unwrap()))
+}
+
+fn print_endpoint(action: &str, kind: EndpointKind, endpoint: impl std::fmt::Display) {
+    cli_print!("{action} {kind} endpoint '");
+    cli_print!(@Green, "{endpoint}");
+    cli_println!("'");
+}
+
+fn print_updated(ctx: &Ctx, idx: usize, id: &Id) {
+    // re unwrap: the index came from Formations so it has to be valid
+    let formation = ctx.db.formations.get_formation(idx).unwrap();
+    cli_print!("Successfully updated Formation Plan '");
+    cli_print!(@Green, "{}", formation.name.as_deref().unwrap_or_default());
+    cli_print!("' with new Configuration ID '");
+    cli_print!(@Green, "{}", &id.to_string()[..8]);
+    cli_println!("'");
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationEndpoint;
+
+impl SeaplaneFormationEndpoint {
+    pub fn command() -> Command<'static> {
+        Command::new("endpoint")
+            .visible_alias("endpoints")
+            .about("Operate on the endpoints of local Formation Plans")
+            .long_about(LONG_ABOUT)
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .subcommand(SeaplaneFormationEndpointList::command())
+            .subcommand(SeaplaneFormationEndpointAdd::command())
+            .subcommand(SeaplaneFormationEndpointRemove::command())
+    }
+}
+
+impl CliCommand for SeaplaneFormationEndpoint {
+    fn next_subcmd<'a>(
+        &self,
+        matches: &'a ArgMatches,
+    ) -> Option<(Box<dyn CliCommand>, &'a ArgMatches)> {
+        match &matches.subcommand() {
+            Some(("list", m)) => Some((Box::new(SeaplaneFormationEndpointList), m)),
+            Some(("add", m)) => Some((Box::new(SeaplaneFormationEndpointAdd), m)),
+            Some(("remove", m)) => Some((Box::new(SeaplaneFormationEndpointRemove), m)),
+            _ => None,
+        }
+    }
+}
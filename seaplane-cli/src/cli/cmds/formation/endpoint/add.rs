@@ -0,0 +1,163 @@
+use clap::{ArgGroup, ArgMatches, Command};
+use seaplane::api::compute::v1::EndpointKey as EndpointKeyModel;
+
+use crate::{
+    cli::{
+        cmds::formation::endpoint::{
+            editable_configuration, formation, print_endpoint, print_updated,
+        },
+        validator::{validate_endpoint, validate_public_endpoint},
+        CliCommand,
+    },
+    context::Ctx,
+    error::{CliErrorKind, Context, Result},
+    ops::formation::{
+        all_endpoints, validate_configuration, Endpoint, EndpointKind, FormationConfiguration,
+    },
+    printer::Color,
+};
+
+static LONG_ABOUT: &str = "Add endpoints to a local Formation Plan
+
+Public endpoints take the form 'ROUTE=FLIGHT:PORT' while formation and flight endpoints take the
+form 'PROTO:TARGET=FLIGHT:PORT' (see 'seaplane formation plan --help' for details).
+
+Each port or route may only be used by a single endpoint across the public, formation and flight
+endpoints, and the FLIGHT must be part of the Formation Plan. Remove an existing endpoint with
+'seaplane formation endpoint remove' before replacing it.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationEndpointAdd;
+
+impl SeaplaneFormationEndpointAdd {
+    pub fn command() -> Command<'static> {
+        let hide = cfg!(not(feature = "unstable"));
+        Command::new("add")
+            .override_usage(
+                "seaplane formation endpoint add <NAME|ID> [--public <SPEC>...] [--flight <SPEC>...]",
+            )
+            .about("Add endpoints to a local Formation Plan")
+            .long_about(LONG_ABOUT)
+            .arg(formation())
+            .arg(
+                arg!(--public =["SPEC"]...)
+                    .help("A public endpoint in the form of 'ROUTE=FLIGHT:PORT' (supports comma separated list, or multiple uses)")
+                    .validator(validate_public_endpoint),
+            )
+            .arg(
+                arg!(--flight =["SPEC"]...)
+                    .help("A flight endpoint in the form of 'PROTO:TARGET=FLIGHT:PORT' (supports comma separated list, or multiple uses)")
+                    .validator(validate_endpoint),
+            )
+            .arg(
+                arg!(--formation =["SPEC"]...)
+                    .help("A formation endpoint in the form of 'PROTO:TARGET=FLIGHT:PORT' (supports comma separated list, or multiple uses)")
+                    .validator(validate_endpoint)
+                    .hide(hide), // Hidden on feature = unstable
+            )
+            .group(
+                ArgGroup::new("endpoints")
+                    .args(&["public", "flight", "formation"])
+                    .multiple(true)
+                    .required(true),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneFormationEndpointAdd {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let (idx, cfg) = editable_configuration(ctx)?;
+        let old_id = cfg.id;
+        let mut model = cfg.model.clone();
+
+        let cfg_ctx = &ctx.formation_ctx.get_or_init().cfg_ctx;
+        let added = cfg_ctx
+            .public_endpoints
+            .iter()
+            .map(|ep| (EndpointKind::Public, ep))
+            .chain(
+                cfg_ctx
+                    .flight_endpoints
+                    .iter()
+                    .map(|ep| (EndpointKind::Flight, ep)),
+            )
+            .chain(
+                cfg_ctx
+                    .formation_endpoints
+                    .iter()
+                    .map(|ep| (EndpointKind::Formation, ep)),
+            );
+        for (kind, ep) in added.clone() {
+            let key = ep.key();
+            if all_endpoints(&model).any(|(_, k, _)| k == &key) {
+                return Err(CliErrorKind::EndpointCollision(key.to_string())
+                    .into_err()
+                    .context("(hint: remove the existing endpoint first with '")
+                    .with_color_context(|| {
+                        (
+                            Color::Green,
+                            format!(
+                                "seaplane formation endpoint remove {} {key}",
+                                ctx.args.name_id.as_ref().unwrap()
+                            ),
+                        )
+                    })
+                    .context("')\n"));
+            }
+            add_endpoint(&mut model, kind, ep);
+        }
+        validate_configuration(&model).context("(hint: the Formation Plan was not changed)\n")?;
+
+        let id = ctx.db.formations.replace_local_configuration(
+            idx,
+            &old_id,
+            FormationConfiguration::new(model),
+        );
+        ctx.persist_formations()?;
+
+        for (kind, ep) in added {
+            print_endpoint("Added", kind, format!("{}={}", ep.key(), ep.value()));
+        }
+        print_updated(ctx, idx, &id);
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.args.name_id = matches.get_one::<String>("name_id").map(ToOwned::to_owned);
+        let endpoints = |id| -> Vec<Endpoint> {
+            matches
+                .get_many::<String>(id)
+                .unwrap_or_default()
+                .filter_map(|val| val.parse::<Endpoint>().ok())
+                .collect()
+        };
+        let cfg_ctx = &mut ctx.formation_ctx.get_mut_or_init().cfg_ctx;
+        cfg_ctx.public_endpoints = endpoints("public");
+        cfg_ctx.flight_endpoints = endpoints("flight");
+        #[cfg(feature = "unstable")]
+        {
+            cfg_ctx.formation_endpoints = endpoints("formation");
+        }
+        Ok(())
+    }
+}
+
+fn add_endpoint(
+    model: &mut seaplane::api::compute::v1::FormationConfiguration,
+    kind: EndpointKind,
+    ep: &Endpoint,
+) {
+    match (kind, ep.key()) {
+        // re validator: public endpoints are always HTTP
+        (EndpointKind::Public, EndpointKeyModel::Http { path }) => {
+            model.add_public_endpoint(path, ep.value())
+        }
+        (EndpointKind::Public, _) => (),
+        (EndpointKind::Flight, key) => model.add_flight_endpoint(key, ep.value()),
+        #[cfg(feature = "unstable")]
+        (EndpointKind::Formation, key) => model.add_formation_endpoint(key, ep.value()),
+        #[cfg(not(feature = "unstable"))]
+        (EndpointKind::Formation, _) => (),
+    }
+}
@@ -0,0 +1,53 @@
+use clap::{value_parser, ArgMatches, Command};
+
+use crate::{
+    cli::{
+        cmds::formation::endpoint::{editable_configuration, formation},
+        CliCommand,
+    },
+    context::Ctx,
+    error::Result,
+    ops::formation::FormationEndpoints,
+    printer::{Output, OutputFormat},
+};
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationEndpointList;
+
+impl SeaplaneFormationEndpointList {
+    pub fn command() -> Command<'static> {
+        Command::new("list")
+            .visible_alias("ls")
+            .about("List the endpoints of a local Formation Plan")
+            .arg(formation())
+            .arg(
+                arg!(--format =["FORMAT"=>"table"])
+                    .value_parser(value_parser!(OutputFormat))
+                    .help("Change the output format"),
+            )
+            .arg(
+                arg!(--("no-header") | ("no-heading") | ("no-headers"))
+                    .help("Omit the header when printing with `--format=table`"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneFormationEndpointList {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let (_, cfg) = editable_configuration(ctx)?;
+        let endpoints = FormationEndpoints::new(&cfg.model);
+
+        match ctx.args.out_format {
+            OutputFormat::Json => endpoints.print_json(ctx)?,
+            OutputFormat::Table => endpoints.print_table(ctx)?,
+        }
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.args.name_id = matches.get_one::<String>("name_id").map(ToOwned::to_owned);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        ctx.formation_ctx.get_mut_or_init().no_header = matches.contains_id("no-header");
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use clap::{ArgMatches, Command};
+
+use crate::{
+    cli::{
+        cmds::formation::endpoint::{
+            editable_configuration, formation, print_endpoint, print_updated,
+        },
+        validator::validate_endpoint_src,
+        CliCommand,
+    },
+    context::Ctx,
+    error::{CliErrorKind, Context, Result},
+    ops::formation::{validate_configuration, EndpointKind, EndpointSrc, FormationConfiguration},
+    printer::Color,
+};
+
+static LONG_ABOUT: &str = "Remove endpoints from a local Formation Plan
+
+Endpoints are given by the part before the '=', i.e. 'ROUTE' or 'http:ROUTE' for HTTP endpoints
+and 'tcp:PORT' or 'udp:PORT' for network ports. Matching public, formation and flight endpoints are
+all removed.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationEndpointRemove;
+
+impl SeaplaneFormationEndpointRemove {
+    pub fn command() -> Command<'static> {
+        Command::new("remove")
+            .visible_aliases(&["rm", "delete"])
+            .about("Remove endpoints from a local Formation Plan")
+            .long_about(LONG_ABOUT)
+            .arg(formation())
+            .arg(
+                arg!(endpoint =["ENDPOINT"]... required)
+                    .help("The route or PROTO:TARGET of the endpoint to remove")
+                    .validator(validate_endpoint_src),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneFormationEndpointRemove {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let (idx, cfg) = editable_configuration(ctx)?;
+        let old_id = cfg.id;
+        let mut model = cfg.model.clone();
+
+        let mut removed = Vec::new();
+        let removals = &ctx.formation_ctx.get_or_init().cfg_removals;
+        for src in &removals.public_endpoints {
+            let key = src.key();
+            if let Some(value) = model.remove_public_endpoint(&key) {
+                removed.push((EndpointKind::Public, key, value));
+            }
+        }
+        for src in &removals.flight_endpoints {
+            let key = src.key();
+            if let Some(value) = model.remove_flight_endpoint(&key) {
+                removed.push((EndpointKind::Flight, key, value));
+            }
+        }
+        #[cfg(feature = "unstable")]
+        for src in &removals.formation_endpoints {
+            let key = src.key();
+            if let Some(value) = model.remove_formation_endpoint(&key) {
+                removed.push((EndpointKind::Formation, key, value));
+            }
+        }
+
+        // Every endpoint is listed in each of the removals, so look for those that matched nothing
+        if let Some(key) = removals
+            .public_endpoints
+            .iter()
+            .map(EndpointSrc::key)
+            .find(|key| !removed.iter().any(|(_, k, _)| k == key))
+        {
+            return Err(CliErrorKind::NoMatchingItem(key.to_string())
+                .into_err()
+                .context("(hint: list the endpoints with '")
+                .with_color_context(|| {
+                    (
+                        Color::Green,
+                        format!(
+                            "seaplane formation endpoint list {}",
+                            ctx.args.name_id.as_ref().unwrap()
+                        ),
+                    )
+                })
+                .context("')\n"));
+        }
+        validate_configuration(&model).context("(hint: the Formation Plan was not changed)\n")?;
+
+        let id = ctx.db.formations.replace_local_configuration(
+            idx,
+            &old_id,
+            FormationConfiguration::new(model),
+        );
+        ctx.persist_formations()?;
+
+        for (kind, key, value) in removed {
+            print_endpoint("Removed", kind, format!("{key}={value}"));
+        }
+        print_updated(ctx, idx, &id);
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.args.name_id = matches.get_one::<String>("name_id").map(ToOwned::to_owned);
+        let endpoints: Vec<EndpointSrc> = matches
+            .get_many::<String>("endpoint")
+            .unwrap_or_default()
+            .filter_map(|val| val.parse::<EndpointSrc>().ok())
+            .collect();
+        // An endpoint is removed from whichever of the maps it is found in
+        let removals = &mut ctx.formation_ctx.get_mut_or_init().cfg_removals;
+        removals.public_endpoints = endpoints.clone();
+        removals.flight_endpoints = endpoints.clone();
+        removals.formation_endpoints = endpoints;
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use clap::{value_parser, ArgMatches, Command};
+
+use crate::{
+    cli::{
+        errors,
+        validator::{validate_formation_name, validate_name_id},
+        CliCommand,
+    },
+    context::Ctx,
+    error::Result,
+    printer::{Output, OutputFormat},
+};
+
+static LONG_ABOUT: &str = "Show the history of active configurations of a Formation Instance
+
+Each time the CLI changes which configurations of a Formation Instance are active (i.e. with
+'seaplane formation launch', 'land', 'rollout', 'rollback' or 'clone --active') the configuration
+UUIDs and their traffic weights are recorded locally, along with a timestamp. Entries are numbered
+from 1 (the oldest) and can be passed to 'seaplane formation rollback --to'.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationHistory;
+
+impl SeaplaneFormationHistory {
+    pub fn command() -> Command<'static> {
+        let validator = |s: &str| validate_name_id(validate_formation_name, s);
+        Command::new("history")
+            .about("Show the history of active configurations of a Formation Instance")
+            .long_about(LONG_ABOUT)
+            .arg(
+                arg!(formation =["NAME|ID"] required)
+                    .validator(validator)
+                    .help("The name or ID of the Formation, must be unambiguous"),
+            )
+            .arg(
+                arg!(--format =["FORMAT"=>"table"])
+                    .value_parser(value_parser!(OutputFormat))
+                    .help("Change the output format"),
+            )
+            .arg(
+                arg!(--("no-header") | ("no-heading") | ("no-headers"))
+                    .help("Omit the header when printing with `--format=table`"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneFormationHistory {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let name = ctx.args.name_id.as_ref().unwrap().clone();
+        let indices = ctx.db.formations.formation_indices_of_matches(&name);
+        let idx = match indices.as_slice() {
+            [] => return errors::no_matching_item(name, false, false),
+            [idx] => *idx,
+            _ => return errors::ambiguous_item(name, false),
+        };
+        // re unwrap: the index came from Formations so it has to be valid
+        let history = &ctx.db.formations.get_formation(idx).unwrap().history;
+
+        match ctx.args.out_format {
+            OutputFormat::Json => history.print_json(ctx)?,
+            OutputFormat::Table => history.print_table(ctx)?,
+        }
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.args.name_id = matches
+            .get_one::<String>("formation")
+            .map(ToOwned::to_owned);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        ctx.formation_ctx.get_mut_or_init().no_header = matches.contains_id("no-header");
+        Ok(())
+    }
+}
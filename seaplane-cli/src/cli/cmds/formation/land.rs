@@ -9,6 +9,7 @@ use crate::{
         CliCommand,
     },
     error::Result,
+    ops::formation::ActiveSet,
     Ctx,
 };
 
@@ -77,6 +78,7 @@ impl CliCommand for SeaplaneFormationLand {
             for id in ids {
                 formation.grounded.insert(id);
             }
+            formation.history.push(ActiveSet::new(Vec::new()));
 
             ctx.persist_formations()?;
 
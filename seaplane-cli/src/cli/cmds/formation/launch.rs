@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use clap::{ArgMatches, Command};
 use seaplane::{
     api::{
@@ -6,17 +8,26 @@ use seaplane::{
     },
     error::SeaplaneError,
 };
+use uuid::Uuid;
 
 use crate::{
     api::FormationsReq,
     cli::{
-        cmds::formation::SeaplaneFormationFetch,
+        cmds::formation::{
+            wait::{self, wait_for_formation},
+            SeaplaneFormationFetch,
+        },
         errors,
         validator::{validate_formation_name, validate_name_id},
         CliCommand,
     },
     context::Ctx,
     error::{CliErrorKind, Context, Result},
+    ops::{
+        formation::{all_endpoints, validate_configuration, Formation, WaitFor},
+        image::{check_flight_architectures, pin_flight_image},
+        Id,
+    },
     printer::{Color, Pb},
 };
 
@@ -54,7 +65,22 @@ sending traffic to.
 
 These configurations will be made active. If the Seaplane Cloud already has active
 configurations for the given Formation, these newly activated configurations will be appended,
-and traffic will be balanced between any *all* configurations. ";
+and traffic will be balanced between any *all* configurations.
+
+Flight images which are not pinned to a digest are resolved by the Seaplane Cloud at launch time,
+meaning re-launching the same Plan later may run a different image. Use '--pin-digest' to resolve
+each tag to the digest it currently points to before uploading. The pinned images are also
+recorded in the matching local Flight Plans.
+
+A Flight whose image is not published for one of its architectures will never start. Use
+'--check-arch' to fail before uploading when an image is missing a declared architecture, or
+'--infer-arch' to declare the architectures each image is published for. See 'seaplane image
+--help' for reaching registries which require credentials.
+
+This command returns as soon as Seaplane accepts the configurations. Use '--wait' to instead wait
+until every Flight of the Formation Instance has at least its minimum number of containers
+running, optionally failing if that does not happen within '--timeout' seconds (see 'seaplane
+formation wait'). ";
 
 #[derive(Copy, Clone, Debug)]
 pub struct SeaplaneFormationLaunch;
@@ -85,7 +111,119 @@ impl SeaplaneFormationLaunch {
                     "Upload the configuration(s) defined in this local Formation Plan to Seaplane but *DO NOT* set them to active",
                 ),
             )
+            .arg(arg!(--("pin-digest")).help(
+                "Resolve Flight image tags to the digests they currently point to before uploading",
+            ))
+            .arg(arg!(--("check-arch")).help(
+                "Fail if any Flight image is not published for every architecture the Flight declares",
+            ))
+            .arg(arg!(--("infer-arch")).help(
+                "Replace the architectures of each Flight with those its image is published for",
+            ))
+            .arg(
+                arg!(--wait)
+                    .conflicts_with("grounded")
+                    .help("Wait for the Formation Instance to be up before returning"),
+            )
+            .arg(wait::timeout().requires("wait").help(
+                "Give up waiting for the Formation Instance after SECS seconds (requires --wait)",
+            ))
+    }
+}
+
+/// The configurations of `formation` that `launch` will upload
+fn launch_cfg_ids(formation: &Formation, grounded: bool) -> Vec<Id> {
+    if grounded {
+        formation.local_only_configs()
+    } else {
+        formation.local_or_grounded_configs()
+    }
+}
+
+/// Pins the unpinned Flight images of the configuration with the given ID, and records the pinned
+/// images in any matching local Flight Plans
+fn pin_configuration(ctx: &mut Ctx, id: &Id) -> Result<()> {
+    // re unwrap: the IDs came from our own Formations
+    let mut flights = ctx
+        .db
+        .formations
+        .get_configuration(id)
+        .unwrap()
+        .model
+        .flights()
+        .to_vec();
+    let mut pinned_any = false;
+    for flight in &mut flights {
+        if let Some(pinned) = pin_flight_image(ctx, flight.name(), flight.image())? {
+            ctx.db.flights.record_pinned_image(flight.name(), &pinned);
+            flight.set_image(pinned);
+            pinned_any = true;
+        }
+    }
+    if pinned_any {
+        ctx.db
+            .formations
+            .get_configuration_mut(id)
+            .unwrap()
+            .model
+            .set_flights(flights);
+    }
+    Ok(())
+}
+
+/// Checks (or with `infer`, infers) the Flight architectures of the configuration with the given
+/// ID, and records any inferred architectures in matching local Flight Plans
+fn check_configuration(ctx: &mut Ctx, id: &Id, infer: bool) -> Result<()> {
+    // re unwrap: the IDs came from our own Formations
+    let mut flights = ctx
+        .db
+        .formations
+        .get_configuration(id)
+        .unwrap()
+        .model
+        .flights()
+        .to_vec();
+    let mut inferred_any = false;
+    for flight in &mut flights {
+        if let Some(archs) = check_flight_architectures(ctx, flight, infer)? {
+            ctx.db
+                .flights
+                .record_architectures(flight.name(), flight.image(), &archs);
+            flight.set_architecture(archs);
+            inferred_any = true;
+        }
     }
+    if inferred_any {
+        ctx.db
+            .formations
+            .get_configuration_mut(id)
+            .unwrap()
+            .model
+            .set_flights(flights);
+    }
+    Ok(())
+}
+
+/// Warns about any Flight images of the configuration with the given ID that are not pinned to a
+/// digest, returning `true` if there were any
+fn warn_unpinned(ctx: &Ctx, id: &Id) -> bool {
+    let mut unpinned = false;
+    // re unwrap: the IDs came from our own Formations
+    let cfg = ctx.db.formations.get_configuration(id).unwrap();
+    for flight in cfg
+        .model
+        .flights()
+        .iter()
+        .filter(|f| f.image().digest.is_none())
+    {
+        cli_warnln!(
+            "Flight '{}' uses image '{}' which is not pinned to a digest",
+            flight.name(),
+            flight.image()
+        );
+        unpinned = true;
+    }
+    unpinned
 }
 
 impl CliCommand for SeaplaneFormationLaunch {
@@ -117,9 +255,46 @@ impl CliCommand for SeaplaneFormationLaunch {
             }
         }
 
-        let pb = Pb::new(ctx);
         let grounded = ctx.formation_ctx.get_or_init().grounded;
+        let launch_ids: Vec<Id> = indices
+            .iter()
+            // re unwrap: the indices returned came from Formations so they have to be valid
+            .flat_map(|&idx| {
+                launch_cfg_ids(ctx.db.formations.get_formation(idx).unwrap(), grounded)
+            })
+            .collect();
+        let (pin_digest, check_arch, infer_arch) = {
+            let fctx = ctx.formation_ctx.get_or_init();
+            (fctx.pin_digest, fctx.check_arch, fctx.infer_arch)
+        };
+        if pin_digest {
+            for id in &launch_ids {
+                pin_configuration(ctx, id)?;
+            }
+        } else {
+            let mut unpinned = false;
+            for id in &launch_ids {
+                unpinned |= warn_unpinned(ctx, id);
+            }
+            if unpinned {
+                cli_warn!("(hint: re-launching later may run different images, use '");
+                cli_warn!(@Yellow, "--pin-digest");
+                cli_warnln!(@noprefix, "' to pin them)");
+            }
+        }
+        if check_arch || infer_arch {
+            for id in &launch_ids {
+                check_configuration(ctx, id, infer_arch)?;
+            }
+        }
+        if pin_digest || infer_arch {
+            ctx.persist_flights()?;
+            ctx.persist_formations()?;
+        }
+
+        let pb = Pb::new(ctx);
         let mut req = FormationsReq::new_delay_token(ctx)?;
+        let mut launched = Vec::new();
         for idx in indices {
             // re unwrap: the indices returned came from Formations so they have to be valid
             let formation = ctx.db.formations.get_formation(idx).unwrap();
@@ -127,11 +302,7 @@ impl CliCommand for SeaplaneFormationLaunch {
             req.set_name(&formation_name)?;
 
             // Get the local configs that don't exist remote yet
-            let cfgs_ids = if grounded {
-                formation.local_only_configs()
-            } else {
-                formation.local_or_grounded_configs()
-            };
+            let cfgs_ids = launch_cfg_ids(formation, grounded);
 
             if cfgs_ids.is_empty() {
                 if ctx.args.fetch && grounded {
@@ -157,7 +328,7 @@ impl CliCommand for SeaplaneFormationLaunch {
             'inner: for id in &cfgs_ids {
                 if let Some(cfg) = ctx.db.formations.get_configuration(id) {
                     has_public_endpoints = cfg.model.public_endpoints().count() > 0;
-                    if let Some(flight) = cfg.model.public_endpoints().find_map(|(_, dst)| {
+                    if let Some(flight) = all_endpoints(&cfg.model).find_map(|(_, _, dst)| {
                         if !ctx.db.formations.has_flight(&dst.flight_name) {
                             Some(dst.flight_name.clone())
                         } else {
@@ -173,28 +344,39 @@ impl CliCommand for SeaplaneFormationLaunch {
                             .color_context(Color::Green, "seaplane flight plan")
                             .context("')\n"));
                     }
+                    validate_configuration(&cfg.model)
+                        .context("(hint: fix the Formation Plan with '")
+                        .with_color_context(|| {
+                            (Color::Green, format!("seaplane formation edit {formation_name}"))
+                        })
+                        .context("')\n")?;
 
                     // We don't set the configuration to active because we'll be doing that to
                     // *all* formation configs in a minute
                     pb.set_message("Searching for existing Formations...");
-                    if let Err(e) = req.add_configuration(&cfg.model, false) {
-                        match e.kind() {
+                    match req.add_configuration(&cfg.model, false) {
+                        Err(e) => match e.kind() {
                             CliErrorKind::Seaplane(SeaplaneError::ApiResponse(ae))
                                 if ae.kind == ApiErrorKind::NotFound =>
                             {
                                 // If the formation didn't exist, create it
                                 pb.set_message("Creating new Formation Instance...");
                                 let cfg_uuid = req.create(&cfg.model, !grounded)?;
+                                if let Some(uuid) = cfg_uuid.first() {
+                                    ctx.db.formations.add_uuid(id, *uuid);
+                                }
                                 cfg_uuids.extend(cfg_uuid);
                                 ctx.db.formations.add_in_air_by_name(&formation_name, *id);
                                 created_new = true;
                                 break 'inner;
                             }
                             _ => return Err(e),
+                        },
+                        Ok(uuid) => {
+                            pb.set_message("Found existing Formation Instance...");
+                            ctx.db.formations.add_uuid(id, uuid);
+                            ctx.db.formations.add_grounded_by_name(&formation_name, *id);
                         }
-                    } else {
-                        pb.set_message("Found existing Formation Instance...");
-                        ctx.db.formations.add_grounded_by_name(&formation_name, *id);
                     }
                 } else {
                     // TODO: Inform the user of possible error? Somehow there is no config by the
@@ -230,6 +412,12 @@ impl CliCommand for SeaplaneFormationLaunch {
                     ctx.db.formations.add_in_air_by_name(&formation_name, id);
                 }
             }
+            if !grounded {
+                let active: Vec<(Uuid, f32)> = cfg_uuids.iter().map(|uuid| (*uuid, 1.0)).collect();
+                ctx.db
+                    .formations
+                    .record_active_configurations(&formation_name, &active);
+            }
             pb.set_message("Getting Formation URL...");
             let domain = req.get_metadata()?.url;
 
@@ -256,10 +444,17 @@ impl CliCommand for SeaplaneFormationLaunch {
             cli_print!("(hint: check the status of this Formation Instance with '");
             cli_print!(@Green, "seaplane formation status {formation_name}");
             cli_println!("')");
+            launched.push(formation_name);
         }
 
         ctx.persist_formations()?;
 
+        if ctx.formation_ctx.get_or_init().wait_for.is_some() {
+            for name in &launched {
+                wait_for_formation(ctx, name)?;
+            }
+        }
+
         Ok(())
     }
 
@@ -270,6 +465,16 @@ impl CliCommand for SeaplaneFormationLaunch {
         ctx.args.fetch = matches.contains_id("fetch");
         let fctx = ctx.formation_ctx.get_mut_or_init();
         fctx.grounded = matches.contains_id("grounded");
+        fctx.pin_digest = matches.contains_id("pin-digest");
+        fctx.check_arch = matches.contains_id("check-arch");
+        fctx.infer_arch = matches.contains_id("infer-arch");
+        if matches.contains_id("wait") {
+            fctx.wait_for = Some(WaitFor::Up);
+            fctx.wait_timeout = matches
+                .get_one::<u64>("timeout")
+                .copied()
+                .map(Duration::from_secs);
+        }
         Ok(())
     }
 }
@@ -0,0 +1,91 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use clap::{value_parser, ArgMatches, Command};
+use seaplane::api::compute::v1::ContainerStatus;
+
+use crate::{
+    api::FormationsReq,
+    cli::{validator::validate_formation_name, CliCommand},
+    context::Ctx,
+    error::{CliErrorKind, Result},
+    ops::formation::{ContainerPlacement, FormationPlacement},
+    printer::{Output, OutputFormat},
+};
+
+static LONG_ABOUT: &str = "Show where the containers of a remote Formation Instance run
+
+Every container that has not stopped is listed with the provider, region, country and nearest
+airport (IATA code) of its host. Each one is checked against the allowed and denied providers and
+regions of the configuration it belongs to, and any container running outside of them is flagged.
+When a configuration restricts providers or regions, a container whose host does not report them
+is flagged as well.
+
+This command exits with an error if any container was flagged, after printing the full report.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationPlacement;
+
+impl SeaplaneFormationPlacement {
+    pub fn command() -> Command<'static> {
+        Command::new("placement")
+            .about("Show where the containers of a remote Formation Instance run")
+            .long_about(LONG_ABOUT)
+            .arg(
+                arg!(name =["NAME"] required)
+                    .validator(validate_formation_name)
+                    .help("The name of the Formation Instance to check"),
+            )
+            .arg(
+                arg!(--format =["FORMAT"=>"table"])
+                    .value_parser(value_parser!(OutputFormat))
+                    .help("Change the output format"),
+            )
+            .arg(
+                arg!(--("no-header") | ("no-heading") | ("no-headers"))
+                    .help("Omit the header when printing with `--format=table`"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneFormationPlacement {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        // re unwrap: the name is a required argument
+        let name = ctx.args.name_id.as_ref().unwrap();
+        let mut req = FormationsReq::new_delay_token(ctx)?;
+        req.set_name(name)?;
+
+        // Check against the configurations as they are in the Seaplane Cloud, which is what the
+        // containers were scheduled with
+        let mut models = HashMap::new();
+        let mut placement = FormationPlacement::default();
+        for container in req
+            .get_containers()?
+            .iter()
+            .filter(|c| c.status != ContainerStatus::Stopped)
+        {
+            let uuid = container.configuration_id;
+            let model = match models.entry(uuid) {
+                Entry::Occupied(e) => e.into_mut(),
+                Entry::Vacant(e) => e.insert(req.get_configuration(uuid)?),
+            };
+            placement.push(ContainerPlacement::new(container, model));
+        }
+
+        match ctx.args.out_format {
+            OutputFormat::Json => placement.print_json(ctx)?,
+            OutputFormat::Table => placement.print_table(ctx)?,
+        }
+
+        match placement.violations() {
+            0 => Ok(()),
+            n => Err(CliErrorKind::FormationPlacementViolations(name.into(), n).into_err()),
+        }
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.args.name_id = matches.get_one::<String>("name").map(ToOwned::to_owned);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        ctx.formation_ctx.get_mut_or_init().no_header = matches.contains_id("no-header");
+        Ok(())
+    }
+}
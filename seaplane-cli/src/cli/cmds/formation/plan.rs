@@ -130,57 +130,7 @@ impl CliCommand for SeaplaneFormationPlan {
         ctx.args.fetch = matches.contains_id("fetch");
         ctx.args.force = matches.contains_id("force");
 
-        // Create any flights required
-        let mut flights: Vec<_> = matches
-            .get_many::<String>("include-flight-plan")
-            .unwrap_or_default()
-            .collect();
-
-        // Flights declared with i.e. name=FOO,image=nginx:latest
-        let inline_flights = vec_remove_if!(flights, |f: &str| f.contains('='));
-        for flight in inline_flights {
-            let mut cloned_ctx = ctx.clone();
-            // We set stateless because we don't want the created flights to be persisted until
-            // we're ready (i.e. we're sure this formation will be created)
-            cloned_ctx.args.stateless = true;
-            cloned_ctx.internal_run = true;
-            cloned_ctx
-                .flight_ctx
-                .init(FlightCtx::from_inline_flight(flight, &ctx.registry)?);
-
-            let flight_plan: Box<dyn CliCommand> = Box::new(SeaplaneFlightPlan);
-            flight_plan.run(&mut cloned_ctx)?;
-
-            let name = cloned_ctx.flight_ctx.get_or_init().name_id.clone();
-            // copy the newly created flight out of the cloned context into the "real" one
-            ctx.db
-                .flights
-                .add_flight(cloned_ctx.db.flights.remove_flight(&name, true).unwrap());
-
-            // Store the newly created Flight name as if it was passed by name via
-            // `--include-flight-plan FOO`
-            ctx.formation_ctx
-                .get_mut_or_init()
-                .cfg_ctx
-                .flights
-                .push(name);
-
-            ctx.db.needs_persist = true;
-        }
-
-        // Flights using @path or @-
-        for name in ctx
-            .db
-            .flights
-            .add_from_at_strs(vec_remove_if!(flights, |f: &str| f.starts_with('@')))?
-        {
-            ctx.formation_ctx
-                .get_mut_or_init()
-                .cfg_ctx
-                .flights
-                .push(name);
-            ctx.db.needs_persist = true;
-        }
+        create_included_flights(matches, ctx)?;
 
         // Any flights we created will be persisted during `run` as we could still hit an error
         // such as Duplicate Formation Names and would need to roll them back
@@ -195,3 +145,63 @@ impl CliCommand for SeaplaneFormationPlan {
         Ok(())
     }
 }
+
+/// Creates the new Flight Plans included via INLINE-SPEC, @path or @- in `--include-flight-plan`
+/// and adds their names to the Formation context.
+///
+/// The Flight Plans are not persisted, and `ctx.db.needs_persist` is set if any were created.
+pub(crate) fn create_included_flights(matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+    // Create any flights required
+    let mut flights: Vec<_> = matches
+        .get_many::<String>("include-flight-plan")
+        .unwrap_or_default()
+        .collect();
+
+    // Flights declared with i.e. name=FOO,image=nginx:latest
+    let inline_flights = vec_remove_if!(flights, |f: &str| f.contains('='));
+    for flight in inline_flights {
+        let mut cloned_ctx = ctx.clone();
+        // We set stateless because we don't want the created flights to be persisted until
+        // we're ready (i.e. we're sure this formation will be created)
+        cloned_ctx.args.stateless = true;
+        cloned_ctx.internal_run = true;
+        cloned_ctx
+            .flight_ctx
+            .init(FlightCtx::from_inline_flight(flight, &ctx.registry)?);
+
+        let flight_plan: Box<dyn CliCommand> = Box::new(SeaplaneFlightPlan);
+        flight_plan.run(&mut cloned_ctx)?;
+
+        let name = cloned_ctx.flight_ctx.get_or_init().name_id.clone();
+        // copy the newly created flight out of the cloned context into the "real" one
+        ctx.db
+            .flights
+            .add_flight(cloned_ctx.db.flights.remove_flight(&name, true).unwrap());
+
+        // Store the newly created Flight name as if it was passed by name via
+        // `--include-flight-plan FOO`
+        ctx.formation_ctx
+            .get_mut_or_init()
+            .cfg_ctx
+            .flights
+            .push(name);
+
+        ctx.db.needs_persist = true;
+    }
+
+    // Flights using @path or @-
+    for name in ctx
+        .db
+        .flights
+        .add_from_at_strs(vec_remove_if!(flights, |f: &str| f.starts_with('@')))?
+    {
+        ctx.formation_ctx
+            .get_mut_or_init()
+            .cfg_ctx
+            .flights
+            .push(name);
+        ctx.db.needs_persist = true;
+    }
+
+    Ok(())
+}
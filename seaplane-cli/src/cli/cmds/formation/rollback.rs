@@ -0,0 +1,182 @@
+use clap::{value_parser, ArgMatches, Command};
+use seaplane::{api::ApiErrorKind, error::SeaplaneError};
+
+use crate::{
+    api::FormationsReq,
+    cli::{
+        errors,
+        validator::{validate_formation_name, validate_name_id},
+        CliCommand,
+    },
+    context::Ctx,
+    error::{CliError, CliErrorKind, Context, Result},
+    printer::Color,
+};
+
+static LONG_ABOUT: &str = "Re-activate a previous set of configurations of a Formation Instance
+
+By default the set of configurations that was active before the current one is restored, use
+'--to' to pick any entry of 'seaplane formation history' instead. Traffic weights are only
+restored along with the configurations in builds with unstable features enabled, otherwise the
+configurations share traffic evenly.
+
+Any configuration of the set that no longer exists in the Seaplane Cloud is uploaded again from
+the locally stored Formation Plan, creating the Formation Instance itself if it was deleted. If
+the entry records the Formation Instance being landed, it is landed again.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationRollback;
+
+impl SeaplaneFormationRollback {
+    pub fn command() -> Command<'static> {
+        let validator = |s: &str| validate_name_id(validate_formation_name, s);
+        Command::new("rollback")
+            .about("Re-activate a previous set of configurations of a Formation Instance")
+            .long_about(LONG_ABOUT)
+            .arg(
+                arg!(formation =["NAME|ID"] required)
+                    .validator(validator)
+                    .help("The name or ID of the Formation to roll back, must be unambiguous"),
+            )
+            .arg(
+                arg!(--to = ["N"])
+                    .value_parser(value_parser!(u64).range(1..))
+                    .help("The number of the 'seaplane formation history' entry to roll back to"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneFormationRollback {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let name = ctx.args.name_id.as_ref().unwrap().clone();
+        let indices = ctx.db.formations.formation_indices_of_matches(&name);
+        let idx = match indices.as_slice() {
+            [] => return errors::no_matching_item(name, false, false),
+            [idx] => *idx,
+            _ => return errors::ambiguous_item(name, false),
+        };
+        // re unwrap: the index came from Formations so it has to be valid
+        let formation = ctx.db.formations.get_formation(idx).unwrap();
+        let formation_name = formation.name.clone().unwrap();
+        // By default go back to the set before the current (i.e. last) one
+        let n = ctx
+            .formation_ctx
+            .get_or_init()
+            .rollback_to
+            .unwrap_or_else(|| formation.history.len().saturating_sub(1));
+        let mut target = match formation.history.get(n) {
+            Some(set) => set.clone(),
+            None => {
+                let msg = if n == 0 {
+                    format!("Formation '{formation_name}' has no previous active configurations")
+                } else {
+                    format!("Formation '{formation_name}' has no history entry #{n}")
+                };
+                return Err(CliErrorKind::OneOff(msg).into_err())
+                    .context("(hint: see the recorded entries with '")
+                    .with_color_context(|| {
+                        (Color::Green, format!("seaplane formation history {formation_name}"))
+                    })
+                    .context("')\n");
+            }
+        };
+
+        let mut req = FormationsReq::new_delay_token(ctx)?;
+        req.set_name(&formation_name)?;
+
+        if target.is_landed() {
+            req.stop()?;
+        } else {
+            // None if the Formation Instance itself no longer exists
+            let mut remote_ids = match req.list_configuration_ids() {
+                Ok(ids) => Some(ids),
+                Err(e) => match e.kind() {
+                    CliErrorKind::Seaplane(SeaplaneError::ApiResponse(ae))
+                        if ae.kind == ApiErrorKind::NotFound =>
+                    {
+                        None
+                    }
+                    _ => return Err(e),
+                },
+            };
+
+            for cfg in &mut target.configurations {
+                if matches!(&remote_ids, Some(ids) if ids.contains(&cfg.uuid)) {
+                    continue;
+                }
+                let local = cfg
+                    .id
+                    .and_then(|id| ctx.db.formations.get_configuration(&id))
+                    .or_else(|| ctx.db.formations.get_configuration_by_uuid(cfg.uuid));
+                let (id, model) = match local {
+                    Some(local) => (local.id, local.model.clone()),
+                    None => {
+                        return Err(CliErrorKind::OneOff(format!(
+                            "configuration {} no longer exists remotely, and there is no local copy to upload",
+                            cfg.uuid
+                        ))
+                        .into_err())
+                    }
+                };
+
+                let uuid = if remote_ids.is_some() {
+                    req.add_configuration(&model, false)?
+                } else {
+                    let uuid = req
+                        .create(&model, false)?
+                        .first()
+                        .copied()
+                        .ok_or_else(|| CliError::bail("no configuration UUID was returned"))?;
+                    remote_ids = Some(Vec::new());
+                    uuid
+                };
+                cli_print!("Re-uploaded configuration ");
+                cli_print!(@Green, "{uuid}");
+                cli_println!(" (previously {})", cfg.uuid);
+                ctx.db.formations.add_uuid(&id, uuid);
+                cfg.uuid = uuid;
+                cfg.id = Some(id);
+            }
+
+            req.set_active_configurations(&target.to_model()?, false)
+                .context("Context: failed to re-activate the configurations\n")?;
+        }
+
+        // re unwrap: the index came from Formations so it has to be valid
+        let formation = ctx.db.formations.get_formation_mut(idx).unwrap();
+        let ids: Vec<_> = formation.in_air.drain().collect();
+        formation.grounded.extend(ids);
+        for id in target.configurations.iter().filter_map(|cfg| cfg.id) {
+            formation.grounded.remove(&id);
+            formation.in_air.insert(id);
+        }
+        let active: Vec<_> = target
+            .configurations
+            .iter()
+            .map(|cfg| (cfg.uuid, cfg.traffic_weight))
+            .collect();
+        ctx.db
+            .formations
+            .record_active_configurations(&formation_name, &active);
+        ctx.persist_formations()?;
+
+        cli_print!("Successfully rolled back Formation Instance '");
+        cli_print!(@Green, "{formation_name}");
+        if target.is_landed() {
+            cli_println!("' to #{n} (landed)");
+        } else {
+            cli_println!("' to #{n}");
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.args.name_id = matches
+            .get_one::<String>("formation")
+            .map(ToOwned::to_owned);
+        ctx.formation_ctx.get_mut_or_init().rollback_to =
+            matches.get_one::<u64>("to").map(|&n| n as usize);
+        Ok(())
+    }
+}
@@ -0,0 +1,372 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use clap::{value_parser, ArgMatches, Command};
+use seaplane::api::compute::v1::{
+    ActiveConfiguration, ActiveConfigurations,
+    FormationConfiguration as FormationConfigurationModel,
+};
+use uuid::Uuid;
+
+use crate::{
+    api::FormationsReq,
+    cli::{
+        cmds::locks::common,
+        errors,
+        validator::{validate_formation_name, validate_name_id},
+        CliCommand,
+    },
+    context::Ctx,
+    error::{CliError, CliErrorKind, Context, Result},
+    ops::formation::{validate_configuration, FormationStatus},
+    printer::Color,
+};
+
+static LONG_ABOUT: &str =
+    "Gradually shift traffic of a remote Formation Instance to a new configuration
+
+The new configuration of the local Formation Plan (i.e. one created with 'seaplane formation
+edit') is uploaded, and the share of traffic it receives is raised to each percentage of '--steps'
+in turn, while the currently active configurations share the remainder in their existing
+proportions. Once the last step reaches 100 the previously active configurations are deactivated.
+
+After each step, and waiting '--interval' seconds, the containers of the new configuration are
+checked. If any of its Flights has errored containers, or is not running its minimum number of
+containers (and at least one), the previously active configurations are restored and this command
+exits with an error. The same happens if shifting traffic or checking the containers fails part
+way through the rollout, or if interrupted (i.e. with Ctrl-C). The new configuration stays
+uploaded, but inactive.
+
+Only the final set of active configurations (and the restored one if the rollout fails) is
+recorded in 'seaplane formation history', so 'seaplane formation rollback' never returns to one
+of the intermediate steps.";
+
+/// How often to check whether the rollout was interrupted while waiting between steps
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationRollout;
+
+impl SeaplaneFormationRollout {
+    pub fn command() -> Command<'static> {
+        let validator = |s: &str| validate_name_id(validate_formation_name, s);
+        Command::new("rollout")
+            .about("Gradually shift traffic of a remote Formation Instance to a new configuration")
+            .long_about(LONG_ABOUT)
+            .arg(
+                arg!(formation =["NAME|ID"] required)
+                    .validator(validator)
+                    .help("The name or ID of the Formation Plan with the new configuration"),
+            )
+            .arg(
+                arg!(--steps =["PERCENT"=>"10,25,50,100"]...)
+                    .value_parser(value_parser!(u8).range(1..=100))
+                    .help("The percentages of traffic to shift to the new configuration, one step at a time"),
+            )
+            .arg(
+                arg!(--interval =["SECS"=>"30"])
+                    .value_parser(value_parser!(u64))
+                    .help("Wait SECS seconds after each step before checking the new configuration"),
+            )
+    }
+}
+
+/// The active configurations with `percent` of the traffic going to `new`, and the rest shared by
+/// the configurations in `old` keeping their proportions
+fn shifted_configurations(
+    old: &ActiveConfigurations,
+    new: Uuid,
+    percent: u8,
+) -> Result<ActiveConfigurations> {
+    let mut cfgs = ActiveConfigurations::new();
+    if percent < 100 {
+        let total: f32 = old
+            .iter()
+            .map(|cfg| cfg.traffic_weight().unwrap_or(1.))
+            .sum();
+        for cfg in old.iter() {
+            let share = cfg.traffic_weight().unwrap_or(1.) / total;
+            cfgs.add_configuration_mut(
+                ActiveConfiguration::builder()
+                    .uuid(*cfg.uuid())
+                    .traffic_weight(share * f32::from(100 - percent))
+                    .build()?,
+            );
+        }
+    }
+    cfgs.add_configuration_mut(
+        ActiveConfiguration::builder()
+            .uuid(new)
+            .traffic_weight(f32::from(percent))
+            .build()?,
+    );
+    Ok(cfgs)
+}
+
+/// The UUID and traffic weight of each of the active configurations
+fn weights(cfgs: &ActiveConfigurations) -> Vec<(Uuid, f32)> {
+    cfgs.iter()
+        .map(|cfg| (*cfg.uuid(), cfg.traffic_weight().unwrap_or(1.)))
+        .collect()
+}
+
+/// The status of only the containers of the configuration `uuid`
+fn configuration_status(
+    req: &mut FormationsReq,
+    name: &str,
+    uuid: Uuid,
+    model: &FormationConfigurationModel,
+) -> Result<FormationStatus> {
+    let mut status = FormationStatus::new(name);
+    status.add_configuration(uuid, model);
+    for container in req
+        .get_containers()?
+        .iter()
+        .filter(|c| c.configuration_id == uuid)
+    {
+        if let Some(flight) = model
+            .flights()
+            .iter()
+            .find(|f| f.name() == container.flight_name)
+        {
+            status.add_container(container, flight.minimum(), flight.maximum());
+        }
+    }
+    status.update_status();
+    Ok(status)
+}
+
+/// A rollout of the configuration `uuid` to the Formation Instance `name`, taking traffic from the
+/// configurations in `old`
+struct Rollout<'a> {
+    name: &'a str,
+    old: &'a ActiveConfigurations,
+    uuid: Uuid,
+    model: &'a FormationConfigurationModel,
+    interval: Duration,
+    /// Whether any traffic has been shifted to the new configuration
+    shifted: bool,
+    /// Whether the new configuration was recorded in the history, which only happens once it
+    /// receives all of the traffic
+    recorded: bool,
+}
+
+impl Rollout<'_> {
+    /// Shifts `percent` of the traffic to the new configuration and checks it is healthy after the
+    /// interval
+    fn step(&mut self, ctx: &mut Ctx, req: &mut FormationsReq, percent: u8) -> Result<()> {
+        if common::signaled() {
+            return Err(CliError::bail("the rollout was interrupted"));
+        }
+        let cfgs = shifted_configurations(self.old, self.uuid, percent)?;
+        req.set_active_configurations(&cfgs, false)
+            .context("Context: failed to shift traffic to the new configuration\n")?;
+        self.shifted = true;
+        // The intermediate steps are not worth rolling back to
+        if percent == 100 {
+            ctx.db
+                .formations
+                .record_active_configurations(self.name, &weights(&cfgs));
+            ctx.persist_formations()?;
+            self.recorded = true;
+        }
+        cli_println!("Shifted {percent}% of traffic to the new configuration");
+
+        self.wait()?;
+        let status = configuration_status(req, self.name, self.uuid, self.model)?;
+        let unhealthy = status.unhealthy_flights();
+        if !unhealthy.is_empty() {
+            let name = self.name;
+            return Err(CliErrorKind::FormationRolloutFailed(
+                unhealthy.into_iter().map(ToOwned::to_owned).collect(),
+            )
+            .into_err())
+            .context("(hint: see the status of each Flight with '")
+            .with_color_context(|| (Color::Green, format!("seaplane formation status {name}")))
+            .context("')\n");
+        }
+        cli_println!("  {}", status.progress());
+        Ok(())
+    }
+
+    /// Waits for the interval, failing early if the rollout is interrupted
+    fn wait(&self) -> Result<()> {
+        let until = Instant::now() + self.interval;
+        loop {
+            if common::signaled() {
+                return Err(CliError::bail("the rollout was interrupted"));
+            }
+            let left = until.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return Ok(());
+            }
+            thread::sleep(left.min(SIGNAL_CHECK_INTERVAL));
+        }
+    }
+
+    /// Gives all of the traffic back to the previously active configurations
+    fn restore(&self, ctx: &mut Ctx, req: &mut FormationsReq) -> Result<()> {
+        req.set_active_configurations(self.old, false)
+            .context("Context: failed to restore the previously active configurations\n")?;
+        // Otherwise the history still ends the same way it did before the rollout
+        if self.recorded {
+            ctx.db
+                .formations
+                .record_active_configurations(self.name, &weights(self.old));
+            ctx.persist_formations()?;
+        }
+        cli_println!("Restored the previously active configurations");
+        Ok(())
+    }
+}
+
+impl CliCommand for SeaplaneFormationRollout {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let name = ctx.args.name_id.as_ref().unwrap().clone();
+        let indices = ctx.db.formations.formation_indices_of_matches(&name);
+        let idx = match indices.as_slice() {
+            [] => return errors::no_matching_item(name, false, false),
+            [idx] => *idx,
+            _ => return errors::ambiguous_item(name, false),
+        };
+        // re unwrap: the index came from Formations so it has to be valid
+        let formation = ctx.db.formations.get_formation(idx).unwrap();
+        let formation_name = formation.name.clone().unwrap();
+        let id = match formation.local_only_configs().as_slice() {
+            [id] => *id,
+            [] => {
+                return Err(CliErrorKind::OneOff(format!(
+                "Formation Plan '{formation_name}' does not have a new configuration to roll out"
+            ))
+                .into_err())
+                .context("(hint: create one with '")
+                .with_color_context(|| {
+                    (Color::Green, format!("seaplane formation edit {formation_name}"))
+                })
+                .context("')\n")
+            }
+            _ => {
+                return Err(CliErrorKind::OneOff(format!(
+                    "Formation Plan '{formation_name}' has more than one new configuration"
+                ))
+                .into_err())
+                .context("(hint: only a single configuration can be rolled out at a time)\n")
+            }
+        };
+        // re unwrap: the ID came from our own Formations
+        let model = ctx
+            .db
+            .formations
+            .get_configuration(&id)
+            .unwrap()
+            .model
+            .clone();
+        validate_configuration(&model)
+            .context("(hint: fix the Formation Plan with '")
+            .with_color_context(|| {
+                (Color::Green, format!("seaplane formation edit {formation_name}"))
+            })
+            .context("')\n")?;
+
+        let (steps, interval) = {
+            let fctx = ctx.formation_ctx.get_or_init();
+            (fctx.rollout_steps.clone(), fctx.interval)
+        };
+
+        let mut req = FormationsReq::new_delay_token(ctx)?;
+        req.set_name(&formation_name)?;
+
+        let old = req.get_active_configurations()?;
+        if old.is_empty() {
+            return Err(CliErrorKind::OneOff(format!(
+                "Formation Instance '{formation_name}' does not have any active configurations"
+            ))
+            .into_err())
+            .context("(hint: there is no traffic to shift, use '")
+            .with_color_context(|| {
+                (Color::Green, format!("seaplane formation launch {formation_name}"))
+            })
+            .context("' instead)\n");
+        }
+
+        let uuid = req.add_configuration(&model, false)?;
+        ctx.db.formations.add_uuid(&id, uuid);
+        ctx.db.formations.add_grounded_by_name(&formation_name, id);
+        ctx.persist_formations()?;
+        cli_print!("Uploaded new configuration ");
+        cli_print!(@Green, "{uuid}");
+        cli_println!(" of Formation Instance '{formation_name}'");
+
+        common::catch_signals();
+        let mut rollout = Rollout {
+            name: &formation_name,
+            old: &old,
+            uuid,
+            model: &model,
+            interval,
+            shifted: false,
+            recorded: false,
+        };
+        for percent in steps {
+            if let Err(e) = rollout.step(ctx, &mut req, percent) {
+                // However the rollout failed, any traffic already shifted has to be given back
+                if rollout.shifted {
+                    if let Err(restore_err) = rollout.restore(ctx, &mut req) {
+                        // Why the rollout failed matters just as much as why it couldn't be undone
+                        e.print();
+                        return Err(restore_err.context(
+                            "(hint: this happened restoring the previously active configurations after the error above)\n",
+                        ));
+                    }
+                }
+                return Err(e);
+            }
+        }
+
+        // The previously active configurations now only remain uploaded
+        for old_uuid in old.iter().map(|cfg| *cfg.uuid()) {
+            if let Some(old_id) = ctx
+                .db
+                .formations
+                .get_configuration_by_uuid(old_uuid)
+                .map(|cfg| cfg.id)
+            {
+                ctx.db
+                    .formations
+                    .add_grounded_by_name(&formation_name, old_id);
+            }
+        }
+        ctx.db.formations.add_in_air_by_name(&formation_name, id);
+        ctx.persist_formations()?;
+
+        cli_print!("Successfully rolled out configuration ");
+        cli_print!(@Green, "{uuid}");
+        cli_println!(" to Formation Instance '{formation_name}'");
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.args.name_id = matches
+            .get_one::<String>("formation")
+            .map(ToOwned::to_owned);
+        let fctx = ctx.formation_ctx.get_mut_or_init();
+        let mut steps: Vec<u8> = matches
+            .get_many::<u8>("steps")
+            .unwrap_or_default()
+            .copied()
+            .collect();
+        steps.sort_unstable();
+        steps.dedup();
+        // Always finish with all of the traffic going to the new configuration
+        if steps.last() != Some(&100) {
+            steps.push(100);
+        }
+        fctx.rollout_steps = steps;
+        // re unwrap: the argument has a default value
+        fctx.interval = Duration::from_secs(*matches.get_one::<u64>("interval").unwrap());
+        Ok(())
+    }
+}
@@ -1,3 +1,6 @@
+use std::{thread, time::Duration};
+
+use chrono::{SecondsFormat, Utc};
 use clap::{value_parser, ArgMatches, Command};
 
 use crate::{
@@ -9,7 +12,7 @@ use crate::{
     },
     error::Result,
     ops::formation::FormationStatus,
-    printer::{Output, Pb},
+    printer::{stdout_is_tty, Output, Pb},
     Ctx, OutputFormat,
 };
 
@@ -17,7 +20,19 @@ static LONG_ABOUT: &str = "Show the status of a remote Formation Instance
 
 This command will display the status of one or more Formation Instances such as how many actual
 containers are running compared to the minimum and maximums per Flight Plan that the configuration
-defines.";
+defines.
+
+A Flight is shown as crash looping when at least 3 of its containers stopped in the last 10
+minutes, either with a non-zero exit code or after running for less than a minute, along with
+the most common of those exit codes.
+
+With '--watch' the Formation Instances are checked again every '--interval' seconds until
+interrupted. When standard output is a terminal the status is redrawn in place with any changed
+container counts highlighted, otherwise each change is printed as a timestamped line after the
+initial status. With '--format=json' one JSON object is printed per Flight whose containers
+changed. Network failures and the Seaplane API being temporarily unavailable only print a warning
+(to standard error with '--format=json'), the Formation Instances are checked again at the next
+interval.";
 
 #[derive(Copy, Clone, Debug)]
 pub struct SeaplaneFormationStatus;
@@ -39,6 +54,114 @@ impl SeaplaneFormationStatus {
                     .help("Change the output format"),
             )
             .arg(arg!(--("no-fetch")).help("Skip fetching and synchronizing of remote instances"))
+            .arg(
+                arg!(--watch - ('w'))
+                    .help("Keep checking the Formation Instances and show changes as they happen"),
+            )
+            .arg(
+                arg!(--interval =["SECS"=>"5"])
+                    .requires("watch")
+                    .value_parser(value_parser!(u64).range(1..))
+                    .help("Check the Formation Instances every SECS seconds (requires --watch)"),
+            )
+    }
+}
+
+/// Builds the status of each of the named Formation Instances from their containers
+fn formation_statuses(
+    ctx: &Ctx,
+    req: &mut FormationsReq,
+    names: &[&str],
+    pb: Option<&Pb>,
+) -> Result<Vec<FormationStatus>> {
+    let mut statuses: Vec<FormationStatus> = Vec::new();
+    for &name in names {
+        if let Some(pb) = pb {
+            pb.set_message(format!("Gathering {name} container info..."));
+        }
+        req.set_name(name)?;
+        let mut f_status = FormationStatus::new(name);
+        for container in req.get_containers()?.iter() {
+            if let Some(cfg) = ctx
+                .db
+                .formations
+                .get_configuration_by_uuid(container.configuration_id)
+            {
+                if let Some(flight) = cfg.get_flight(&container.flight_name) {
+                    f_status.add_container(container, flight.minimum(), flight.maximum());
+                }
+            }
+        }
+        // TODO it stinks that we have to do this here and it's not automatic
+        f_status.update_status();
+        statuses.push(f_status);
+    }
+
+    Ok(statuses)
+}
+
+/// Checks the Formation Instances every `interval` until interrupted, printing changes in the
+/// requested output format
+fn watch(ctx: &Ctx, req: &mut FormationsReq, names: &[&str], interval: Duration) -> Result<()> {
+    let tty = stdout_is_tty();
+    let mut prev: Option<Vec<FormationStatus>> = None;
+    loop {
+        let statuses = match formation_statuses(ctx, req, names, None) {
+            Ok(statuses) => statuses,
+            // Keep watching through network failures and the API being briefly unavailable
+            Err(e) if e.is_transient() => {
+                let secs = interval.as_secs();
+                if ctx.args.out_format == OutputFormat::Json {
+                    // Keep stdout to JSON events only
+                    cli_eprint!(@Yellow, "warn: ");
+                    cli_eprintln!(
+                        "failed to check the Formation Instances, trying again in {secs}s"
+                    );
+                } else {
+                    cli_warnln!("failed to check the Formation Instances, trying again in {secs}s");
+                }
+                thread::sleep(interval);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let timestamp = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+        // The statuses are always in the order of `names` so the previous status of a Formation
+        // shares its index
+        let prev_status = |i: usize| prev.as_ref().map(|p: &Vec<FormationStatus>| &p[i]);
+
+        match ctx.args.out_format {
+            OutputFormat::Json => {
+                for (i, status) in statuses.iter().enumerate() {
+                    for change in status.changes(prev_status(i), &timestamp) {
+                        cli_println!("{}", serde_json::to_string(&change)?);
+                    }
+                }
+            }
+            OutputFormat::Table if tty => {
+                // Clear the screen and move the cursor to the top left corner
+                cli_print!("\x1b[2J\x1b[H");
+                cli_println!("Every {}s: {timestamp}", interval.as_secs());
+                cli_println!("");
+                for (i, status) in statuses.iter().enumerate() {
+                    status.print_tree(prev_status(i));
+                }
+            }
+            OutputFormat::Table => {
+                if prev.is_none() {
+                    statuses.print_table(ctx)?;
+                } else {
+                    for (i, status) in statuses.iter().enumerate() {
+                        for change in status.changes(prev_status(i), &timestamp) {
+                            change.print();
+                        }
+                    }
+                }
+            }
+        }
+
+        prev = Some(statuses);
+        thread::sleep(interval);
     }
 }
 
@@ -60,37 +183,21 @@ impl CliCommand for SeaplaneFormationStatus {
             ctx.args.stateless = old_stateless;
         }
 
-        let pb = Pb::new(ctx);
-
         let names = if let Some(name) = ctx.args.name_id.as_deref() {
             vec![name]
         } else {
             ctx.db.formations.remote_names()
         };
 
-        let mut statuses: Vec<FormationStatus> = Vec::new();
-
         let mut req = FormationsReq::new_delay_token(ctx)?;
-        for name in names {
-            pb.set_message(format!("Gathering {name} container info..."));
-            req.set_name(name)?;
-            let mut f_status = FormationStatus::new(name);
-            for container in req.get_containers()?.iter() {
-                if let Some(cfg) = ctx
-                    .db
-                    .formations
-                    .get_configuration_by_uuid(container.configuration_id)
-                {
-                    if let Some(flight) = cfg.get_flight(&container.flight_name) {
-                        f_status.add_container(container, flight.minimum(), flight.maximum());
-                    }
-                }
-            }
-            // TODO it stinks that we have to do this here and it's not automatic
-            f_status.update_status();
-            statuses.push(f_status);
+
+        let fctx = ctx.formation_ctx.get_or_init();
+        if fctx.watch {
+            return watch(ctx, &mut req, &names, fctx.interval);
         }
 
+        let pb = Pb::new(ctx);
+        let statuses = formation_statuses(ctx, &mut req, &names, Some(&pb))?;
         pb.finish_and_clear();
 
         match ctx.args.out_format {
@@ -107,6 +214,10 @@ impl CliCommand for SeaplaneFormationStatus {
             .get_one::<String>("formation")
             .map(ToOwned::to_owned);
         ctx.args.fetch = !matches.contains_id("no-fetch");
+        let fctx = ctx.formation_ctx.get_mut_or_init();
+        fctx.watch = matches.contains_id("watch");
+        // re unwrap: the argument has a default value
+        fctx.interval = Duration::from_secs(*matches.get_one::<u64>("interval").unwrap());
         Ok(())
     }
 }
@@ -0,0 +1,187 @@
+use std::{
+    cmp, thread,
+    time::{Duration, Instant},
+};
+
+use clap::{value_parser, ArgMatches, Command};
+
+use crate::{
+    api::FormationsReq,
+    cli::{validator::validate_formation_name, CliCommand},
+    context::Ctx,
+    error::{CliErrorKind, Context, Result},
+    ops::formation::{FormationStatus, WaitFor},
+    printer::Color,
+};
+
+static LONG_ABOUT: &str = "Wait for a remote Formation Instance to become up or down
+
+A Formation Instance is up once every Flight of each of its active configurations has at least its
+minimum number of containers running (and always at least one), and down once no Flight has any
+containers running or starting.
+
+The containers of the Formation Instance are checked every '--interval' seconds, printing the
+running and minimum number of containers of each Flight whenever they change. With '--timeout'
+this command exits with an error if the requested state is not reached within that many seconds,
+which makes it suitable as a gate in CI pipelines. Network failures and the Seaplane API being
+temporarily unavailable only print a warning, the containers are checked again at the next
+interval.";
+
+/// The `--timeout` argument shared with `formation launch --wait`
+pub fn timeout() -> clap::Arg<'static> {
+    arg!(--timeout = ["SECS"])
+        .value_parser(value_parser!(u64))
+        .help("Give up waiting for the Formation Instance after SECS seconds")
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneFormationWait;
+
+impl SeaplaneFormationWait {
+    pub fn command() -> Command<'static> {
+        Command::new("wait")
+            .about("Wait for a remote Formation Instance to become up or down")
+            .long_about(LONG_ABOUT)
+            .arg(
+                arg!(name =["NAME"] required)
+                    .validator(validate_formation_name)
+                    .help("The name of the Formation Instance to wait for"),
+            )
+            .arg(
+                arg!(--("for") =["STATE"=>"up"])
+                    .value_parser(value_parser!(WaitFor))
+                    .help("The state to wait for the Formation Instance to reach"),
+            )
+            .arg(timeout())
+            .arg(
+                arg!(--interval =["SECS"=>"5"])
+                    .value_parser(value_parser!(u64).range(1..))
+                    .help("Check the Formation Instance every SECS seconds"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneFormationWait {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        // re unwrap: the name is a required argument
+        let name = ctx.args.name_id.as_ref().unwrap();
+        wait_for_formation(ctx, name)
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.args.name_id = matches.get_one::<String>("name").map(ToOwned::to_owned);
+        let fctx = ctx.formation_ctx.get_mut_or_init();
+        fctx.wait_for = matches.get_one::<WaitFor>("for").copied();
+        fctx.wait_timeout = matches
+            .get_one::<u64>("timeout")
+            .copied()
+            .map(Duration::from_secs);
+        // re unwrap: the argument has a default value
+        fctx.interval = Duration::from_secs(*matches.get_one::<u64>("interval").unwrap());
+        Ok(())
+    }
+}
+
+/// Checks the containers of the remote Formation Instance `name` until it reaches the state set in
+/// the Formation context, or the optional timeout expires
+pub fn wait_for_formation(ctx: &Ctx, name: &str) -> Result<()> {
+    let (target, timeout, interval) = {
+        let fctx = ctx.formation_ctx.get_or_init();
+        (fctx.wait_for.unwrap_or(WaitFor::Up), fctx.wait_timeout, fctx.interval)
+    };
+
+    let mut req = FormationsReq::new_delay_token(ctx)?;
+    req.set_name(name)?;
+
+    // Only the configurations receiving traffic have to be up
+    let mut active = Vec::new();
+    for cfg in req.get_active_configurations()?.iter() {
+        active.push((*cfg.uuid(), req.get_configuration(*cfg.uuid())?));
+    }
+    if target == WaitFor::Up && active.is_empty() {
+        return Err(CliErrorKind::OneOff(format!(
+            "Formation Instance '{name}' does not have any active configurations"
+        ))
+        .into_err())
+        .context("(hint: launch it with '")
+        .with_color_context(|| (Color::Green, format!("seaplane formation launch {name}")))
+        .context("')\n");
+    }
+
+    cli_print!("Waiting for Formation Instance '");
+    cli_print!(@Green, "{name}");
+    cli_println!("' to be {target}...");
+
+    let start = Instant::now();
+    let mut last_progress = String::new();
+    loop {
+        let mut status = FormationStatus::new(name);
+        if target == WaitFor::Up {
+            for (uuid, model) in &active {
+                status.add_configuration(*uuid, model);
+            }
+        }
+        match req.get_containers() {
+            Ok(containers) => {
+                for container in containers.iter() {
+                    let flight = active
+                        .iter()
+                        .find(|(uuid, _)| *uuid == container.configuration_id)
+                        .and_then(|(_, model)| {
+                            model
+                                .flights()
+                                .iter()
+                                .find(|f| f.name() == container.flight_name)
+                        });
+                    match flight {
+                        Some(flight) => {
+                            status.add_container(container, flight.minimum(), flight.maximum())
+                        }
+                        // Containers of inactive configurations still have to stop before the
+                        // Formation Instance is down
+                        None if target == WaitFor::Down => status.add_container(container, 0, None),
+                        None => (),
+                    }
+                }
+                status.update_status();
+
+                let progress = status.progress();
+                if !progress.is_empty() && progress != last_progress {
+                    cli_println!("  {progress}");
+                    last_progress = progress;
+                }
+
+                if status.reached(target) {
+                    cli_print!("Formation Instance '");
+                    cli_print!(@Green, "{name}");
+                    cli_print!("' is ");
+                    cli_println!(@Green, "{target}");
+                    return Ok(());
+                }
+            }
+            // A single failed check shouldn't end a wait that may last several minutes, the
+            // timeout still applies
+            Err(e) if e.is_transient() => {
+                cli_warnln!(
+                    "failed to check the containers of Formation Instance '{name}', trying again"
+                );
+            }
+            Err(e) => return Err(e),
+        }
+
+        let mut sleep = interval;
+        if let Some(timeout) = timeout {
+            let elapsed = start.elapsed();
+            if elapsed >= timeout {
+                return Err(CliErrorKind::FormationWaitTimeout(name.into(), target).into_err())
+                    .context("(hint: see the status of each Flight with '")
+                    .with_color_context(|| {
+                        (Color::Green, format!("seaplane formation status {name}"))
+                    })
+                    .context("')\n");
+            }
+            sleep = cmp::min(sleep, timeout - elapsed);
+        }
+        thread::sleep(sleep);
+    }
+}
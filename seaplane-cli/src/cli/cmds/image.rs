@@ -1,16 +1,74 @@
-use clap::{ArgMatches, Command};
+mod manifest;
+mod pin;
+mod resolve;
+mod tags;
 
-use crate::{cli::CliCommand, context::Ctx, error::Result};
+use clap::{value_parser, Arg, ArgMatches, Command};
+
+pub use self::{
+    manifest::SeaplaneImageManifest, pin::SeaplaneImagePin, resolve::SeaplaneImageResolve,
+    tags::SeaplaneImageTags,
+};
+use crate::{cli::CliCommand, printer::OutputFormat};
+
+static LONG_ABOUT: &str =
+    "Inspect container images in OCI registries and pin Flight Plans to image digests
+
+When neither a tag nor a digest is given the 'latest' tag is used. Registries on this machine
+(such as 'localhost:5000') are reached over plain HTTP, which allows using a local registry.
+
+Registries are accessed anonymously unless credentials for them are given in the
+SEAPLANE_REGISTRY_CREDENTIALS environment variable as comma separated DOMAIN=USERNAME:PASSWORD
+entries (i.e. 'registry.cplane.cloud=me:secret'). Each registry only ever receives its own
+credentials. The same applies to the '--pin-digest', '--check-arch' and '--infer-arch' flags of
+'seaplane flight plan', 'seaplane flight edit' and 'seaplane formation launch'.";
+
+/// A newtype wrapper to enforce where the ArgMatches came from which reduces errors in checking if
+/// values of arguments were used or not. i.e. `seaplane formation create` may not have the same
+/// arguments as `seaplane account token` even though both produce an `ArgMatches`.
+#[allow(missing_debug_implementations)]
+pub struct SeaplaneImageCommonArgMatches<'a>(pub &'a ArgMatches);
+
+pub fn image() -> Arg<'static> {
+    arg!(image =["IMAGE"] required).help("The container image reference")
+}
 
 #[derive(Copy, Clone, Debug)]
 pub struct SeaplaneImage;
 
 impl SeaplaneImage {
-    pub fn command() -> Command<'static> { Command::new("image").visible_alias("img") }
+    pub fn command() -> Command<'static> {
+        Command::new("image")
+            .visible_alias("img")
+            .about(
+                "Inspect container images in OCI registries and pin Flight Plans to image digests",
+            )
+            .long_about(LONG_ABOUT)
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .arg(
+                arg!(--format =["FORMAT"=>"table"] global)
+                    .help("Change the output format")
+                    .value_parser(value_parser!(OutputFormat)),
+            )
+            .subcommand(SeaplaneImageResolve::command())
+            .subcommand(SeaplaneImageTags::command())
+            .subcommand(SeaplaneImageManifest::command())
+            .subcommand(SeaplaneImagePin::command())
+    }
 }
+
 impl CliCommand for SeaplaneImage {
-    fn run(&self, _ctx: &mut Ctx) -> Result<()> { todo!("SeaplaneImage::run") }
-    fn update_ctx(&self, _matches: &ArgMatches, _ctx: &mut Ctx) -> Result<()> {
-        todo!("SeaplaneImage::update_ctx")
+    fn next_subcmd<'a>(
+        &self,
+        matches: &'a ArgMatches,
+    ) -> Option<(Box<dyn CliCommand>, &'a ArgMatches)> {
+        match &matches.subcommand() {
+            Some(("resolve", m)) => Some((Box::new(SeaplaneImageResolve), m)),
+            Some(("tags", m)) => Some((Box::new(SeaplaneImageTags), m)),
+            Some(("manifest", m)) => Some((Box::new(SeaplaneImageManifest), m)),
+            Some(("pin", m)) => Some((Box::new(SeaplaneImagePin), m)),
+            _ => None,
+        }
     }
 }
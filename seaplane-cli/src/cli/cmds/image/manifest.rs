@@ -0,0 +1,62 @@
+use clap::{ArgMatches, Command};
+
+use crate::{
+    api::RegistryReq,
+    cli::{
+        cmds::image::{image, SeaplaneImageCommonArgMatches},
+        specs::IMAGE_SPEC,
+        CliCommand,
+    },
+    context::{Ctx, ImageCtx},
+    error::Result,
+    ops::image::Manifest,
+    printer::{Output, OutputFormat},
+};
+
+static LONG_ABOUT: &str =
+    "Show the manifest of a container image and the platforms it is available for
+
+Images built for several platforms have an index listing a manifest per platform. Images built
+for a single platform have a single manifest, whose platform is read from the image
+configuration.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneImageManifest;
+
+impl SeaplaneImageManifest {
+    pub fn command() -> Command<'static> {
+        Command::new("manifest")
+            .visible_alias("inspect")
+            .about("Show the manifest of a container image and the platforms it is available for")
+            .long_about(LONG_ABOUT)
+            .after_help(IMAGE_SPEC)
+            .arg(image())
+            .arg(
+                arg!(--("no-header") | ("no-heading") | ("no-headers"))
+                    .help("Omit the header when printing with `--format=table`"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneImageManifest {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let image = ctx.image_ctx.get_or_init().reference.clone().unwrap();
+        let manifest = Manifest { manifest: RegistryReq::new(ctx, &image)?.get_manifest()?, image };
+
+        match ctx.args.out_format {
+            OutputFormat::Json => manifest.print_json(ctx)?,
+            OutputFormat::Table => manifest.print_table(ctx)?,
+        }
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.image_ctx.init(ImageCtx::from_image_common(
+            &SeaplaneImageCommonArgMatches(matches),
+            &ctx.registry,
+        )?);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        ctx.image_ctx.get_mut_or_init().no_header = matches.contains_id("no-header");
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+use clap::{ArgMatches, Command};
+
+use crate::{
+    cli::{
+        errors,
+        validator::{validate_flight_name, validate_name_id},
+        CliCommand,
+    },
+    context::Ctx,
+    error::Result,
+    ops::image::pin_image,
+};
+
+static LONG_ABOUT: &str = "Pin local Flight Plans to the digest of their container image
+
+The image tag of a Flight Plan is resolved to the digest it currently points to, and the digest
+is added to the image reference of the Flight Plan. A pinned Flight always runs the exact same
+image, even if the tag is moved to point to another image later on.
+
+Flight Plans which are already pinned are left as they are unless --update is used, which
+resolves their tag again.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneImagePin;
+
+impl SeaplaneImagePin {
+    pub fn command() -> Command<'static> {
+        let validator = |s: &str| validate_name_id(validate_flight_name, s);
+        Command::new("pin")
+            .override_usage("seaplane image pin <NAME|ID> [OPTIONS]")
+            .about("Pin local Flight Plans to the digest of their container image")
+            .long_about(LONG_ABOUT)
+            .arg(
+                arg!(flight required =["NAME|ID"])
+                    .validator(validator)
+                    .help("The name or ID of the Flight Plan to pin, must be unambiguous"),
+            )
+            .arg(
+                arg!(--all - ('a'))
+                    .help("Pin all matching Flight Plans even when the name or ID is ambiguous"),
+            )
+            .arg(
+                arg!(--update - ('u'))
+                    .help("Resolve the image tag again for Flight Plans which are already pinned"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneImagePin {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let name_id = ctx.args.name_id.clone().unwrap();
+        let indices = if ctx.args.all {
+            ctx.db.flights.indices_of_left_matches(&name_id)
+        } else {
+            ctx.db.flights.indices_of_matches(&name_id)
+        };
+
+        match indices.len() {
+            0 => errors::no_matching_item(name_id, false, ctx.args.all)?,
+            1 => (),
+            _ => {
+                if !ctx.args.all {
+                    errors::ambiguous_item(name_id, true)?;
+                }
+            }
+        }
+
+        let update = ctx.image_ctx.get_or_init().update;
+        let mut pinned = 0;
+        for idx in indices {
+            let flight = &ctx.db.flights.iter().nth(idx).unwrap().model;
+            if flight.image().digest.is_some() && !update {
+                cli_println!(
+                    "Flight Plan {} is already pinned to {}",
+                    flight.name(),
+                    flight.image()
+                );
+                continue;
+            }
+
+            let image = pin_image(ctx, flight.image())?;
+            let flight = &mut ctx.db.flights.get_mut(idx).unwrap().model;
+            cli_println!("Pinned Flight Plan {} to {image}", flight.name());
+            flight.set_image(image);
+            pinned += 1;
+        }
+
+        ctx.persist_flights()?;
+
+        if !ctx.internal_run {
+            cli_println!(
+                "\nSuccessfully pinned {pinned} item{}",
+                if pinned == 1 { "" } else { "s" }
+            );
+        }
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.args.all = matches.contains_id("all");
+        ctx.args.name_id = matches.get_one::<String>("flight").map(ToOwned::to_owned);
+        ctx.image_ctx.get_mut_or_init().update = matches.contains_id("update");
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use clap::{ArgMatches, Command};
+
+use crate::{
+    api::RegistryReq,
+    cli::{
+        cmds::image::{image, SeaplaneImageCommonArgMatches},
+        specs::IMAGE_SPEC,
+        CliCommand,
+    },
+    context::{Ctx, ImageCtx},
+    error::Result,
+    ops::image::ResolvedImage,
+    printer::{Output, OutputFormat},
+};
+
+static LONG_ABOUT: &str = "Resolve a container image reference to the digest it points to
+
+The digest identifies the exact image contents, unlike a tag which may be moved to point to
+other contents at any time.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneImageResolve;
+
+impl SeaplaneImageResolve {
+    pub fn command() -> Command<'static> {
+        Command::new("resolve")
+            .visible_alias("digest")
+            .about("Resolve a container image reference to the digest it points to")
+            .long_about(LONG_ABOUT)
+            .after_help(IMAGE_SPEC)
+            .arg(image())
+    }
+}
+
+impl CliCommand for SeaplaneImageResolve {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let image = ctx.image_ctx.get_or_init().reference.clone().unwrap();
+        let resolved =
+            ResolvedImage { digest: RegistryReq::new(ctx, &image)?.resolve_digest()?, image };
+
+        match ctx.args.out_format {
+            OutputFormat::Json => resolved.print_json(ctx)?,
+            OutputFormat::Table => resolved.print_table(ctx)?,
+        }
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.image_ctx.init(ImageCtx::from_image_common(
+            &SeaplaneImageCommonArgMatches(matches),
+            &ctx.registry,
+        )?);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use clap::{ArgMatches, Command};
+
+use crate::{
+    api::RegistryReq,
+    cli::{
+        cmds::image::{image, SeaplaneImageCommonArgMatches},
+        specs::IMAGE_SPEC,
+        CliCommand,
+    },
+    context::{Ctx, ImageCtx},
+    error::Result,
+    ops::image::ImageTags,
+    printer::{Output, OutputFormat},
+};
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneImageTags;
+
+impl SeaplaneImageTags {
+    pub fn command() -> Command<'static> {
+        Command::new("tags")
+            .about("List the tags of a container image repository")
+            .after_help(IMAGE_SPEC)
+            .arg(image().help("The container image reference, any tag or digest is ignored"))
+            .arg(
+                arg!(--("no-header") | ("no-heading") | ("no-headers"))
+                    .help("Omit the header when printing with `--format=table`"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneImageTags {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let image = ctx.image_ctx.get_or_init().reference.clone().unwrap();
+        let tags = ImageTags {
+            image: format!("{}/{}", image.domain(), image.path()),
+            tags: RegistryReq::new(ctx, &image)?.get_tags()?,
+        };
+
+        match ctx.args.out_format {
+            OutputFormat::Json => tags.print_json(ctx)?,
+            OutputFormat::Table => tags.print_table(ctx)?,
+        }
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.image_ctx.init(ImageCtx::from_image_common(
+            &SeaplaneImageCommonArgMatches(matches),
+            &ctx.registry,
+        )?);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        ctx.image_ctx.get_mut_or_init().no_header = matches.contains_id("no-header");
+        Ok(())
+    }
+}
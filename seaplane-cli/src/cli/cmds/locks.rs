@@ -1,14 +1,29 @@
 mod acquire;
-mod common;
+pub(crate) mod common;
+mod elect;
+mod force_release;
+mod hold;
+mod inspect;
 mod list;
 mod release;
 mod renew;
+mod semaphore;
 
 use clap::{value_parser, ArgMatches, Command};
 
 pub use self::{
-    acquire::SeaplaneLocksAcquire, common::SeaplaneLocksCommonArgMatches, list::SeaplaneLocksList,
-    release::SeaplaneLocksRelease, renew::SeaplaneLocksRenew,
+    acquire::SeaplaneLocksAcquire,
+    common::SeaplaneLocksCommonArgMatches,
+    elect::SeaplaneLocksElect,
+    force_release::SeaplaneLocksForceRelease,
+    hold::SeaplaneLocksHold,
+    inspect::SeaplaneLocksInspect,
+    list::SeaplaneLocksList,
+    release::SeaplaneLocksRelease,
+    renew::SeaplaneLocksRenew,
+    semaphore::{
+        SeaplaneLocksSemaphore, SeaplaneLocksSemaphoreAcquire, SeaplaneLocksSemaphoreHold,
+    },
 };
 use crate::{cli::CliCommand, printer::OutputFormat};
 
@@ -27,9 +42,14 @@ impl SeaplaneLocks {
                     .value_parser(value_parser!(OutputFormat)),
             )
             .subcommand(SeaplaneLocksList::command())
+            .subcommand(SeaplaneLocksInspect::command())
             .subcommand(SeaplaneLocksAcquire::command())
             .subcommand(SeaplaneLocksRelease::command())
+            .subcommand(SeaplaneLocksForceRelease::command())
             .subcommand(SeaplaneLocksRenew::command())
+            .subcommand(SeaplaneLocksHold::command())
+            .subcommand(SeaplaneLocksElect::command())
+            .subcommand(SeaplaneLocksSemaphore::command())
     }
 }
 
@@ -40,9 +60,14 @@ impl CliCommand for SeaplaneLocks {
     ) -> Option<(Box<dyn CliCommand>, &'a ArgMatches)> {
         match &matches.subcommand() {
             Some(("list", m)) => Some((Box::new(SeaplaneLocksList), m)),
+            Some(("inspect", m)) => Some((Box::new(SeaplaneLocksInspect), m)),
             Some(("acquire", m)) => Some((Box::new(SeaplaneLocksAcquire), m)),
             Some(("release", m)) => Some((Box::new(SeaplaneLocksRelease), m)),
+            Some(("force-release", m)) => Some((Box::new(SeaplaneLocksForceRelease), m)),
             Some(("renew", m)) => Some((Box::new(SeaplaneLocksRenew), m)),
+            Some(("hold", m)) => Some((Box::new(SeaplaneLocksHold), m)),
+            Some(("elect", m)) => Some((Box::new(SeaplaneLocksElect), m)),
+            Some(("semaphore", m)) => Some((Box::new(SeaplaneLocksSemaphore), m)),
             _ => None,
         }
     }
@@ -1,4 +1,6 @@
-use clap::{ArgMatches, Command};
+use std::time::Duration;
+
+use clap::{value_parser, ArgMatches, Command};
 
 use crate::{
     api::LocksReq,
@@ -24,10 +26,13 @@ impl SeaplaneLocksAcquire {
             .arg(common::lock_name())
             .arg(common::ttl())
             .arg(common::base64())
+            .arg(common::client_id())
+            .arg(arg!(--wait).help("Wait for the lock to be released if it is currently held"))
             .arg(
-                arg!(--("client-id") - ('L') =["STRING"] required).help(
-                    "Client-chosen identifier stored with the lock for informational purposes",
-                ),
+                arg!(--timeout = ["SECS"])
+                    .requires("wait")
+                    .value_parser(value_parser!(u64))
+                    .help("Give up waiting for the lock after SECS seconds (requires --wait)"),
             )
     }
 }
@@ -42,7 +47,15 @@ impl CliCommand for SeaplaneLocksAcquire {
 
         let ttl = locksctx.ttl.as_ref().unwrap();
         let client_id: &str = locksctx.client_id.as_ref().unwrap();
-        let held_lock_model = req.acquire(*ttl, client_id)?;
+        let held_lock_model = if locksctx.wait {
+            req.acquire_blocking(*ttl, client_id, locksctx.timeout, |info| {
+                cli_eprint!("Waiting for the lock, currently held by ");
+                cli_eprint!(@Green, "{}", info.info.client_id);
+                cli_eprintln!(" ({})", info.info.ip);
+            })?
+        } else {
+            req.acquire(*ttl, client_id)?
+        };
 
         let held_lock = HeldLock {
             lock_id: held_lock_model.id().encoded().to_owned(),
@@ -62,10 +75,15 @@ impl CliCommand for SeaplaneLocksAcquire {
             .init(LocksCtx::from_locks_common(&SeaplaneLocksCommonArgMatches(matches))?);
 
         ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
-        let mut locksctx = ctx.locks_ctx.get_mut().unwrap();
+        let locksctx = ctx.locks_ctx.get_mut().unwrap();
         locksctx.ttl = matches.get_one::<u32>("ttl").copied();
         locksctx.base64 = matches.contains_id("base64");
         locksctx.client_id = Some(matches.get_one::<String>("client-id").unwrap().to_string());
+        locksctx.wait = matches.contains_id("wait");
+        locksctx.timeout = matches
+            .get_one::<u64>("timeout")
+            .copied()
+            .map(Duration::from_secs);
 
         Ok(())
     }
@@ -1,3 +1,8 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Once,
+};
+
 use clap::{value_parser, Arg, ArgMatches};
 
 const LONG_DECODE: &str = "Decode the lock name before printing it
@@ -5,6 +10,25 @@ const LONG_DECODE: &str = "Decode the lock name before printing it
 Binary values will be written directly to standard output (which may do strange
 things to your terminal)";
 
+/// Set by the signal handler when we've been asked to stop (i.e. SIGINT or SIGTERM)
+static SIGNALED: AtomicBool = AtomicBool::new(false);
+static SIGNAL_HANDLER: Once = Once::new();
+
+/// Installs a handler for SIGINT and SIGTERM (only the first time it's called) so that commands
+/// which hold locks, or otherwise have to clean up, can do so before exiting, and forgets any
+/// previously received signal.
+pub fn catch_signals() {
+    SIGNAL_HANDLER.call_once(|| {
+        if let Err(e) = ctrlc::set_handler(|| SIGNALED.store(true, Ordering::SeqCst)) {
+            cli_warnln!("unable to handle signals, nothing will be cleaned up if interrupted: {e}");
+        }
+    });
+    SIGNALED.store(false, Ordering::SeqCst);
+}
+
+/// Returns `true` if a signal was received since [`catch_signals`] was called
+pub fn signaled() -> bool { SIGNALED.load(Ordering::SeqCst) }
+
 /// A newtype wrapper to enforce where the ArgMatches came from which reduces errors in checking if
 /// values of arguments were used or not. i.e. `seaplane formation create` may not have the same
 /// arguments as `seaplane account token` even though both produce an `ArgMatches`.
@@ -35,6 +59,11 @@ pub fn ttl() -> Arg<'static> {
         .help("The TTL (Time To Live) in seconds, i.e. a positive integer")
 }
 
+pub fn client_id() -> Arg<'static> {
+    arg!(--("client-id") - ('L') =["STRING"] required)
+        .help("Client-chosen identifier stored with the lock for informational purposes")
+}
+
 pub fn lock_id() -> Arg<'static> {
     arg!(--("lock-id") - ('L') =["STRING"] required).help(
         "A valid lock-id can be obtained from a successful acquisition, or listing of the locks",
@@ -0,0 +1,136 @@
+use std::{sync::mpsc::RecvTimeoutError, time::Duration};
+
+use clap::{ArgMatches, Command};
+use seaplane::api::locks::v1::{LeaderElection, LeadershipEvent};
+
+use crate::{
+    api::LocksReq,
+    cli::cmds::locks::{common, common::SeaplaneLocksCommonArgMatches, CliCommand},
+    context::{Ctx, LocksCtx},
+    error::{CliError, Context, Result},
+    ops::locks::LeadershipTransition,
+    printer::{Output, OutputFormat},
+};
+
+/// How often to check if we've been asked to stop
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+static LONG_ABOUT: &str = "Campaign for leadership using a lock
+
+Whichever client holds the lock is the leader. While this client is the leader the
+lock is renewed every third of the TTL, and while it isn't it tries to acquire the lock
+on the same interval. Leadership transitions are printed as they happen.
+
+Network failures and the Seaplane API being temporarily unavailable only print a
+warning while campaigning, any other error (i.e. an invalid lock name or API key)
+exits with an error.
+
+When interrupted (i.e. Ctrl-C) the lock is released if held, so that another client
+can take over right away.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneLocksElect;
+
+impl SeaplaneLocksElect {
+    pub fn command() -> Command<'static> {
+        Command::new("elect")
+            .about("Campaign for leadership using a lock, printing leadership transitions")
+            .long_about(LONG_ABOUT)
+            .arg(common::lock_name())
+            .arg(common::ttl())
+            .arg(common::base64())
+            .arg(common::client_id())
+            .arg(
+                arg!(--("exit-on-loss"))
+                    .help("Exit with an error if leadership is lost instead of campaigning again"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneLocksElect {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let mut req = LocksReq::new(ctx)?;
+        let election = {
+            let locksctx = ctx.locks_ctx.get_or_init();
+            req.set_name(locksctx.lock_name.as_ref().unwrap().to_model())?;
+            // The election outlives any single access token, so it's handed a way to build
+            // requests with new ones
+            LeaderElection::campaign(
+                req.request_factory(),
+                locksctx.ttl.unwrap(),
+                locksctx.client_id.as_ref().unwrap(),
+            )?
+        };
+        let exit_on_loss = ctx.locks_ctx.get_or_init().exit_on_loss;
+
+        common::catch_signals();
+        let res = loop {
+            match election.events().recv_timeout(POLL_INTERVAL) {
+                Ok(LeadershipEvent::Elected(lock)) => {
+                    print_transition(
+                        ctx,
+                        LeadershipTransition::Elected {
+                            lock_id: lock.id().encoded().to_owned(),
+                            sequencer: lock.sequencer(),
+                        },
+                    )?;
+                }
+                Ok(LeadershipEvent::Lost(e)) => {
+                    print_transition(ctx, LeadershipTransition::Lost { reason: e.to_string() })?;
+                    if exit_on_loss {
+                        break Err(CliError::from(e).context(
+                            "\n(hint: leadership was lost because the lock could not be renewed)\n",
+                        ));
+                    }
+                }
+                Ok(LeadershipEvent::Failed(e)) => {
+                    let e = CliError::from(e);
+                    if !e.is_transient() {
+                        break Err(e.context("Context: failed to campaign for leadership\n"));
+                    }
+                    cli_warnln!("failed to campaign for leadership, trying again");
+                }
+                Err(RecvTimeoutError::Timeout) => (),
+                Err(RecvTimeoutError::Disconnected) => {
+                    break Err(CliError::bail("leader election stopped unexpectedly"))
+                }
+            }
+            if common::signaled() {
+                break Ok(());
+            }
+        };
+
+        // Step down even if leadership was lost, which stops campaigning. Errors from the
+        // election take priority over failing to release the lock
+        let was_leader = election.is_leader();
+        let stepped_down = election.step_down();
+        res?;
+        stepped_down?;
+        if was_leader {
+            print_transition(ctx, LeadershipTransition::SteppedDown)?;
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.locks_ctx
+            .init(LocksCtx::from_locks_common(&SeaplaneLocksCommonArgMatches(matches))?);
+
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        let locksctx = ctx.locks_ctx.get_mut().unwrap();
+        locksctx.ttl = matches.get_one::<u32>("ttl").copied();
+        locksctx.base64 = matches.contains_id("base64");
+        locksctx.client_id = Some(matches.get_one::<String>("client-id").unwrap().to_string());
+        locksctx.exit_on_loss = matches.contains_id("exit-on-loss");
+
+        Ok(())
+    }
+}
+
+fn print_transition(ctx: &Ctx, transition: LeadershipTransition) -> Result<()> {
+    match ctx.args.out_format {
+        OutputFormat::Json => transition.print_json(ctx),
+        OutputFormat::Table => transition.print_table(ctx),
+    }
+}
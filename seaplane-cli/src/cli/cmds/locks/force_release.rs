@@ -0,0 +1,77 @@
+use clap::{ArgMatches, Command};
+
+use crate::{
+    api::LocksReq,
+    cli::cmds::locks::{common, common::SeaplaneLocksCommonArgMatches, CliCommand},
+    context::{Ctx, LocksCtx},
+    error::{Context, Result},
+    ops::locks::ListedLock,
+    printer::{Color, OutputFormat},
+};
+
+static LONG_ABOUT: &str = "Release a lock without knowing its lock ID
+
+The lock ID is looked up first, and then used to release the lock on behalf of whichever
+client holds it. This is intended for cleaning up after clients which died while holding
+a lock, and should be used with care since the holder will not be notified that it no
+longer holds the lock.
+
+If the lock is re-acquired by another client in the meantime, the new holder's lock is
+not released.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneLocksForceRelease;
+
+impl SeaplaneLocksForceRelease {
+    pub fn command() -> Command<'static> {
+        Command::new("force-release")
+            .about("Release a lock held by any client, without knowing its lock ID")
+            .long_about(LONG_ABOUT)
+            .arg(common::lock_name())
+            .arg(common::base64())
+    }
+}
+
+impl CliCommand for SeaplaneLocksForceRelease {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let mut req = LocksReq::new(ctx)?;
+        let model_name = ctx
+            .locks_ctx
+            .get_or_init()
+            .lock_name
+            .as_ref()
+            .map(|s| s.to_model());
+
+        req.set_name(model_name.clone().unwrap())?;
+        let lock = ListedLock::from(req.get_lock_info()?);
+
+        req.set_identifiers(model_name, Some(&lock.id))?;
+        req.release()
+            .context("\n\tthe lock is held by ")
+            .with_color_context(|| (Color::Green, &lock.info.client_id))
+            .with_context(|| {
+                format!(" ({}) and expires in {} seconds\n", lock.info.ip, lock.info.ttl)
+            })?;
+
+        match ctx.args.out_format {
+            OutputFormat::Json => cli_println!("{}", serde_json::to_string(&lock)?),
+            OutputFormat::Table => {
+                cli_print!("Successfully released the lock held by ");
+                cli_print!(@Green, "{}", lock.info.client_id);
+                cli_println!(" ({}) with {} seconds remaining", lock.info.ip, lock.info.ttl);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.locks_ctx
+            .init(LocksCtx::from_locks_common(&SeaplaneLocksCommonArgMatches(matches))?);
+
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        ctx.locks_ctx.get_mut().unwrap().base64 = matches.contains_id("base64");
+
+        Ok(())
+    }
+}
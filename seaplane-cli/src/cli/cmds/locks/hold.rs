@@ -0,0 +1,182 @@
+use std::{
+    process::{Command as ProcessCommand, ExitStatus},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use clap::{ArgMatches, Command};
+use seaplane::api::locks::v1::HeldLock;
+
+use crate::{
+    api::LocksReq,
+    cli::cmds::locks::{common, common::SeaplaneLocksCommonArgMatches, CliCommand},
+    context::{Ctx, LocksCtx},
+    error::{CliError, CliErrorKind, Context, Result},
+    printer::Color,
+};
+
+/// The environment variable containing the lock ID passed to the child command
+pub const LOCK_ID_ENV: &str = "SEAPLANE_LOCK_ID";
+/// The environment variable containing the lock sequencer passed to the child command
+pub const LOCK_SEQUENCER_ENV: &str = "SEAPLANE_LOCK_SEQUENCER";
+
+/// How long the command has to exit on its own after we receive a signal before it is killed
+const SIGNAL_GRACE_PERIOD: Duration = Duration::from_secs(5);
+/// How often to check if the command has exited
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+static LONG_ABOUT: &str = "Acquire a lock and hold it while running a command
+
+The lock is renewed in the background every third of the TTL for as long as the command
+runs, and released once it exits. The lock ID and sequencer are exported to the command
+as SEAPLANE_LOCK_ID and SEAPLANE_LOCK_SEQUENCER respectively.
+
+If the lock can not be renewed the command is killed, since it may no longer hold the
+lock.
+
+The exit status of the command is used as the exit status of 'seaplane locks hold'.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneLocksHold;
+
+impl SeaplaneLocksHold {
+    pub fn command() -> Command<'static> {
+        Command::new("hold")
+            .about("Acquire a lock and hold it while running a command")
+            .long_about(LONG_ABOUT)
+            .override_usage("seaplane locks hold <LOCK_NAME> --ttl <SECS> --client-id <STRING> [OPTIONS] -- <COMMAND>...")
+            .arg(common::lock_name())
+            .arg(common::ttl())
+            .arg(common::base64())
+            .arg(common::client_id())
+            .arg(
+                arg!(command =["COMMAND"] required last multiple_values)
+                    .help("The command (and its arguments) to run while holding the lock"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneLocksHold {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let mut req = LocksReq::new(ctx)?;
+        let renew_req = LocksReq::new(ctx)?;
+        let locksctx = ctx.locks_ctx.get_or_init();
+
+        req.set_name(locksctx.lock_name.as_ref().unwrap().to_model())?;
+        let held_lock = req.acquire(locksctx.ttl.unwrap(), locksctx.client_id.as_ref().unwrap())?;
+
+        hold_while_running(req, renew_req, &held_lock, locksctx.ttl.unwrap(), &locksctx.command)
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.locks_ctx
+            .init(LocksCtx::from_locks_common(&SeaplaneLocksCommonArgMatches(matches))?);
+
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        let locksctx = ctx.locks_ctx.get_mut().unwrap();
+        locksctx.ttl = matches.get_one::<u32>("ttl").copied();
+        locksctx.base64 = matches.contains_id("base64");
+        locksctx.client_id = Some(matches.get_one::<String>("client-id").unwrap().to_string());
+        locksctx.command = matches
+            .get_many::<String>("command")
+            .unwrap()
+            .cloned()
+            .collect();
+
+        Ok(())
+    }
+}
+
+/// Runs `command` while holding the already acquired `held_lock`, renewing it every third of the
+/// `ttl` with `renew_req` and releasing it with `req` once the command exits
+pub(super) fn hold_while_running(
+    mut req: LocksReq,
+    mut renew_req: LocksReq,
+    held_lock: &HeldLock,
+    ttl: u32,
+    command: &[String],
+) -> Result<()> {
+    let lock_id = held_lock.id().encoded().to_owned();
+    req.set_identifiers(Some(held_lock.name().clone()), Some(&lock_id))?;
+    renew_req.set_identifiers(Some(held_lock.name().clone()), Some(&lock_id))?;
+    cli_debugln!(@prefix, "acquired lock {lock_id} (sequencer {})", held_lock.sequencer());
+
+    common::catch_signals();
+
+    let res = (|| {
+        let (program, args) = command.split_first().unwrap();
+        let mut child = ProcessCommand::new(program)
+            .args(args)
+            .env(LOCK_ID_ENV, &lock_id)
+            .env(LOCK_SEQUENCER_ENV, held_lock.sequencer().to_string())
+            .spawn()
+            .map_err(CliError::from)
+            .context("\n\tcommand: ")
+            .with_color_context(|| (Color::Yellow, program))?;
+
+        // The renewal thread runs until told to stop, or a renewal fails
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let (failed_tx, failed_rx) = mpsc::channel::<CliError>();
+        let interval = Duration::from_millis(u64::from(ttl) * 1000 / 3);
+        let renewer = thread::spawn(move || {
+            while let Err(mpsc::RecvTimeoutError::Timeout) = stop_rx.recv_timeout(interval) {
+                cli_debugln!(@prefix, "renewing lock");
+                if let Err(e) = renew_req.renew(ttl) {
+                    let _ = failed_tx.send(e);
+                    break;
+                }
+            }
+        });
+
+        let mut signaled_at = None;
+        let res = loop {
+            if let Some(status) = child.try_wait()? {
+                break Ok(status);
+            }
+            if let Ok(e) = failed_rx.try_recv() {
+                let _ = child.kill();
+                let _ = child.wait();
+                break Err(e.context(
+                    "\n(hint: the command was killed because the lock could not be renewed)\n",
+                ));
+            }
+            if common::signaled() {
+                // Give the command a chance to exit on its own first since it will usually
+                // have received the same signal
+                let signaled_at = signaled_at.get_or_insert_with(Instant::now);
+                if signaled_at.elapsed() > SIGNAL_GRACE_PERIOD {
+                    let _ = child.kill();
+                }
+            }
+            thread::sleep(POLL_INTERVAL);
+        };
+
+        let _ = stop_tx.send(());
+        let _ = renewer.join();
+        res
+    })();
+
+    // Always release the lock, even if the command failed. Errors running the command take
+    // priority over failing to release the lock, since the latter is expected after the lock
+    // was lost
+    let released = req.release();
+    let status = res?;
+    released?;
+    cli_debugln!(@prefix, "released lock {lock_id}");
+
+    exit_status_to_result(status)
+}
+
+/// Maps an unsuccessful exit of the command to an error with the same exit status
+fn exit_status_to_result(status: ExitStatus) -> Result<()> {
+    if status.success() {
+        return Ok(());
+    }
+
+    let err = CliErrorKind::ChildFailed(status.code()).into_err();
+    Err(match status.code() {
+        Some(code) => err.with_status(code),
+        None => err,
+    })
+}
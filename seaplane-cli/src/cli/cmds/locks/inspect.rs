@@ -0,0 +1,69 @@
+use clap::{ArgMatches, Command};
+
+use crate::{
+    api::LocksReq,
+    cli::cmds::locks::{common, common::SeaplaneLocksCommonArgMatches, CliCommand},
+    context::{Ctx, LocksCtx},
+    error::{CliError, CliErrorKind, Result},
+    ops::locks::{self, ListedLock},
+    printer::OutputFormat,
+};
+
+static LONG_ABOUT: &str = "Show the details of a single held lock
+
+The TTL is the number of seconds remaining until the lock expires, unless it is renewed.
+
+Locknames will be displayed in base64 encoded format by default because they may contain
+arbitrary binary data. Using --decode to output the decoded values instead.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneLocksInspect;
+
+impl SeaplaneLocksInspect {
+    pub fn command() -> Command<'static> {
+        Command::new("inspect")
+            .about("Show the details of a single held lock")
+            .long_about(LONG_ABOUT)
+            .arg(common::lock_name())
+            .arg(common::base64())
+            .args(common::display_args())
+    }
+}
+
+impl CliCommand for SeaplaneLocksInspect {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let mut req = LocksReq::new(ctx)?;
+        let locksctx = ctx.locks_ctx.get_or_init();
+        req.set_name(locksctx.lock_name.as_ref().unwrap().to_model())?;
+
+        let lock = ListedLock::from(req.get_lock_info()?);
+
+        match ctx.args.out_format {
+            OutputFormat::Json => cli_println!("{}", serde_json::to_string(&lock)?),
+            OutputFormat::Table => locks::print_lock_details(!locksctx.no_header, &lock, ctx)?,
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.locks_ctx
+            .init(LocksCtx::from_locks_common(&SeaplaneLocksCommonArgMatches(matches))?);
+
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        let locksctx = ctx.locks_ctx.get_mut().unwrap();
+        locksctx.base64 = matches.contains_id("base64");
+        locksctx.decode = matches.contains_id("decode");
+        locksctx.no_header = matches.contains_id("no-header");
+
+        if locksctx.decode && ctx.args.out_format != OutputFormat::Table {
+            let format_arg = format!("--format {}", ctx.args.out_format);
+            return Err(CliError::from(CliErrorKind::ConflictingArguments(
+                "--decode".to_owned(),
+                format_arg,
+            )));
+        }
+
+        Ok(())
+    }
+}
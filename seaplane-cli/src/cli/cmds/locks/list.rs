@@ -1,11 +1,11 @@
-use clap::{ArgMatches, Command};
+use clap::{value_parser, ArgMatches, Command};
 
 use crate::{
     api::LocksReq,
     cli::cmds::locks::{common, common::SeaplaneLocksCommonArgMatches, CliCommand},
     context::{Ctx, LocksCtx},
     error::{CliError, CliErrorKind, Result},
-    ops::locks::{self, ListedLock, LockName},
+    ops::locks::{self, ListedLock, LockFilter, LockName},
     printer::OutputFormat,
 };
 
@@ -19,7 +19,11 @@ There are 3 ways to list locks with this command:
 - Use a lock name followed by a trailing slash to list all locks under that directory
 
 Locknames will be displayed in base64 encoded format by default because they may contain
-arbitrary binary data. Using --decode to output the decoded values instead.";
+arbitrary binary data. Using --decode to output the decoded values instead.
+
+The TTL is the number of seconds remaining until the lock expires, unless it is renewed.
+Use the --client-id, --ip, --min-ttl and --max-ttl filters to find specific locks, such as
+those of a client which is no longer running.";
 
 #[derive(Copy, Clone, Debug)]
 pub struct SeaplaneLocksList;
@@ -36,6 +40,21 @@ impl SeaplaneLocksList {
             )
             .arg(common::base64().requires("lock_name"))
             .args(common::display_args())
+            .arg(
+                arg!(--("client-id") =["STRING"])
+                    .help("Only list locks held by the given client ID"),
+            )
+            .arg(arg!(--ip =["IP"]).help("Only list locks held from the given IP address"))
+            .arg(
+                arg!(--("min-ttl") =["SECS"])
+                    .value_parser(value_parser!(u32))
+                    .help("Only list locks with at least SECS seconds of their TTL remaining"),
+            )
+            .arg(
+                arg!(--("max-ttl") =["SECS"])
+                    .value_parser(value_parser!(u32))
+                    .help("Only list locks with at most SECS seconds of their TTL remaining"),
+            )
     }
 }
 
@@ -48,6 +67,9 @@ fn run_one_info(ctx: &mut Ctx) -> Result<()> {
     req.set_name(model_name)?;
 
     let resp = req.get_lock_info()?;
+    if !locksctx.filter.matches(&resp.info) {
+        return Ok(());
+    }
     let out = ListedLock::from(resp);
 
     match ctx.args.out_format {
@@ -63,6 +85,7 @@ fn run_dir_info(ctx: &mut Ctx, dir_name: Option<LockName>) -> Result<()> {
     let mut last_key = None;
     let dir = dir_name.map(|d| d.to_model());
     let mut headers = !ctx.locks_ctx.get_or_init().no_header;
+    let filter = ctx.locks_ctx.get_or_init().filter.clone();
     let mut table_page = Vec::with_capacity(OUTPUT_PAGE_SIZE);
 
     loop {
@@ -72,7 +95,7 @@ fn run_dir_info(ctx: &mut Ctx, dir_name: Option<LockName>) -> Result<()> {
         // We use the regular paging interface rather than
         // get_all_pages so that we don't have to store
         // all of the locks in memory at once.
-        for info in page.infos {
+        for info in page.infos.into_iter().filter(|i| filter.matches(&i.info)) {
             let out = ListedLock::from(info);
             match ctx.args.out_format {
                 OutputFormat::Json => cli_println!("{}", serde_json::to_string(&out)?),
@@ -137,10 +160,16 @@ impl CliCommand for SeaplaneLocksList {
             .init(LocksCtx::from_locks_common(&SeaplaneLocksCommonArgMatches(matches))?);
 
         ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
-        let mut locksctx = ctx.locks_ctx.get_mut().unwrap();
+        let locksctx = ctx.locks_ctx.get_mut().unwrap();
         locksctx.base64 = matches.contains_id("base64");
         locksctx.decode = matches.contains_id("decode");
         locksctx.no_header = matches.contains_id("no-header");
+        locksctx.filter = LockFilter {
+            client_id: matches.get_one::<String>("client-id").cloned(),
+            ip: matches.get_one::<String>("ip").cloned(),
+            min_ttl: matches.get_one::<u32>("min-ttl").copied(),
+            max_ttl: matches.get_one::<u32>("max-ttl").copied(),
+        };
 
         if locksctx.decode && ctx.args.out_format != OutputFormat::Table {
             let format_arg = format!("--format {}", ctx.args.out_format);
@@ -0,0 +1,194 @@
+use clap::{value_parser, Arg, ArgMatches, Command};
+use seaplane::api::locks::v1::Semaphore;
+
+use crate::{
+    api::LocksReq,
+    cli::cmds::locks::{common, common::SeaplaneLocksCommonArgMatches, hold, CliCommand},
+    context::{Ctx, LocksCtx},
+    error::Result,
+    ops::{locks::HeldSlot, EncodedString},
+    printer::{Output, OutputFormat},
+};
+
+static LONG_ABOUT: &str = "Operate on counted locks, which can be held by up to N clients at once
+
+A semaphore named NAME with N slots is made up of the N ordinary locks NAME/0 through
+NAME/N-1. Acquiring the semaphore acquires any one of the slots which is not held, and
+from then on that slot is renewed and released just like any other lock.
+
+A slot taken with 'acquire' stays held until it is released or its TTL runs out, so use
+'hold' to hold a slot only for as long as a command runs.
+
+All clients using a semaphore must agree on the number of slots.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneLocksSemaphore;
+
+impl SeaplaneLocksSemaphore {
+    pub fn command() -> Command<'static> {
+        Command::new("semaphore")
+            .visible_alias("sem")
+            .about("Operate on counted locks, which can be held by up to N clients at once")
+            .long_about(LONG_ABOUT)
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .subcommand(SeaplaneLocksSemaphoreAcquire::command())
+            .subcommand(SeaplaneLocksSemaphoreHold::command())
+    }
+}
+
+/// The number of slots of the semaphore
+fn slots() -> Arg<'static> {
+    arg!(--slots =["N"] required)
+        .value_parser(value_parser!(u32).range(1..))
+        .help("The number of slots, i.e. how many clients may hold the semaphore at once")
+}
+
+impl CliCommand for SeaplaneLocksSemaphore {
+    fn next_subcmd<'a>(
+        &self,
+        matches: &'a ArgMatches,
+    ) -> Option<(Box<dyn CliCommand>, &'a ArgMatches)> {
+        match matches.subcommand() {
+            Some(("acquire", m)) => Some((Box::new(SeaplaneLocksSemaphoreAcquire), m)),
+            Some(("hold", m)) => Some((Box::new(SeaplaneLocksSemaphoreHold), m)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneLocksSemaphoreAcquire;
+
+impl SeaplaneLocksSemaphoreAcquire {
+    pub fn command() -> Command<'static> {
+        Command::new("acquire")
+            .visible_alias("acq")
+            .about("Attempt to acquire any free slot of the semaphore for N seconds")
+            .long_about(
+                "Attempt to acquire any free slot of the semaphore for N seconds
+
+The name of the acquired slot's lock is printed along with the lock ID, and can be used
+with 'seaplane locks renew' and 'seaplane locks release' like any other lock.
+
+The slot is not renewed or released automatically, so it stays taken until released or
+until the TTL runs out. Use 'seaplane locks semaphore hold' to hold a slot while running
+a command instead.",
+            )
+            .arg(common::lock_name().help("The name of the semaphore"))
+            .arg(slots())
+            .arg(common::ttl())
+            .arg(common::base64())
+            .arg(common::client_id())
+    }
+}
+
+impl CliCommand for SeaplaneLocksSemaphoreAcquire {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let mut req = LocksReq::new(ctx)?;
+        let locksctx = ctx.locks_ctx.get_or_init();
+        req.set_name(locksctx.lock_name.as_ref().unwrap().to_model())?;
+
+        let semaphore = Semaphore::new(req.inner()?, locksctx.slots.unwrap())?;
+        let held_lock =
+            semaphore.try_acquire(locksctx.ttl.unwrap(), locksctx.client_id.as_ref().unwrap())?;
+
+        // Print the slot's name the same way the semaphore's name was given
+        let name = EncodedString::new(held_lock.name().encoded().to_owned());
+        let held_slot = HeldSlot {
+            lock_name: if locksctx.base64 { name.to_string() } else { name.decoded_safe()? },
+            lock_id: held_lock.id().encoded().to_owned(),
+            sequencer: held_lock.sequencer(),
+        };
+
+        match ctx.args.out_format {
+            OutputFormat::Json => held_slot.print_json(ctx)?,
+            OutputFormat::Table => held_slot.print_table(ctx)?,
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.locks_ctx
+            .init(LocksCtx::from_locks_common(&SeaplaneLocksCommonArgMatches(matches))?);
+
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        let locksctx = ctx.locks_ctx.get_mut().unwrap();
+        locksctx.ttl = matches.get_one::<u32>("ttl").copied();
+        locksctx.slots = matches.get_one::<u32>("slots").copied();
+        locksctx.base64 = matches.contains_id("base64");
+        locksctx.client_id = Some(matches.get_one::<String>("client-id").unwrap().to_string());
+
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneLocksSemaphoreHold;
+
+impl SeaplaneLocksSemaphoreHold {
+    pub fn command() -> Command<'static> {
+        Command::new("hold")
+            .about("Acquire any free slot of the semaphore and hold it while running a command")
+            .long_about(
+                "Acquire any free slot of the semaphore and hold it while running a command
+
+The slot is renewed and released exactly like a lock held with 'seaplane locks hold',
+and the lock ID and sequencer of the slot are exported to the command as
+SEAPLANE_LOCK_ID and SEAPLANE_LOCK_SEQUENCER respectively.
+
+The exit status of the command is used as the exit status of this command.",
+            )
+            .override_usage("seaplane locks semaphore hold <LOCK_NAME> --slots <N> --ttl <SECS> --client-id <STRING> [OPTIONS] -- <COMMAND>...")
+            .arg(common::lock_name().help("The name of the semaphore"))
+            .arg(slots())
+            .arg(common::ttl())
+            .arg(common::base64())
+            .arg(common::client_id())
+            .arg(
+                arg!(command =["COMMAND"] required last multiple_values)
+                    .help("The command (and its arguments) to run while holding the slot"),
+            )
+    }
+}
+
+impl CliCommand for SeaplaneLocksSemaphoreHold {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let mut req = LocksReq::new(ctx)?;
+        let renew_req = LocksReq::new(ctx)?;
+        let locksctx = ctx.locks_ctx.get_or_init();
+        req.set_name(locksctx.lock_name.as_ref().unwrap().to_model())?;
+
+        let semaphore = Semaphore::new(req.inner()?, locksctx.slots.unwrap())?;
+        let held_lock =
+            semaphore.try_acquire(locksctx.ttl.unwrap(), locksctx.client_id.as_ref().unwrap())?;
+
+        hold::hold_while_running(
+            req,
+            renew_req,
+            &held_lock,
+            locksctx.ttl.unwrap(),
+            &locksctx.command,
+        )
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.locks_ctx
+            .init(LocksCtx::from_locks_common(&SeaplaneLocksCommonArgMatches(matches))?);
+
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        let locksctx = ctx.locks_ctx.get_mut().unwrap();
+        locksctx.ttl = matches.get_one::<u32>("ttl").copied();
+        locksctx.slots = matches.get_one::<u32>("slots").copied();
+        locksctx.base64 = matches.contains_id("base64");
+        locksctx.client_id = Some(matches.get_one::<String>("client-id").unwrap().to_string());
+        locksctx.command = matches
+            .get_many::<String>("command")
+            .unwrap()
+            .cloned()
+            .collect();
+
+        Ok(())
+    }
+}
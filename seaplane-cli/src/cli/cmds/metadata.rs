@@ -3,6 +3,7 @@ mod delete;
 mod get;
 mod list;
 mod set;
+mod sync;
 
 use clap::{value_parser, ArgMatches, Command};
 
@@ -12,6 +13,7 @@ pub use self::{
     get::SeaplaneMetadataGet,
     list::SeaplaneMetadataList,
     set::{SeaplaneMetadataSet, SeaplaneMetadataSetArgMatches},
+    sync::{SeaplaneMetadataSync, SeaplaneMetadataSyncArgMatches},
 };
 use crate::{cli::CliCommand, printer::OutputFormat};
 
@@ -34,6 +36,7 @@ impl SeaplaneMetadata {
             .subcommand(SeaplaneMetadataSet::command())
             .subcommand(SeaplaneMetadataDelete::command())
             .subcommand(SeaplaneMetadataList::command())
+            .subcommand(SeaplaneMetadataSync::command())
     }
 }
 
@@ -47,6 +50,7 @@ impl CliCommand for SeaplaneMetadata {
             Some(("set", m)) => Some((Box::new(SeaplaneMetadataSet), m)),
             Some(("delete", m)) => Some((Box::new(SeaplaneMetadataDelete), m)),
             Some(("list", m)) => Some((Box::new(SeaplaneMetadataList), m)),
+            Some(("sync", m)) => Some((Box::new(SeaplaneMetadataSync), m)),
             _ => None,
         }
     }
@@ -9,6 +9,16 @@ const LONG_HUMAN_READABLE: &str = "Safely decode and truncate output for human r
 
 Implies --decode-safe --values-width-limit 256";
 
+const LONG_KEY_FILE: &str = "A file containing the keys used to encrypt and decrypt metadata values
+
+Each line of the file is a VERSION:KEY pair where KEY is 32 random bytes encoded
+as base64 (i.e. the output of 'head -c 32 /dev/urandom | base64'). New values are
+encrypted with the highest VERSION while any key in the file may be used for
+decryption, which allows rotating keys.
+
+When this flag is omitted the keys may be provided directly in the
+SEAPLANE_METADATA_KEYS environment variable as comma separated VERSION:KEY pairs.";
+
 /// A newtype wrapper to enforce where the ArgMatches came from which reduces errors in checking if
 /// values of arguments were used or not. i.e. `seaplane formation create` may not have the same
 /// arguments as `seaplane account token` even though both produce an `ArgMatches`.
@@ -53,6 +63,14 @@ pub fn base64() -> Arg<'static> {
     arg!(--base64 - ('B')).help("The keys/values are already encoded in URL safe Base64")
 }
 
+pub fn key_file() -> Arg<'static> {
+    arg!(--("key-file") = ["PATH"])
+        .env("SEAPLANE_METADATA_KEY_FILE")
+        .hide_env_values(true)
+        .help("A file containing the keys used to encrypt and decrypt metadata values")
+        .long_help(LONG_KEY_FILE)
+}
+
 pub fn single_key() -> Arg<'static> {
     arg!(key =["KEY"] required ).help("The key of the metadata key-value pair")
 }
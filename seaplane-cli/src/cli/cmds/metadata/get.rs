@@ -26,6 +26,7 @@ impl SeaplaneMetadataGet {
             .long_about(LONG_ABOUT)
             .arg(common::single_key())
             .arg(common::base64())
+            .arg(common::key_file())
             .args(common::display_args())
             .group(common::keys_or_values())
             .mut_arg("no-header", |a| a.hide(true))
@@ -46,6 +47,9 @@ impl CliCommand for SeaplaneMetadataGet {
                     req.get_value()?.to_string(),
                 );
             }
+            if mdctx.decode || mdctx.decode_safe {
+                mdctx.kvs.decrypt_values(mdctx.keyring.as_ref())?;
+            }
 
             mdctx.kvs.clone()
         };
@@ -63,6 +67,7 @@ impl CliCommand for SeaplaneMetadataGet {
         ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
         let mut mdctx = ctx.md_ctx.get_mut_or_init();
         mdctx.decode = matches.contains_id("decode");
+        mdctx.keyring = MetadataCtx::keyring_from_matches(matches)?;
         mdctx.no_header = true;
         mdctx.no_keys = true;
         mdctx.no_values = false;
@@ -34,6 +34,7 @@ impl SeaplaneMetadataList {
                     .help("The root directory of the metadata key-value pairs to list"),
             )
             .arg(common::base64())
+            .arg(common::key_file())
             .args(common::display_args())
             .group(common::keys_or_values())
             .arg(arg!(--from - ('f') =["KEY"]).help("Only print metadata key-value pairs after this key (note: if this key has a value it will be included in the results)"))
@@ -58,7 +59,11 @@ impl CliCommand for SeaplaneMetadataList {
             // Using the KeyValues container makes displaying easy
             let mut req = MetadataReq::new(ctx)?;
             req.set_dir(range)?;
-            KeyValues::from_model(req.get_all_pages()?)
+            let mut kvs = KeyValues::from_model(req.get_all_pages()?);
+            if mdctx.decode || mdctx.decode_safe {
+                kvs.decrypt_values(mdctx.keyring.as_ref())?;
+            }
+            kvs
         };
 
         match ctx.args.out_format {
@@ -77,6 +82,7 @@ impl CliCommand for SeaplaneMetadataList {
         mdctx.decode = matches.contains_id("decode");
         mdctx.decode_safe = matches.contains_id("decode-safe");
         mdctx.no_decode = matches.contains_id("no-decode");
+        mdctx.keyring = MetadataCtx::keyring_from_matches(matches)?;
         mdctx.no_keys = matches.contains_id("only-values");
         mdctx.no_values = matches.contains_id("only-keys");
         mdctx.no_header = matches.contains_id("no-header");
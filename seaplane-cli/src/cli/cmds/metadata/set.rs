@@ -25,6 +25,8 @@ impl SeaplaneMetadataSet {
             .override_usage("seaplane metadata set <KEY> <VALUE> [OPTIONS]")
             .about("Set a metadata key-value pair")
             .arg(common::base64())
+            .arg(arg!(--encrypt - ('E')).help("Encrypt the value before storing it (requires --key-file)"))
+            .arg(common::key_file())
             .arg(arg!(key =["KEY"] required ).help("The key to set"))
             .arg(arg!(value =["VALUE"] required ).help("The value (@path will load the value from a path and @- will load the value from STDIN)"))
     }
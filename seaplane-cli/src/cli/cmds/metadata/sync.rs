@@ -0,0 +1,110 @@
+use clap::{ArgMatches, Command};
+use seaplane::api::{metadata::v1::Value, shared::v1::RangeQueryContext};
+
+use crate::{
+    api::MetadataReq,
+    cli::{cmds::metadata::common, CliCommand},
+    context::{Ctx, MetadataCtx},
+    error::Result,
+    ops::{
+        metadata::{KeyValues, SyncPlan},
+        SyncAction,
+    },
+    printer::{Output, OutputFormat},
+};
+
+static LONG_ABOUT: &str = "Sync a local directory of files to a metadata directory
+
+Each file below LOCAL_DIR is stored under the key REMOTE_DIR/PATH where PATH is the
+path of the file relative to LOCAL_DIR. Only keys whose contents differ from the
+local files are uploaded. Hidden files and directories (those starting with a '.')
+are skipped.
+
+Use --dry-run to see what would change without changing anything.";
+
+/// A newtype wrapper to enforce where the ArgMatches came from which reduces errors in checking if
+/// values of arguments were used or not. i.e. `seaplane formation create` may not have the same
+/// arguments as `seaplane account token` even though both produce an `ArgMatches`.
+#[allow(missing_debug_implementations)]
+pub struct SeaplaneMetadataSyncArgMatches<'a>(pub &'a ArgMatches);
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneMetadataSync;
+
+impl SeaplaneMetadataSync {
+    pub fn command() -> Command<'static> {
+        Command::new("sync")
+            .override_usage("seaplane metadata sync <LOCAL_DIR> <REMOTE_DIR> [OPTIONS]")
+            .about("Sync a local directory of files to a metadata directory")
+            .long_about(LONG_ABOUT)
+            .arg(
+                arg!(local_dir =["LOCAL_DIR"] required)
+                    .help("The local directory of files to sync"),
+            )
+            .arg(
+                arg!(remote_dir =["REMOTE_DIR"] required)
+                    .help("The metadata directory to sync the files into"),
+            )
+            .arg(arg!(--delete).help("Delete keys in REMOTE_DIR which do not exist in LOCAL_DIR"))
+            .arg(
+                arg!(--("dry-run") | diff)
+                    .help("Print the changes that would be made without making them"),
+            )
+            .arg(
+                arg!(--encrypt - ('E'))
+                    .help("Encrypt the values before storing them (requires --key-file)"),
+            )
+            .arg(common::key_file())
+    }
+}
+
+impl CliCommand for SeaplaneMetadataSync {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let mut req = MetadataReq::new(ctx)?;
+        let plan = {
+            let mdctx = ctx.md_ctx.get_or_init();
+
+            let mut range = RangeQueryContext::new();
+            if let Some(dir) = &mdctx.directory {
+                range.set_directory(dir.clone());
+            }
+            req.set_dir(range)?;
+            let remote = KeyValues::from_model(req.get_all_pages()?);
+
+            let mut plan = SyncPlan::new(
+                &mdctx.kvs,
+                &remote,
+                mdctx.delete,
+                mdctx.encrypt,
+                mdctx.keyring.as_ref(),
+            )?;
+            plan.dry_run = mdctx.dry_run;
+            plan
+        };
+
+        if !plan.dry_run {
+            for change in &plan.changes {
+                req.set_key(change.key.to_string())?;
+                match (change.action, &change.value) {
+                    (SyncAction::Delete, _) => req.delete_value()?,
+                    (_, Some(value)) => req.put_value(Value::from_encoded(value.to_string()))?,
+                    (_, None) => unreachable!("only deletions are missing a value"),
+                }
+            }
+        }
+
+        match ctx.args.out_format {
+            OutputFormat::Json => plan.print_json(ctx)?,
+            OutputFormat::Table => plan.print_table(ctx)?,
+        }
+
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.md_ctx
+            .init(MetadataCtx::from_md_sync(&SeaplaneMetadataSyncArgMatches(matches))?);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        Ok(())
+    }
+}
@@ -1,17 +1,27 @@
+mod apply;
+mod audit;
 pub mod common;
 mod delete;
+mod explain;
 mod get;
 mod list;
+mod plan;
 mod set;
+mod wait;
 
 use clap::{ArgMatches, Command};
 
 pub use self::{
+    apply::SeaplaneRestrictApply,
+    audit::SeaplaneRestrictAudit,
     common::SeaplaneRestrictCommonArgMatches,
     delete::SeaplaneRestrictDelete,
+    explain::{SeaplaneRestrictExplain, SeaplaneRestrictExplainArgMatches},
     get::SeaplaneRestrictGet,
     list::{SeaplaneRestrictList, SeaplaneRestrictListArgMatches},
+    plan::SeaplaneRestrictPlan,
     set::{SeaplaneRestrictSet, SeaplaneRestrictSetArgMatches},
+    wait::SeaplaneRestrictWait,
 };
 use crate::cli::CliCommand;
 
@@ -28,6 +38,11 @@ impl SeaplaneRestrict {
             .subcommand(SeaplaneRestrictList::command())
             .subcommand(SeaplaneRestrictSet::command())
             .subcommand(SeaplaneRestrictDelete::command())
+            .subcommand(SeaplaneRestrictPlan::command())
+            .subcommand(SeaplaneRestrictApply::command())
+            .subcommand(SeaplaneRestrictWait::command())
+            .subcommand(SeaplaneRestrictExplain::command())
+            .subcommand(SeaplaneRestrictAudit::command())
     }
 }
 
@@ -41,6 +56,11 @@ impl CliCommand for SeaplaneRestrict {
             Some(("list", m)) => Some((Box::new(SeaplaneRestrictList), m)),
             Some(("set", m)) => Some((Box::new(SeaplaneRestrictSet), m)),
             Some(("delete", m)) => Some((Box::new(SeaplaneRestrictDelete), m)),
+            Some(("plan", m)) => Some((Box::new(SeaplaneRestrictPlan), m)),
+            Some(("apply", m)) => Some((Box::new(SeaplaneRestrictApply), m)),
+            Some(("wait", m)) => Some((Box::new(SeaplaneRestrictWait), m)),
+            Some(("explain", m)) => Some((Box::new(SeaplaneRestrictExplain), m)),
+            Some(("audit", m)) => Some((Box::new(SeaplaneRestrictAudit), m)),
             _ => None,
         }
     }
@@ -0,0 +1,77 @@
+use clap::{value_parser, ArgMatches, Command};
+use const_format::concatcp;
+
+use crate::{
+    api::RestrictReq,
+    cli::{
+        cmds::restrict::{common, common::SeaplaneRestrictPolicyArgMatches, plan},
+        specs::{POLICY_SPEC, REGION_SPEC},
+        CliCommand,
+    },
+    context::{Ctx, RestrictCtx},
+    error::{Context, Result},
+    ops::SyncAction,
+    printer::{Color, Output, OutputFormat},
+};
+
+static LONG_ABOUT: &str = "Make the restrictions match a policy file
+
+Restrictions which are missing are created, those whose details differ are updated, and
+those which are not in the policy are deleted. Restrictions with an API, region or provider
+unknown to this version of the CLI are never changed or deleted. Use 'seaplane restrict plan'
+to see the changes first.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneRestrictApply;
+
+impl SeaplaneRestrictApply {
+    pub fn command() -> Command<'static> {
+        Command::new("apply")
+            .override_usage("seaplane restrict apply <FILE> [OPTIONS]")
+            .about("Make the restrictions match a policy file")
+            .long_about(LONG_ABOUT)
+            .arg(common::policy_file())
+            .arg(
+                arg!(--format =["FORMAT"=>"table"] global)
+                    .help("Change the output format")
+                    .value_parser(value_parser!(OutputFormat)),
+            )
+            .after_help(concatcp!(POLICY_SPEC, "\n\n", REGION_SPEC))
+    }
+}
+
+impl CliCommand for SeaplaneRestrictApply {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let plan = plan::plan(ctx)?;
+
+        let mut req = RestrictReq::new(ctx)?;
+        for change in &plan.changes {
+            let api = change.api.to_string().to_lowercase();
+            req.set_api(&api)?;
+            req.set_directory(change.directory.to_string())?;
+            match (change.action, &change.details) {
+                (SyncAction::Delete, _) => req.delete_restriction(),
+                (_, Some(details)) => req.set_restriction(details.clone()),
+                (_, None) => unreachable!("only deletions are missing details"),
+            }
+            .context("\n\tdirectory: ")
+            .with_color_context(|| (Color::Yellow, change.directory.to_string()))
+            .context(" in ")
+            .with_color_context(|| (Color::Yellow, &api))
+            .context(" API\n")?;
+        }
+
+        match ctx.args.out_format {
+            OutputFormat::Json => plan.print_json(ctx)?,
+            OutputFormat::Table => plan.print_table(ctx)?,
+        }
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.restrict_ctx
+            .init(RestrictCtx::from_restrict_policy(&SeaplaneRestrictPolicyArgMatches(matches))?);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        Ok(())
+    }
+}
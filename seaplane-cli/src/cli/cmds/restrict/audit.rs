@@ -0,0 +1,63 @@
+use clap::{ArgMatches, Command};
+use seaplane::api::shared::v1::RangeQueryContext;
+
+use crate::{
+    api::{MetadataReq, RestrictReq},
+    cli::{cmds::restrict::common, CliCommand},
+    context::{Ctx, RestrictCtx},
+    error::Result,
+    ops::{metadata::KeyValues, restrict::UnrestrictedDirectories},
+    printer::{Output, OutputFormat},
+};
+
+static LONG_ABOUT: &str = "List metadata directories which are not governed by any restriction
+
+Every directory containing at least one metadata key is checked against the restrictions
+on the config API. Data in the directories listed may be placed in any region or provider.
+
+Directories will be displayed in base64 encoded format by default because they may contain
+arbitrary binary data. Use --decode to output the decoded values instead.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneRestrictAudit;
+
+impl SeaplaneRestrictAudit {
+    pub fn command() -> Command<'static> {
+        Command::new("audit")
+            .override_usage("seaplane restrict audit [OPTIONS]")
+            .about("List metadata directories which are not governed by any restriction")
+            .long_about(LONG_ABOUT)
+            .args(common::display_args())
+    }
+}
+
+impl CliCommand for SeaplaneRestrictAudit {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let unrestricted = {
+            let mut req = RestrictReq::new(ctx)?;
+            req.set_api("config")?;
+            let restrictions = req.get_all_pages()?;
+
+            let mut md_req = MetadataReq::new(ctx)?;
+            md_req.set_dir(RangeQueryContext::new())?;
+            let kvs = KeyValues::from_model(md_req.get_all_pages()?);
+
+            UnrestrictedDirectories::new(kvs.keys(), &restrictions)?
+        };
+
+        match ctx.args.out_format {
+            OutputFormat::Json => unrestricted.print_json(ctx)?,
+            OutputFormat::Table => unrestricted.print_table(ctx)?,
+        }
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.restrict_ctx.init(RestrictCtx::default());
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        let restrict_ctx = ctx.restrict_ctx.get_mut_or_init();
+        restrict_ctx.decode = matches.contains_id("decode");
+        restrict_ctx.no_header = matches.contains_id("no-header");
+        Ok(())
+    }
+}
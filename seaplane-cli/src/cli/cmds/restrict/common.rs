@@ -1,5 +1,9 @@
 use clap::{value_parser, Arg, ArgMatches, PossibleValue};
-use seaplane::api::shared::v1::{Provider as ProviderModel, Region as RegionModel};
+use seaplane::api::{
+    restrict::v1::Api as ApiModel,
+    shared::v1::{Provider as ProviderModel, Region as RegionModel},
+};
+use strum::VariantNames;
 
 use crate::OutputFormat;
 
@@ -251,6 +255,13 @@ impl<'a> Into<RegionModel> for &'a Region {
 #[derive(Debug)]
 pub struct SeaplaneRestrictCommonArgMatches<'a>(pub &'a ArgMatches);
 
+/// A newtype wrapper to enforce where the ArgMatches came from which reduces
+/// errors in checking if values of arguments were used or not. i.e. `seaplane
+/// formation create` may not have the same arguments as `seaplane account
+/// token` even though both produce an `ArgMatches`.
+#[derive(Debug)]
+pub struct SeaplaneRestrictPolicyArgMatches<'a>(pub &'a ArgMatches);
+
 pub fn display_args() -> Vec<Arg<'static>> {
     vec![
         arg!(--format =["FORMAT"=>"table"] global)
@@ -302,9 +313,36 @@ pub fn base64() -> Arg<'static> {
 }
 
 pub fn api() -> Arg<'static> {
-    arg!(api =["API"] required ).help("The API of the restricted directory")
+    arg!(api =["API"] required )
+        .help("The API of the restricted directory")
+        .value_parser(known_api)
+}
+
+/// Only the APIs known to this version can be restricted, anything else is most likely a typo
+pub fn known_api(s: &str) -> Result<String, String> {
+    match s.parse() {
+        Ok(ApiModel::Unknown(_)) | Err(_) => {
+            let known: Vec<_> = ApiModel::VARIANTS
+                .iter()
+                .filter(|&&api| api != "Unknown")
+                .map(|api| api.to_lowercase())
+                .collect();
+            Err(format!("unknown API (possible values: {})", known.join(", ")))
+        }
+        Ok(_) => Ok(s.to_owned()),
+    }
 }
 
 pub fn directory() -> Arg<'static> {
     arg!(directory =["DIRECTORY"] required ).help("The restricted directory")
 }
+
+pub fn timeout() -> Arg<'static> {
+    arg!(--timeout = ["SECS"])
+        .value_parser(value_parser!(u64))
+        .help("Give up waiting for the restriction to be enforced after SECS seconds")
+}
+
+pub fn policy_file() -> Arg<'static> {
+    arg!(file =["FILE"] required).help("The restriction policy file (See POLICY FILE below)")
+}
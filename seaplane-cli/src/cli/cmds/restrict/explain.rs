@@ -0,0 +1,76 @@
+use clap::{ArgMatches, Command};
+
+use crate::{
+    api::RestrictReq,
+    cli::{cmds::restrict::common, CliCommand},
+    context::{Ctx, RestrictCtx},
+    error::Result,
+    ops::restrict::{governing_restriction, RestrictionExplanation},
+    printer::{Output, OutputFormat},
+};
+
+static LONG_ABOUT: &str = "Show which restriction governs a metadata key or lock name
+
+The most specific restriction whose directory contains KEY governs where its data may be
+placed, and the regions and providers it allows and denies are printed. If no restriction
+governs KEY its data may be placed anywhere.
+
+Keys and directories will be displayed in base64 encoded format by default because they
+may contain arbitrary binary data. Use --decode to output the decoded values instead.";
+
+/// A newtype wrapper to enforce where the ArgMatches came from which reduces errors in checking if
+/// values of arguments were used or not. i.e. `seaplane formation create` may not have the same
+/// arguments as `seaplane account token` even though both produce an `ArgMatches`.
+#[allow(missing_debug_implementations)]
+pub struct SeaplaneRestrictExplainArgMatches<'a>(pub &'a ArgMatches);
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneRestrictExplain;
+
+impl SeaplaneRestrictExplain {
+    pub fn command() -> Command<'static> {
+        Command::new("explain")
+            .override_usage("seaplane restrict explain <API> <KEY> [OPTIONS]")
+            .about("Show which restriction governs a metadata key or lock name")
+            .long_about(LONG_ABOUT)
+            .arg(common::api().help("The API of the key"))
+            .arg(arg!(key =["KEY"] required).help("The metadata key or lock name"))
+            .arg(arg!(--base64 - ('B')).help("The key is already encoded in URL safe Base64"))
+            .args(common::display_args())
+    }
+}
+
+impl CliCommand for SeaplaneRestrictExplain {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let explanation = {
+            let mut req = RestrictReq::new(ctx)?;
+            let restrict_ctx = ctx.restrict_ctx.get_or_init();
+            let api = restrict_ctx.api.as_ref().unwrap();
+            let key = restrict_ctx.key.clone().unwrap();
+            req.set_api(api)?;
+            let restrictions = req.get_all_pages()?;
+
+            RestrictionExplanation {
+                api: api.to_owned(),
+                restriction: governing_restriction(&restrictions, &key.decoded()?).cloned(),
+                key,
+            }
+        };
+
+        match ctx.args.out_format {
+            OutputFormat::Json => explanation.print_json(ctx)?,
+            OutputFormat::Table => explanation.print_table(ctx)?,
+        }
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.restrict_ctx
+            .init(RestrictCtx::from_restrict_explain(&SeaplaneRestrictExplainArgMatches(matches))?);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        let restrict_ctx = ctx.restrict_ctx.get_mut_or_init();
+        restrict_ctx.decode = matches.contains_id("decode");
+        restrict_ctx.no_header = matches.contains_id("no-header");
+        Ok(())
+    }
+}
@@ -30,7 +30,11 @@ impl SeaplaneRestrictList {
             .override_usage("seaplane restrict list [API] [OPTIONS]")
             .about("List restrictions in an API, or across all APIs")
             .long_about(LONG_ABOUT)
-            .arg(arg!(api = ["API"]).help("The API to list the restrictions from"))
+            .arg(
+                arg!(api = ["API"])
+                    .help("The API to list the restrictions from")
+                    .value_parser(common::known_api),
+            )
             .arg(common::base64())
             .args(common::display_args())
     }
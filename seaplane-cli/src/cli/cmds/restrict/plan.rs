@@ -0,0 +1,72 @@
+use clap::{value_parser, ArgMatches, Command};
+use const_format::concatcp;
+
+use crate::{
+    api::RestrictReq,
+    cli::{
+        cmds::restrict::{common, common::SeaplaneRestrictPolicyArgMatches},
+        specs::{POLICY_SPEC, REGION_SPEC},
+        CliCommand,
+    },
+    context::{Ctx, RestrictCtx},
+    error::Result,
+    ops::restrict::RestrictPlan,
+    printer::{Output, OutputFormat},
+};
+
+static LONG_ABOUT: &str = "Show the changes needed to make the restrictions match a policy file
+
+The policy file is compared against the restrictions across all APIs. Restrictions which
+are missing are created, those whose details differ are updated, and those which are not
+in the policy are deleted. Restrictions with an API, region or provider unknown to this
+version of the CLI can't be part of a policy, so they are left as is. Nothing is changed,
+use 'seaplane restrict apply' to make the changes.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneRestrictPlan;
+
+impl SeaplaneRestrictPlan {
+    pub fn command() -> Command<'static> {
+        Command::new("plan")
+            .visible_alias("diff")
+            .override_usage("seaplane restrict plan <FILE> [OPTIONS]")
+            .about("Show the changes needed to make the restrictions match a policy file")
+            .long_about(LONG_ABOUT)
+            .arg(common::policy_file())
+            .arg(
+                arg!(--format =["FORMAT"=>"table"] global)
+                    .help("Change the output format")
+                    .value_parser(value_parser!(OutputFormat)),
+            )
+            .after_help(concatcp!(POLICY_SPEC, "\n\n", REGION_SPEC))
+    }
+}
+
+/// Compares the policy in the context against the restrictions across all APIs
+pub fn plan(ctx: &mut Ctx) -> Result<RestrictPlan> {
+    let mut req = RestrictReq::new(ctx)?;
+    let remote = req.get_all_pages()?;
+    let restrict_ctx = ctx.restrict_ctx.get_or_init();
+
+    Ok(RestrictPlan::new(&restrict_ctx.policy, &remote))
+}
+
+impl CliCommand for SeaplaneRestrictPlan {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let mut plan = plan(ctx)?;
+        plan.dry_run = true;
+
+        match ctx.args.out_format {
+            OutputFormat::Json => plan.print_json(ctx)?,
+            OutputFormat::Table => plan.print_table(ctx)?,
+        }
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.restrict_ctx
+            .init(RestrictCtx::from_restrict_policy(&SeaplaneRestrictPolicyArgMatches(matches))?);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        Ok(())
+    }
+}
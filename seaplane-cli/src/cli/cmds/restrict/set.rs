@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use clap::{ArgMatches, Command};
 use serde_json::json;
 
@@ -7,7 +9,7 @@ use crate::{
     context::{Ctx, RestrictCtx},
     error::Result,
     ops::EncodedString,
-    printer::OutputFormat,
+    printer::{OutputFormat, Pb},
 };
 
 /// A newtype wrapper to enforce where the ArgMatches came from which reduces errors in checking if
@@ -31,6 +33,12 @@ impl SeaplaneRestrictSet {
             .arg(common::directory())
             .arg(common::base64())
             .args(common::display_args())
+            .arg(arg!(--wait).help("Wait for the restriction to be enforced before returning"))
+            .arg(
+                common::timeout()
+                    .requires("wait")
+                    .help("Give up waiting for the restriction to be enforced after SECS seconds (requires --wait)"),
+            )
             .next_display_order(0)
             .next_help_heading("RESTRICTION DETAILS")
             .args(common::restriction_details())
@@ -42,7 +50,7 @@ impl SeaplaneRestrictSet {
 impl CliCommand for SeaplaneRestrictSet {
     fn run(&self, ctx: &mut Ctx) -> Result<()> {
         let mut req = RestrictReq::new(ctx)?;
-        let restrict_ctx = ctx.restrict_ctx.get_mut_or_init();
+        let restrict_ctx = ctx.restrict_ctx.get_or_init();
         let api = restrict_ctx.api.as_ref().unwrap();
         let mut dir = restrict_ctx.directory.as_ref().unwrap().to_string();
         let details = restrict_ctx.restriction_details()?;
@@ -51,12 +59,22 @@ impl CliCommand for SeaplaneRestrictSet {
         req.set_directory(&dir)?;
         req.set_restriction(details)?;
 
+        if restrict_ctx.wait {
+            let pb = Pb::new(ctx);
+            pb.set_message("Waiting for the restriction to be enforced...");
+            req.wait_until_enforced(restrict_ctx.timeout)?;
+            pb.finish_and_clear();
+        }
+
         if ctx.args.out_format == OutputFormat::Table {
             if restrict_ctx.decode {
                 let es = EncodedString::new(dir);
                 dir = String::from_utf8_lossy(&es.decoded()?).to_string()
             };
             cli_println!("Set a restriction on directory {} in {} API", dir, api);
+            if restrict_ctx.wait {
+                cli_println!("The restriction is enforced");
+            }
         } else {
             cli_println!("{}", json!({"set_restriction": {"api": api, "directory": dir} }))
         }
@@ -67,8 +85,13 @@ impl CliCommand for SeaplaneRestrictSet {
         ctx.restrict_ctx
             .init(RestrictCtx::from_restrict_set(&SeaplaneRestrictSetArgMatches(matches))?);
         ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
-        let mut restrict_ctx = ctx.restrict_ctx.get_mut_or_init();
+        let restrict_ctx = ctx.restrict_ctx.get_mut_or_init();
         restrict_ctx.decode = matches.contains_id("decode");
+        restrict_ctx.wait = matches.contains_id("wait");
+        restrict_ctx.timeout = matches
+            .get_one::<u64>("timeout")
+            .copied()
+            .map(Duration::from_secs);
         Ok(())
     }
 }
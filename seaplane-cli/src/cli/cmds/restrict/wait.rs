@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use clap::{ArgMatches, Command};
+
+use crate::{
+    api::RestrictReq,
+    cli::{cmds::restrict::common, CliCommand},
+    context::{Ctx, RestrictCtx},
+    error::Result,
+    printer::{Output, OutputFormat, Pb},
+};
+
+static LONG_ABOUT: &str = "Wait for a restriction on a directory to be enforced
+
+Newly set restrictions start out pending, and data written to the directory before the
+restriction is enforced may be placed anywhere. This waits until the restriction is
+enforced and then prints it.
+
+Directory will be displayed in base64 encoded format by default because they may contain
+arbitrary binary data. Use --decode to output the decoded values instead.";
+
+#[derive(Copy, Clone, Debug)]
+pub struct SeaplaneRestrictWait;
+
+impl SeaplaneRestrictWait {
+    pub fn command() -> Command<'static> {
+        Command::new("wait")
+            .override_usage("seaplane restrict wait <API> <DIRECTORY> [OPTIONS]")
+            .about("Wait for a restriction on a directory to be enforced")
+            .long_about(LONG_ABOUT)
+            .arg(common::api())
+            .arg(common::directory())
+            .arg(common::base64())
+            .arg(common::timeout())
+            .args(common::display_args())
+    }
+}
+
+impl CliCommand for SeaplaneRestrictWait {
+    fn run(&self, ctx: &mut Ctx) -> Result<()> {
+        let restriction = {
+            let mut req = RestrictReq::new(ctx)?;
+            let restrict_ctx = ctx.restrict_ctx.get_or_init();
+            req.set_api(restrict_ctx.api.as_ref().unwrap())?;
+            req.set_directory(restrict_ctx.directory.as_ref().unwrap().to_string())?;
+
+            let pb = Pb::new(ctx);
+            pb.set_message("Waiting for the restriction to be enforced...");
+            let restriction = req.wait_until_enforced(restrict_ctx.timeout)?;
+            pb.finish_and_clear();
+            restriction
+        };
+        match ctx.args.out_format {
+            OutputFormat::Json => restriction.print_json(ctx)?,
+            OutputFormat::Table => restriction.print_table(ctx)?,
+        }
+        Ok(())
+    }
+
+    fn update_ctx(&self, matches: &ArgMatches, ctx: &mut Ctx) -> Result<()> {
+        ctx.restrict_ctx.init(RestrictCtx::from_restrict_common(
+            &common::SeaplaneRestrictCommonArgMatches(matches),
+        )?);
+        ctx.args.out_format = matches.get_one("format").copied().unwrap_or_default();
+        let restrict_ctx = ctx.restrict_ctx.get_mut_or_init();
+        restrict_ctx.decode = matches.contains_id("decode");
+        restrict_ctx.no_header = matches.contains_id("no-header");
+        restrict_ctx.wait = true;
+        restrict_ctx.timeout = matches
+            .get_one::<u64>("timeout")
+            .copied()
+            .map(Duration::from_secs);
+        Ok(())
+    }
+}
@@ -62,3 +62,20 @@ pub const IMAGE_SPEC: &str = r#"IMAGE SPEC
 
     registry.cplane.cloud/busybox@sha256:7cc4b5aefd1d0cadf8d97d4350462ba51c694ebca145b08d7d41b41acc8db5aa
     registry.cplane.cloud/seaplane/busybox:latest"#;
+
+pub const POLICY_SPEC: &str = r#"POLICY FILE
+
+    A restriction policy file is a TOML file listing every restriction which should exist, for
+    example:
+
+    [[restriction]]
+    api = "config"
+    directory = "foo/bar"
+    regions-allowed = ["xe"]
+    providers-denied = ["aws"]
+
+    Each restriction requires an 'api' and 'directory', and may use 'regions-allowed',
+    'regions-denied', 'providers-allowed' and 'providers-denied'. Regions use the codes from
+    REGION SPEC. If the directory is already URL safe base64 encoded add 'base64 = true'.
+
+    Restrictions which exist but are not listed in the policy are deleted."#;
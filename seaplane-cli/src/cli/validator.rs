@@ -6,7 +6,7 @@ use std::{path::Path, result::Result as StdResult};
 use crate::{
     context::{FlightCtx, DEFAULT_IMAGE_REGISTRY_URL},
     error::CliErrorKind,
-    ops::formation::Endpoint,
+    ops::formation::{Endpoint, EndpointSrc},
 };
 
 pub fn validate_u64(s: &str) -> StdResult<(), String> {
@@ -25,6 +25,14 @@ pub fn validate_endpoint(s: &str) -> StdResult<(), String> {
     Ok(())
 }
 
+/// Ensures a valid Endpoint source (i.e. the part of an Endpoint before the '=')
+pub fn validate_endpoint_src(s: &str) -> StdResult<(), String> {
+    if let Err(e) = s.parse::<EndpointSrc>() {
+        return Err(format!("invalid endpoint: {e}"));
+    }
+    Ok(())
+}
+
 /// Ensures a valid Public Endpoint, we must special case Public Endpoints because it only supports
 /// the 'http' and 'https' protocol field.
 pub fn validate_public_endpoint(s: &str) -> StdResult<(), String> {
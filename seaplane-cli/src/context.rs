@@ -25,7 +25,9 @@
 pub mod flight;
 pub use flight::FlightCtx;
 pub mod formation;
-pub use formation::{FormationCfgCtx, FormationCtx};
+pub use formation::{FormationCfgCtx, FormationCfgRemovals, FormationCtx};
+pub mod image;
+pub use image::ImageCtx;
 pub mod metadata;
 pub use metadata::MetadataCtx;
 pub mod locks;
@@ -125,6 +127,9 @@ pub struct Ctx {
     /// Context relate to exclusively to Restrict operations and commands
     pub restrict_ctx: LateInit<RestrictCtx>,
 
+    /// Context relate to exclusively to container image operations and commands
+    pub image_ctx: LateInit<ImageCtx>,
+
     /// Where the configuration files were loaded from
     pub conf_files: Vec<PathBuf>,
 
@@ -194,6 +199,13 @@ impl Clone for Ctx {
             } else {
                 LateInit::default()
             },
+            image_ctx: if self.image_ctx.get().is_some() {
+                let li = LateInit::default();
+                li.init(self.image_ctx.get().cloned().unwrap());
+                li
+            } else {
+                LateInit::default()
+            },
             conf_files: self.conf_files.clone(),
             args: self.args.clone(),
             db: self.db.clone(),
@@ -220,6 +232,7 @@ impl Default for Ctx {
             md_ctx: LateInit::default(),
             locks_ctx: LateInit::default(),
             restrict_ctx: LateInit::default(),
+            image_ctx: LateInit::default(),
             conf_files: Vec::new(),
             args: Args::default(),
             db: Db::default(),
@@ -2,7 +2,10 @@ use seaplane::api::compute::v1::{Architecture, Flight as FlightModel, ImageRefer
 
 use crate::{
     cli::{
-        cmds::flight::{str_to_image_ref, SeaplaneFlightCommonArgMatches, FLIGHT_MINIMUM_DEFAULT},
+        cmds::flight::{
+            common::Architecture as ArchitectureArg, str_to_image_ref,
+            SeaplaneFlightCommonArgMatches, FLIGHT_MINIMUM_DEFAULT,
+        },
         validator::validate_flight_name,
     },
     context::Ctx,
@@ -25,6 +28,12 @@ pub struct FlightCtx {
     pub reset_maximum: bool,
     // True if we randomly generated the name. False if the user provided it
     pub generated_name: bool,
+    // Resolve the image tag to a digest before storing the Flight Plan
+    pub pin_digest: bool,
+    // Check the architectures against those the image is published for
+    pub check_arch: bool,
+    // Replace the architectures with those the image is published for
+    pub infer_arch: bool,
 }
 
 impl Default for FlightCtx {
@@ -38,6 +47,9 @@ impl Default for FlightCtx {
             api_permission: false,
             reset_maximum: false,
             generated_name: true,
+            pin_digest: false,
+            check_arch: false,
+            infer_arch: false,
         }
     }
 }
@@ -104,7 +116,13 @@ impl FlightCtx {
                 }
                 // @TODO technically archFOOBAR=.. is valid... oh well
                 arch if part.starts_with("arch") => {
-                    fctx.architecture.push(parse_item!(arch)?.parse()?);
+                    // Only architectures known to this version may be requested
+                    match parse_item!(arch)?.parse()? {
+                        Architecture::Unknown(_) => {
+                            return Err(strum::ParseError::VariantNotFound.into())
+                        }
+                        arch => fctx.architecture.push(arch),
+                    }
                 }
                 "api-permission" | "api-permissions" => {
                     fctx.api_permission = true;
@@ -174,14 +192,17 @@ impl FlightCtx {
                 // clap validates valid u64 prior to this
                 .map(|max| max.parse().expect("failed to parse valid u64")),
             architecture: matches
-                .get_many::<Architecture>("architecture")
+                .get_many::<ArchitectureArg>("architecture")
                 .unwrap_or_default()
-                .copied()
+                .map(ArchitectureArg::into_model)
                 .collect(),
             // because of clap overrides we only have to check api_permissions
             api_permission: matches.contains_id("api-permission"),
             reset_maximum: matches.contains_id("no-maximum"),
             generated_name,
+            pin_digest: false,
+            check_arch: false,
+            infer_arch: false,
         })
     }
 
@@ -210,7 +231,7 @@ impl FlightCtx {
         // Add all the architectures. In the CLI they're a Vec but in the Model they're a HashSet
         // which is the reason for the slightly awkward loop
         for arch in &self.architecture {
-            flight_model = flight_model.add_architecture(*arch);
+            flight_model = flight_model.add_architecture(arch.clone());
         }
 
         // Create a new Flight struct we can add to our local JSON "DB"
@@ -1,7 +1,7 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, time::Duration};
 
 use seaplane::api::{
-    compute::v1::FormationConfiguration as FormationConfigurationModel,
+    compute::v1::{Flight as FlightModel, FormationConfiguration as FormationConfigurationModel},
     shared::v1::{Provider as ProviderModel, Region as RegionModel},
 };
 
@@ -9,7 +9,11 @@ use crate::{
     cli::{cmds::formation::SeaplaneFormationPlanArgMatches, Provider, Region},
     context::Ctx,
     error::{CliError, CliErrorKind, Context, Result},
-    ops::{flight::Flights, formation::Endpoint, generate_formation_name},
+    ops::{
+        flight::Flights,
+        formation::{Endpoint, EndpointSrc, WaitFor},
+        generate_formation_name,
+    },
     printer::Color,
 };
 
@@ -41,8 +45,32 @@ pub struct FormationCtx {
     pub local: bool,
     pub grounded: bool,
     pub recursive: bool,
+    // Resolve image tags to digests before uploading
+    pub pin_digest: bool,
+    // Check Flight architectures against those their images are published for
+    pub check_arch: bool,
+    // Replace Flight architectures with those their images are published for
+    pub infer_arch: bool,
+    // Open the configuration in $EDITOR before saving
+    pub editor: bool,
+    // Omit the header of tables
+    pub no_header: bool,
+    // Wait for the remote Formation Instance to reach this state
+    pub wait_for: Option<WaitFor>,
+    // How long to wait for the remote Formation Instance before giving up
+    pub wait_timeout: Option<Duration>,
+    // Keep checking the remote Formation Instances for changes
+    pub watch: bool,
+    // The percentages of traffic to shift to a new configuration, one step at a time
+    pub rollout_steps: Vec<u8>,
+    // The history entry to roll back to, counting from 1
+    pub rollback_to: Option<usize>,
+    // How long to sleep between checks of the remote Formation Instances
+    pub interval: Duration,
     // TODO: make multiple possible
     pub cfg_ctx: FormationCfgCtx,
+    // Items to remove from an existing configuration
+    pub cfg_removals: FormationCfgRemovals,
 }
 
 impl Default for FormationCtx {
@@ -55,6 +83,18 @@ impl Default for FormationCtx {
             local: true,
             grounded: false,
             recursive: false,
+            pin_digest: false,
+            check_arch: false,
+            infer_arch: false,
+            editor: false,
+            no_header: false,
+            wait_for: None,
+            wait_timeout: None,
+            watch: false,
+            rollout_steps: Vec::new(),
+            rollback_to: None,
+            interval: Duration::from_secs(5),
+            cfg_removals: FormationCfgRemovals::default(),
         }
     }
 }
@@ -150,6 +190,15 @@ impl FormationCtx {
 
     /// Creates a new seaplane::api::compute::v1::FormationConfiguration from the contained values
     pub fn configuration_model(&self, ctx: &Ctx) -> Result<FormationConfigurationModel> {
+        self.build_configuration_model(ctx, &[])
+    }
+
+    /// Flights are looked up in the local Flight Plans first, and then in `known_flights`
+    fn build_configuration_model(
+        &self,
+        ctx: &Ctx,
+        known_flights: &[FlightModel],
+    ) -> Result<FormationConfigurationModel> {
         // Create the new Formation model from the CLI inputs
         let mut f_model = FormationConfigurationModel::builder();
 
@@ -158,22 +207,24 @@ impl FormationCtx {
                 .db
                 .flights
                 .find_name(flight_name)
+                .map(|f| &f.model)
+                .or_else(|| known_flights.iter().find(|f| f.name() == flight_name))
                 .ok_or_else(|| no_matching_flight(flight_name))?;
-            f_model = f_model.add_flight(flight.model.clone());
+            f_model = f_model.add_flight(flight.clone());
         }
 
         // TODO: clean this up...yuck
-        for &item in &self.cfg_ctx.providers_allowed {
-            f_model = f_model.add_allowed_provider(item);
+        for item in &self.cfg_ctx.providers_allowed {
+            f_model = f_model.add_allowed_provider(item.clone());
         }
-        for &item in &self.cfg_ctx.providers_denied {
-            f_model = f_model.add_denied_provider(item);
+        for item in &self.cfg_ctx.providers_denied {
+            f_model = f_model.add_denied_provider(item.clone());
         }
-        for &item in &self.cfg_ctx.regions_allowed {
-            f_model = f_model.add_allowed_region(item);
+        for item in &self.cfg_ctx.regions_allowed {
+            f_model = f_model.add_allowed_region(item.clone());
         }
-        for &item in &self.cfg_ctx.regions_denied {
-            f_model = f_model.add_denied_region(item);
+        for item in &self.cfg_ctx.regions_denied {
+            f_model = f_model.add_denied_region(item.clone());
         }
         for item in &self.cfg_ctx.public_endpoints {
             f_model = f_model.add_public_endpoint(item.key(), item.value());
@@ -181,22 +232,33 @@ impl FormationCtx {
         for item in &self.cfg_ctx.flight_endpoints {
             f_model = f_model.add_flight_endpoint(item.key(), item.value());
         }
-        #[cfg(feature = "unstable")]
-        {
-            for item in &self.cfg_ctx.affinities {
-                f_model = f_model.add_affinity(item);
-            }
-            for item in &self.cfg_ctx.connections {
-                f_model = f_model.add_connection(item);
-            }
-            for item in &self.cfg_ctx.formation_endpoints {
-                f_model = f_model.add_formation_endpoint(item.key(), item.value());
-            }
+        // Only editing these requires the unstable feature, existing values are always kept
+        for item in &self.cfg_ctx.affinities {
+            f_model = f_model.add_affinity(item);
+        }
+        for item in &self.cfg_ctx.connections {
+            f_model = f_model.add_connection(item);
+        }
+        for item in &self.cfg_ctx.formation_endpoints {
+            f_model = f_model.add_formation_endpoint(item.key(), item.value());
         }
 
         // TODO: probably match and check errors
         f_model.build().map_err(Into::into)
     }
+
+    /// Creates a new seaplane::api::compute::v1::FormationConfiguration by applying the contained
+    /// additions and removals to an existing configuration. Flights without a local Flight Plan
+    /// keep their definition from the existing configuration.
+    pub fn edited_configuration_model(
+        &self,
+        ctx: &Ctx,
+        base: &FormationConfigurationModel,
+    ) -> Result<FormationConfigurationModel> {
+        let mut cfg_ctx = FormationCfgCtx::from_model(base);
+        cfg_ctx.apply_edits(&self.cfg_removals, &self.cfg_ctx);
+        Self { cfg_ctx, ..self.clone() }.build_configuration_model(ctx, base.flights())
+    }
 }
 
 #[derive(Default, Debug, Clone)]
@@ -219,3 +281,103 @@ pub struct FormationCfgCtx {
     pub formation_endpoints: Vec<Endpoint>,
     pub flight_endpoints: Vec<Endpoint>,
 }
+
+impl FormationCfgCtx {
+    /// Creates the context that would have produced an existing configuration
+    pub fn from_model(model: &FormationConfigurationModel) -> Self {
+        Self {
+            flights: model
+                .flights()
+                .iter()
+                .map(|f| f.name().to_owned())
+                .collect(),
+            affinities: model.affinities().to_vec(),
+            connections: model.connections().to_vec(),
+            providers_allowed: model.allowed_providers().cloned().collect(),
+            providers_denied: model.denied_providers().cloned().collect(),
+            regions_allowed: model.allowed_regions().cloned().collect(),
+            regions_denied: model.denied_region().cloned().collect(),
+            public_endpoints: model.public_endpoints().map(Endpoint::from).collect(),
+            formation_endpoints: model.formation_endpoints().map(Endpoint::from).collect(),
+            flight_endpoints: model.flight_endpoints().map(Endpoint::from).collect(),
+        }
+    }
+
+    /// Removes everything listed in `removals` and then adds everything listed in `additions`.
+    /// Added endpoints replace any existing endpoints with the same source.
+    pub fn apply_edits(&mut self, removals: &FormationCfgRemovals, additions: &FormationCfgCtx) {
+        fn edit_list(list: &mut Vec<String>, remove: &[String], add: &[String]) {
+            list.retain(|item| !remove.contains(item));
+            for item in add {
+                if !list.contains(item) {
+                    list.push(item.clone());
+                }
+            }
+        }
+        fn edit_set<T: Clone + Eq + std::hash::Hash>(
+            set: &mut HashSet<T>,
+            remove: &HashSet<T>,
+            add: &HashSet<T>,
+        ) {
+            set.retain(|item| !remove.contains(item));
+            set.extend(add.iter().cloned());
+        }
+        fn edit_endpoints(list: &mut Vec<Endpoint>, remove: &[EndpointSrc], add: &[Endpoint]) {
+            let removed: Vec<_> = remove
+                .iter()
+                .map(EndpointSrc::key)
+                .chain(add.iter().map(Endpoint::key))
+                .collect();
+            list.retain(|ep| !removed.contains(&ep.key()));
+            list.extend(add.iter().cloned());
+        }
+
+        edit_list(&mut self.flights, &removals.flights, &additions.flights);
+        edit_list(&mut self.affinities, &removals.affinities, &additions.affinities);
+        edit_list(&mut self.connections, &removals.connections, &additions.connections);
+        edit_set(
+            &mut self.providers_allowed,
+            &removals.providers_allowed,
+            &additions.providers_allowed,
+        );
+        edit_set(
+            &mut self.providers_denied,
+            &removals.providers_denied,
+            &additions.providers_denied,
+        );
+        edit_set(&mut self.regions_allowed, &removals.regions_allowed, &additions.regions_allowed);
+        edit_set(&mut self.regions_denied, &removals.regions_denied, &additions.regions_denied);
+        edit_endpoints(
+            &mut self.public_endpoints,
+            &removals.public_endpoints,
+            &additions.public_endpoints,
+        );
+        edit_endpoints(
+            &mut self.formation_endpoints,
+            &removals.formation_endpoints,
+            &additions.formation_endpoints,
+        );
+        edit_endpoints(
+            &mut self.flight_endpoints,
+            &removals.flight_endpoints,
+            &additions.flight_endpoints,
+        );
+    }
+}
+
+/// The items `seaplane formation edit` removes from an existing configuration
+#[derive(Default, Debug, Clone)]
+pub struct FormationCfgRemovals {
+    /// `String` is a flight name because that's the only thing shared by both local and remote
+    pub flights: Vec<String>,
+    pub affinities: Vec<String>,
+    pub connections: Vec<String>,
+    pub providers_allowed: HashSet<ProviderModel>,
+    pub providers_denied: HashSet<ProviderModel>,
+    pub regions_allowed: HashSet<RegionModel>,
+    pub regions_denied: HashSet<RegionModel>,
+    /// Endpoints are removed by their source alone
+    pub public_endpoints: Vec<EndpointSrc>,
+    pub formation_endpoints: Vec<EndpointSrc>,
+    pub flight_endpoints: Vec<EndpointSrc>,
+}
@@ -0,0 +1,31 @@
+use seaplane::api::compute::v1::ImageReference;
+
+use crate::{
+    cli::cmds::{flight::str_to_image_ref, image::SeaplaneImageCommonArgMatches},
+    error::Result,
+};
+
+/// Represents the "Source of Truth" i.e. it combines all the CLI options, ENV vars, and config
+/// values into a single structure that can be used later to build models for the API or local
+/// structs for serializing
+#[derive(Debug, Default, Clone)]
+pub struct ImageCtx {
+    /// The container image to operate on, with the default registry filled in when omitted
+    pub reference: Option<ImageReference>,
+    /// Skip the headers in --format=table
+    pub no_header: bool,
+    /// Re-resolve images which are already pinned to a digest
+    pub update: bool,
+}
+
+impl ImageCtx {
+    /// Builds an ImageCtx from ArgMatches
+    pub fn from_image_common(
+        matches: &SeaplaneImageCommonArgMatches,
+        registry: &str,
+    ) -> Result<ImageCtx> {
+        let image = matches.0.get_one::<String>("image").unwrap();
+
+        Ok(ImageCtx { reference: Some(str_to_image_ref(registry, image)?), ..Default::default() })
+    }
+}
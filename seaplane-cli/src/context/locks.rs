@@ -1,6 +1,12 @@
+use std::time::Duration;
+
 use seaplane::api::locks::v1::LockId;
 
-use crate::{cli::cmds::locks::SeaplaneLocksCommonArgMatches, error::Result, ops::locks::LockName};
+use crate::{
+    cli::cmds::locks::SeaplaneLocksCommonArgMatches,
+    error::Result,
+    ops::locks::{LockFilter, LockName},
+};
 
 /// Represents the "Source of Truth" i.e. it combines all the CLI options, ENV vars, and config
 /// values into a single structure that can be used later to build models for the API or local
@@ -17,6 +23,18 @@ pub struct LocksCtx {
     pub decode: bool,
     /// Skip the KEY or VALUE header in --format=table
     pub no_header: bool,
+    /// The command (and arguments) to run while holding the lock
+    pub command: Vec<String>,
+    /// Wait for the lock to be free instead of failing if it is held
+    pub wait: bool,
+    /// How long to wait for the lock before giving up, or forever if `None`
+    pub timeout: Option<Duration>,
+    /// Stop campaigning for leadership once it's lost
+    pub exit_on_loss: bool,
+    /// The number of slots of a semaphore
+    pub slots: Option<u32>,
+    /// Only list locks matching these filters
+    pub filter: LockFilter,
 }
 
 impl LocksCtx {
@@ -1,17 +1,30 @@
 use std::{
-    fs::File,
+    env,
+    fs::{self, File},
     io::{self, Read},
+    path::Path,
 };
 
-use seaplane::api::{metadata::v1::Key, shared::v1::Directory};
+use clap::ArgMatches;
+use seaplane::api::{
+    metadata::v1::{Key, Keyring},
+    shared::v1::Directory,
+};
 
 use crate::{
-    cli::cmds::metadata::{SeaplaneMetadataCommonArgMatches, SeaplaneMetadataSetArgMatches},
-    error::{CliError, Context, Result},
+    cli::cmds::metadata::{
+        SeaplaneMetadataCommonArgMatches, SeaplaneMetadataSetArgMatches,
+        SeaplaneMetadataSyncArgMatches,
+    },
+    error::{CliError, CliErrorKind, Context, Result},
     ops::metadata::{KeyValue, KeyValues},
     printer::Color,
 };
 
+/// Environment variable which may hold the metadata encryption keys directly, as opposed to in a
+/// file
+pub const METADATA_KEYS_ENV: &str = "SEAPLANE_METADATA_KEYS";
+
 /// Represents the "Source of Truth" i.e. it combines all the CLI options, ENV vars, and config
 /// values into a single structure that can be used later to build models for the API or local
 /// structs for serializing
@@ -40,6 +53,14 @@ pub struct MetadataCtx {
     pub keys_width_limit: usize,
     /// Max width of values
     pub values_width_limit: usize,
+    /// Keys used to encrypt or decrypt values, if any were provided
+    pub keyring: Option<Keyring>,
+    /// Encrypt values before uploading them
+    pub encrypt: bool,
+    /// Remove remote keys which do not exist locally
+    pub delete: bool,
+    /// Only print the changes that would be made
+    pub dry_run: bool,
 }
 
 impl MetadataCtx {
@@ -67,6 +88,25 @@ impl MetadataCtx {
         })
     }
 
+    /// Loads the encryption keys from the file given by `--key-file`, or failing that the
+    /// `SEAPLANE_METADATA_KEYS` environment variable.
+    ///
+    /// Returns `Ok(None)` if no keys were provided at all.
+    pub fn keyring_from_matches(matches: &ArgMatches) -> Result<Option<Keyring>> {
+        let keys = if let Some(path) = matches.get_one::<String>("key-file") {
+            fs::read_to_string(path)
+                .map_err(CliError::from)
+                .context("\n\tpath: ")
+                .with_color_context(|| (Color::Yellow, path))?
+        } else if let Ok(keys) = env::var(METADATA_KEYS_ENV) {
+            keys
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(keys.parse::<Keyring>()?))
+    }
+
     /// Builds a MetadataCtx from ArgMatches
     pub fn from_md_set(matches: &SeaplaneMetadataSetArgMatches) -> Result<MetadataCtx> {
         let matches = matches.0;
@@ -96,7 +136,27 @@ impl MetadataCtx {
             raw_value.as_bytes().to_vec()
         };
 
-        let kv = if base64 {
+        let keyring = MetadataCtx::keyring_from_matches(matches)?;
+
+        let kv = if matches.contains_id("encrypt") {
+            let keyring = keyring.as_ref().ok_or_else(|| {
+                CliErrorKind::CliArgNotUsed("--key-file")
+                    .into_err()
+                    .context("(hint: keys may also be provided with the ")
+                    .color_context(Color::Yellow, METADATA_KEYS_ENV)
+                    .context(" environment variable)\n")
+            })?;
+            let (key, plaintext) = if base64 {
+                let _ = base64::decode_config(raw_key, base64::URL_SAFE_NO_PAD)?;
+                (raw_key.to_owned(), base64::decode_config(&value, base64::URL_SAFE_NO_PAD)?)
+            } else {
+                (base64::encode_config(raw_key, base64::URL_SAFE_NO_PAD), value)
+            };
+            KeyValue::new(
+                key,
+                base64::encode_config(keyring.encrypt(plaintext)?, base64::URL_SAFE_NO_PAD),
+            )
+        } else if base64 {
             // make sure it's valid base64
             let _ = base64::decode_config(raw_key, base64::URL_SAFE_NO_PAD)?;
             let _ = base64::decode_config(&value, base64::URL_SAFE_NO_PAD)?;
@@ -110,9 +170,96 @@ impl MetadataCtx {
             )
         };
 
+        kv.validate()?;
+        // The value has been encrypted by now, so this is the size that will actually be stored
+        kv.warn_if_large()?;
         let mut kvs = KeyValues::default();
         kvs.push(kv);
 
-        Ok(MetadataCtx { kvs, base64: true, ..MetadataCtx::default() })
+        Ok(MetadataCtx { kvs, base64: true, keyring, ..MetadataCtx::default() })
+    }
+
+    /// Builds a MetadataCtx from ArgMatches, reading every file of the local directory into a
+    /// key-value pair whose key is the file's path relative to the local directory, prefixed with
+    /// the remote directory
+    pub fn from_md_sync(matches: &SeaplaneMetadataSyncArgMatches) -> Result<MetadataCtx> {
+        let matches = matches.0;
+        let local_dir = matches.get_one::<String>("local_dir").unwrap();
+        let remote_dir = matches
+            .get_one::<String>("remote_dir")
+            .unwrap()
+            .trim_end_matches('/');
+
+        let mut files = Vec::new();
+        read_dir_files(Path::new(local_dir), "", &mut files)?;
+
+        let mut kvs = KeyValues::default();
+        for (path, contents) in files {
+            let kv = KeyValue::new(
+                base64::encode_config(format!("{remote_dir}/{path}"), base64::URL_SAFE_NO_PAD),
+                base64::encode_config(contents, base64::URL_SAFE_NO_PAD),
+            );
+            kv.validate()?;
+            kvs.push(kv);
+        }
+
+        let encrypt = matches.contains_id("encrypt");
+        let keyring = MetadataCtx::keyring_from_matches(matches)?;
+        if encrypt && keyring.is_none() {
+            return Err(CliErrorKind::CliArgNotUsed("--key-file")
+                .into_err()
+                .context("(hint: keys may also be provided with the ")
+                .color_context(Color::Yellow, METADATA_KEYS_ENV)
+                .context(" environment variable)\n"));
+        }
+
+        Ok(MetadataCtx {
+            kvs,
+            directory: Some(Directory::from_unencoded(remote_dir)),
+            base64: true,
+            keyring,
+            encrypt,
+            delete: matches.contains_id("delete"),
+            dry_run: matches.contains_id("dry-run"),
+            ..MetadataCtx::default()
+        })
     }
 }
+
+/// Recursively reads all files below `dir` into `files` as pairs of the path relative to the
+/// original directory (using `/` as the separator) and the file contents. Hidden files and
+/// directories (i.e. those starting with a `.`) are skipped. The output is sorted by path.
+fn read_dir_files(dir: &Path, prefix: &str, files: &mut Vec<(String, Vec<u8>)>) -> Result<()> {
+    let mut entries = fs::read_dir(dir)
+        .and_then(|entries| entries.collect::<io::Result<Vec<_>>>())
+        .map_err(CliError::from)
+        .context("\n\tpath: ")
+        .with_color_context(|| (Color::Yellow, dir.display().to_string()))?;
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let name = entry.file_name();
+        let name = name.to_str().ok_or_else(|| {
+            CliError::bail("file names must be valid UTF-8")
+                .context("\n\tpath: ")
+                .color_context(Color::Yellow, entry.path().display().to_string())
+        })?;
+        if name.starts_with('.') {
+            continue;
+        }
+
+        let path = format!("{prefix}{name}");
+        // Uses fs::metadata over DirEntry::file_type in order to follow symlinks
+        if fs::metadata(entry.path())?.is_dir() {
+            read_dir_files(&entry.path(), &format!("{path}/"), files)?;
+        } else {
+            let contents = fs::read(entry.path())
+                .map_err(CliError::from)
+                .context("\n\tpath: ")
+                .with_color_context(|| (Color::Yellow, entry.path().display().to_string()))?;
+            files.push((path, contents));
+        }
+    }
+
+    Ok(())
+}
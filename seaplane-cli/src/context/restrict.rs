@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, time::Duration};
 
 use seaplane::api::{
     restrict::v1::{RestrictedDirectory, RestrictionDetails},
@@ -7,11 +7,12 @@ use seaplane::api::{
 
 use crate::{
     cli::cmds::restrict::{
-        common::{Provider, Region},
-        SeaplaneRestrictCommonArgMatches, SeaplaneRestrictListArgMatches,
-        SeaplaneRestrictSetArgMatches,
+        common::{Provider, Region, SeaplaneRestrictPolicyArgMatches},
+        SeaplaneRestrictCommonArgMatches, SeaplaneRestrictExplainArgMatches,
+        SeaplaneRestrictListArgMatches, SeaplaneRestrictSetArgMatches,
     },
     error::Result,
+    ops::{restrict::RestrictionPolicy, EncodedString},
 };
 
 /// Represents the "Source of Truth" i.e. it combines all the CLI options, ENV vars, and config
@@ -40,6 +41,14 @@ pub struct RestrictCtx {
     pub decode: bool,
     /// Skip the headers in --format=table
     pub no_header: bool,
+    /// A base64 encoded metadata key or lock name
+    pub key: Option<EncodedString>,
+    /// The restrictions read from a policy file
+    pub policy: RestrictionPolicy,
+    /// Wait for the restriction to be enforced
+    pub wait: bool,
+    /// How long to wait for the restriction to be enforced, forever if `None`
+    pub timeout: Option<Duration>,
 }
 
 impl RestrictCtx {
@@ -71,6 +80,34 @@ impl RestrictCtx {
         Ok(RestrictCtx { api, ..RestrictCtx::default() })
     }
 
+    /// Builds a RestictCtx from ArgMatches
+    pub fn from_restrict_explain(
+        matches: &SeaplaneRestrictExplainArgMatches,
+    ) -> Result<RestrictCtx> {
+        let matches = matches.0;
+        let api = matches.get_one::<String>("api").unwrap();
+        let key = matches.get_one::<String>("key").unwrap();
+
+        Ok(RestrictCtx {
+            api: Some(api.into()),
+            key: Some(if matches.contains_id("base64") {
+                // Check that what the user passed really is valid base64
+                let _ = base64::decode_config(key, base64::URL_SAFE_NO_PAD)?;
+                EncodedString::new(key.into())
+            } else {
+                EncodedString::new(base64::encode_config(key, base64::URL_SAFE_NO_PAD))
+            }),
+            ..RestrictCtx::default()
+        })
+    }
+
+    /// Builds a RestictCtx from ArgMatches, reading the policy file
+    pub fn from_restrict_policy(matches: &SeaplaneRestrictPolicyArgMatches) -> Result<RestrictCtx> {
+        let path = matches.0.get_one::<String>("file").unwrap();
+
+        Ok(RestrictCtx { policy: RestrictionPolicy::load(path)?, ..RestrictCtx::default() })
+    }
+
     /// Builds a RestictCtx from ArgMatches
     pub fn from_restrict_set(matches: &SeaplaneRestrictSetArgMatches) -> Result<RestrictCtx> {
         let matches = matches.0;
@@ -120,16 +157,16 @@ impl RestrictCtx {
         let mut builder = RestrictionDetails::builder();
 
         for item in &self.providers_allowed {
-            builder = builder.add_allowed_provider(*item);
+            builder = builder.add_allowed_provider(item.clone());
         }
         for item in &self.providers_denied {
-            builder = builder.add_denied_provider(*item);
+            builder = builder.add_denied_provider(item.clone());
         }
         for item in &self.regions_allowed {
-            builder = builder.add_allowed_region(*item);
+            builder = builder.add_allowed_region(item.clone());
         }
         for item in &self.regions_denied {
-            builder = builder.add_denied_region(*item);
+            builder = builder.add_denied_region(item.clone());
         }
 
         Ok(builder.build()?)
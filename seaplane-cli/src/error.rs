@@ -12,6 +12,7 @@ use seaplane::{
 
 use crate::{
     log::{log_level, LogLevel},
+    ops::formation::WaitFor,
     printer::{eprinter, Color},
 };
 
@@ -252,6 +253,13 @@ pub enum CliErrorKind {
     StrumParse(strum::ParseError),
     FlightsInUse(Vec<String>),
     EndpointInvalidFlight(String),
+    EndpointUnknownFlight(String, String),
+    EndpointCollision(String),
+    FormationWaitTimeout(String, WaitFor),
+    FormationRolloutFailed(Vec<String>),
+    FormationPlacementViolations(String, usize),
+    ImageMissingArchitectures(Vec<String>),
+    ChildFailed(Option<i32>),
     OneOff(String),
 }
 
@@ -263,6 +271,12 @@ impl CliErrorKind {
             OneOff(msg) => {
                 cli_eprintln!("{msg}");
             }
+            ChildFailed(Some(code)) => {
+                cli_eprintln!("the command exited with status {code}");
+            }
+            ChildFailed(None) => {
+                cli_eprintln!("the command was terminated by a signal");
+            }
             FlightsInUse(flights) => {
                 cli_eprintln!("the following Flight Plans are referenced by a Formation Plan and cannot be deleted");
                 for f in flights {
@@ -280,6 +294,43 @@ impl CliErrorKind {
                     "' is referenced in an endpoint but does not exist in the local Plans"
                 );
             }
+            EndpointUnknownFlight(endpoint, flight) => {
+                cli_eprint!("endpoint '");
+                cli_eprint!(@Yellow, "{endpoint}");
+                cli_eprint!("' routes to Flight Plan '");
+                cli_eprint!(@Red, "{flight}");
+                cli_eprintln!("' which is not part of the Formation Configuration");
+            }
+            EndpointCollision(endpoint) => {
+                cli_eprint!("endpoint '");
+                cli_eprint!(@Red, "{endpoint}");
+                cli_eprintln!("' is used by more than one public, formation or flight endpoint");
+            }
+            FormationWaitTimeout(name, target) => {
+                cli_eprint!("timed out waiting for Formation Instance '");
+                cli_eprint!(@Yellow, "{name}");
+                cli_eprint!("' to be ");
+                cli_eprintln!(@Red, "{target}");
+            }
+            FormationRolloutFailed(flights) => {
+                cli_eprintln!("the new configuration failed its health check, the previously active configurations were restored");
+                cli_eprintln!("the following Flights have errored containers or are not running their minimum");
+                for f in flights {
+                    cli_eprintln!(@Yellow, "\t{f}");
+                }
+            }
+            FormationPlacementViolations(name, count) => {
+                cli_eprint!(@Red, "{count}");
+                cli_eprint!(" container(s) of Formation Instance '");
+                cli_eprint!(@Yellow, "{name}");
+                cli_eprintln!(
+                    "' run outside of the provider or region constraints of their configuration"
+                );
+            }
+            ImageMissingArchitectures(archs) => {
+                cli_eprint!("the image is not published for the declared architecture(s) ");
+                cli_eprintln!(@Red, "{}", archs.join(", "));
+            }
             ConflictingArguments(a, b) => {
                 cli_eprint!("cannot use '");
                 cli_eprint!(@Yellow, "{a}");
@@ -386,6 +437,26 @@ impl CliErrorKind {
                         cli_eprintln!("')");
                     }
                 }
+                SeaplaneError::MissingEncryptionKey(_) => {
+                    cli_eprintln!("{e}");
+                    cli_eprint!("(hint: add the missing key version to the file given by '");
+                    cli_eprint!(@Yellow, "--key-file");
+                    cli_eprintln!("')");
+                }
+                SeaplaneError::MetadataEncryption(_)
+                | SeaplaneError::InvalidMetadata(_)
+                | SeaplaneError::SemaphoreFull(_)
+                | SeaplaneError::MissingImageDigest
+                | SeaplaneError::MissingRegistryToken
+                | SeaplaneError::UnsupportedImageManifest(_) => {
+                    cli_eprintln!("{e}")
+                }
+                SeaplaneError::LockWaitTimeout | SeaplaneError::RestrictionWaitTimeout => {
+                    cli_eprintln!("{e}");
+                    cli_eprint!("(hint: increase the time to wait with '");
+                    cli_eprint!(@Yellow, "--timeout");
+                    cli_eprintln!("' or omit it to wait indefinitely)");
+                }
                 _ => {
                     cli_eprintln!("Seaplane API: {e}")
                 }
@@ -429,6 +500,12 @@ impl PartialEq<Self> for CliErrorKind {
         match self {
             OneOff(_) => matches!(rhs, OneOff(_)),
             EndpointInvalidFlight(_) => matches!(rhs, EndpointInvalidFlight(_)),
+            EndpointUnknownFlight(_, _) => matches!(rhs, EndpointUnknownFlight(_, _)),
+            EndpointCollision(_) => matches!(rhs, EndpointCollision(_)),
+            FormationWaitTimeout(..) => matches!(rhs, FormationWaitTimeout(..)),
+            FormationRolloutFailed(_) => matches!(rhs, FormationRolloutFailed(_)),
+            FormationPlacementViolations(..) => matches!(rhs, FormationPlacementViolations(..)),
+            ImageMissingArchitectures(_) => matches!(rhs, ImageMissingArchitectures(_)),
             AmbiguousItem(_) => matches!(rhs, AmbiguousItem(_)),
             Io(_, _) => matches!(rhs, Io(_, _)),
             DuplicateName(_) => matches!(rhs, DuplicateName(_)),
@@ -459,6 +536,7 @@ impl PartialEq<Self> for CliErrorKind {
             InlineFlightMissingValue(_) => matches!(rhs, InlineFlightMissingValue(_)),
             ParseInt(_) => matches!(rhs, ParseInt(_)),
             FlightsInUse(_) => matches!(rhs, FlightsInUse(_)),
+            ChildFailed(_) => matches!(rhs, ChildFailed(_)),
         }
     }
 }
@@ -497,4 +575,27 @@ impl CliError {
     }
 
     pub fn kind(&self) -> &CliErrorKind { &self.kind }
+
+    /// Returns true if the request that failed may succeed when tried again later, i.e. network
+    /// failures or the API being temporarily unavailable
+    pub fn is_transient(&self) -> bool {
+        match &self.kind {
+            CliErrorKind::Seaplane(SeaplaneError::UnknownHttp(_)) => true,
+            CliErrorKind::Seaplane(SeaplaneError::ApiResponse(ae)) => match &ae.kind {
+                ApiErrorKind::InternalServerError | ApiErrorKind::ServiceUnavailable => true,
+                ApiErrorKind::UnimplementedHttpStatus(code) => {
+                    code.is_server_error() || code.as_u16() == 429
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Sets the exit status used when exiting due to this error
+    #[must_use]
+    pub fn with_status(mut self, status: i32) -> Self {
+        self.status = Some(status);
+        self
+    }
 }
@@ -415,7 +415,9 @@ macro_rules! maybe_retry {
                         $this.identity_url.as_ref(),
                         $this.insecure_urls,
                         $this.invalid_certs)?);
-                Ok(req.$fn($( $arg ,)*)?)
+                // The inner request still carries the expired token, so it has to be rebuilt
+                $this.refresh_inner()?;
+                Ok($this.inner.as_mut().unwrap().$fn($( $arg ,)*)?)
             }
             Err(e) => Err(e),
         };
@@ -4,6 +4,7 @@
 pub mod encoded_string;
 pub mod flight;
 pub mod formation;
+pub mod image;
 pub mod locks;
 pub mod metadata;
 pub mod restrict;
@@ -57,3 +58,23 @@ impl fmt::Display for Id {
 impl fmt::Debug for Id {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "Id [ {} ]", self) }
 }
+
+/// The change required to bring a single remote item (such as a metadata key or a restriction) in
+/// line with the local copy
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncAction {
+    Create,
+    Update,
+    Delete,
+}
+
+impl SyncAction {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            SyncAction::Create => "+",
+            SyncAction::Update => "~",
+            SyncAction::Delete => "-",
+        }
+    }
+}
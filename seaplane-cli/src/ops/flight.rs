@@ -4,7 +4,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use seaplane::api::compute::v1::Flight as FlightModel;
+use seaplane::api::compute::v1::{Architecture, Flight as FlightModel, ImageReference};
 use serde::{Deserialize, Serialize};
 use tabwriter::TabWriter;
 
@@ -66,7 +66,7 @@ impl Flight {
 
         // Architecture
         for arch in ctx.architecture.iter().chain(self.model.architecture()) {
-            dest_builder = dest_builder.add_architecture(*arch);
+            dest_builder = dest_builder.add_architecture(arch.clone());
         }
 
         // API Permission
@@ -191,6 +191,8 @@ impl Flights {
 
     pub fn iter(&self) -> impl Iterator<Item = &Flight> { self.inner.iter() }
 
+    pub fn get_mut(&mut self, idx: usize) -> Option<&mut Flight> { self.inner.get_mut(idx) }
+
     pub fn clone_flight(&mut self, src: &str, exact: bool) -> Result<Flight> {
         let src_flight = self.remove_flight(src, exact)?;
         let model = src_flight.model.clone();
@@ -216,7 +218,7 @@ impl Flights {
             flight.model.set_maximum(model.maximum());
 
             for arch in model.architecture() {
-                flight.model.add_architecture(*arch);
+                flight.model.add_architecture(arch.clone());
             }
 
             #[cfg(feature = "unstable")]
@@ -234,14 +236,51 @@ impl Flights {
         ret
     }
 
-    pub fn update_flight(&mut self, src: &str, exact: bool, ctx: &FlightCtx) -> Result<()> {
+    /// Updates the matching flight from `ctx`, returning its ID
+    pub fn update_flight(&mut self, src: &str, exact: bool, ctx: &FlightCtx) -> Result<Id> {
         let mut src_flight = self.remove_flight(src, exact)?;
         src_flight.update_from(ctx, ctx.generated_name)?;
+        let id = src_flight.id;
 
         // Re add the source flight
         self.inner.push(src_flight);
 
-        Ok(())
+        Ok(id)
+    }
+
+    /// Replaces the image of every flight named `name` whose image matches `image` (i.e. the same
+    /// image before it was pinned to a digest) with `image`. Returns the number of flights updated.
+    pub fn record_pinned_image(&mut self, name: &str, image: &ImageReference) -> usize {
+        let mut updated = 0;
+        for flight in self
+            .inner
+            .iter_mut()
+            .filter(|f| f.model.name() == name && f.model.image() == image)
+        {
+            flight.model.set_image(image.clone());
+            updated += 1;
+        }
+        updated
+    }
+
+    /// Replaces the architectures of every flight named `name` whose image matches `image` with
+    /// `archs`. Returns the number of flights updated.
+    pub fn record_architectures(
+        &mut self,
+        name: &str,
+        image: &ImageReference,
+        archs: &[Architecture],
+    ) -> usize {
+        let mut updated = 0;
+        for flight in self
+            .inner
+            .iter_mut()
+            .filter(|f| f.model.name() == name && f.model.image() == image)
+        {
+            flight.model.set_architecture(archs.to_vec());
+            updated += 1;
+        }
+        updated
     }
 
     pub fn add_flight(&mut self, flight: Flight) { self.inner.push(flight); }
@@ -260,6 +299,12 @@ impl Flights {
         Ok(self.remove_indices(&indices).pop().unwrap())
     }
 
+    pub fn find_id(&self, id: &Id) -> Option<&Flight> { self.inner.iter().find(|f| &f.id == id) }
+
+    pub fn find_id_mut(&mut self, id: &Id) -> Option<&mut Flight> {
+        self.inner.iter_mut().find(|f| &f.id == id)
+    }
+
     pub fn find_name(&self, name: &str) -> Option<&Flight> {
         self.inner.iter().find(|f| f.model.name() == name)
     }
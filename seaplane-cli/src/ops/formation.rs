@@ -1,11 +1,16 @@
 mod endpoint;
+mod history;
+mod placement;
 use std::{
     collections::HashSet,
     io::Write,
     path::{Path, PathBuf},
 };
 
+use chrono::{DateTime, Duration, Utc};
 pub use endpoint::*;
+pub use history::*;
+pub use placement::*;
 use seaplane::api::compute::v1::{
     Container as ContainerModel, ContainerStatus, Flight as FlightModel,
     FormationConfiguration as FormationConfigurationModel,
@@ -16,7 +21,7 @@ use uuid::Uuid;
 
 use crate::{
     context::Ctx,
-    error::{CliError, Result},
+    error::{CliError, CliErrorKind, Context, Result},
     fs::{FromDisk, ToDisk},
     ops::Id,
     printer::Output,
@@ -78,6 +83,10 @@ impl Formations {
         self.configurations.iter().find(|fc| &fc.id == id)
     }
 
+    pub fn get_configuration_mut(&mut self, id: &Id) -> Option<&mut FormationConfiguration> {
+        self.configurations.iter_mut().find(|fc| &fc.id == id)
+    }
+
     /// Returns the removed FormationConfiguration by ID or None if there was no match
     ///
     /// DANGER: this will invalidate any previously held indices after the removed item
@@ -96,6 +105,24 @@ impl Formations {
         self.formations.get_mut(idx)
     }
 
+    /// Duplicates the Formation at `idx` as a new local only Formation Plan named `name`, copying
+    /// each of its configurations under a fresh ID. Returns the ID of the new Formation, or None if
+    /// there is no Formation at `idx`
+    pub fn clone_formation(&mut self, idx: usize, name: &str) -> Option<Id> {
+        let src_ids: HashSet<Id> = self.formations.get(idx)?.configs().into_iter().collect();
+        let mut dest = Formation::new(name);
+        for id in src_ids {
+            if let Some(cfg) = self.get_configuration(&id) {
+                let cfg = FormationConfiguration::new(cfg.model.clone());
+                dest.local.insert(cfg.id);
+                self.configurations.push(cfg);
+            }
+        }
+        let id = dest.id;
+        self.formations.push(dest);
+        Some(id)
+    }
+
     /// Either updates a matching local Formation Configurations, or creates a new one. Returns the
     /// existing ID of the config that was updated if any
     pub fn update_or_create_configuration(&mut self, cfg: FormationConfiguration) -> Option<Id> {
@@ -148,6 +175,26 @@ impl Formations {
         }
     }
 
+    /// Records the configurations, and their traffic weights, that were just made active for the
+    /// Formation `name`
+    pub fn record_active_configurations(&mut self, name: &str, active: &[(Uuid, f32)]) {
+        let configurations = active
+            .iter()
+            .map(|&(uuid, traffic_weight)| ActiveSetConfiguration {
+                uuid,
+                traffic_weight,
+                id: self.get_configuration_by_uuid(uuid).map(|cfg| cfg.id),
+            })
+            .collect();
+        if let Some(f) = self
+            .formations
+            .iter_mut()
+            .find(|f| f.name.as_deref() == Some(name))
+        {
+            f.history.push(ActiveSet::new(configurations));
+        }
+    }
+
     // TODO: add success indicator
     pub fn add_in_air_by_name(&mut self, name: &str, id: Id) {
         cli_traceln!(
@@ -260,6 +307,51 @@ impl Formations {
             .collect()
     }
 
+    /// Returns the ID of the configuration local edits of the Formation at `idx` apply to, which is
+    /// the one the next launch would use. If the Formation has never been edited or planned
+    /// locally (i.e. it was fetched) that's the one of the remote instance.
+    pub fn editable_configuration(&self, idx: usize) -> Result<Id> {
+        // TODO: allow choosing between multiple configurations
+        let formation = &self.formations[idx];
+        let name = formation.name.as_deref().unwrap_or_default();
+        let ids: Vec<_> = if formation.local.is_empty() {
+            formation.configs()
+        } else {
+            formation.local.iter().copied().collect()
+        };
+        match ids.as_slice() {
+            [id] => Ok(*id),
+            [] => {
+                Err(CliErrorKind::OneOff(format!("Formation Plan '{name}' has no configurations"))
+                    .into_err())
+            }
+            _ => Err(CliErrorKind::OneOff(format!(
+                "Formation Plan '{name}' has more than one configuration to edit"
+            ))
+            .into_err()
+            .context(
+                "(hint: only Formation Plans with a single local configuration can be edited)\n",
+            )),
+        }
+    }
+
+    /// Adds `cfg` as the local configuration of the Formation at `idx` in place of `old_id`,
+    /// returning the new ID. The old configuration is kept so any remote instances using it can
+    /// still be found, it just no longer gets launched.
+    pub fn replace_local_configuration(
+        &mut self,
+        idx: usize,
+        old_id: &Id,
+        cfg: FormationConfiguration,
+    ) -> Id {
+        let id = cfg.id;
+        self.configurations.push(cfg);
+        let formation = &mut self.formations[idx];
+        formation.local.remove(old_id);
+        formation.local.insert(id);
+        id
+    }
+
     /// Removes the given flight from all formations that reference it
     pub fn remove_flight(&mut self, flight: &str) {
         self.configurations.iter_mut().for_each(|cfg| {
@@ -268,6 +360,50 @@ impl Formations {
     }
 }
 
+/// Validates a Formation Configuration that may not have been created through its builder (i.e.
+/// one that was edited by hand) by running it back through the builder, and ensures every endpoint
+/// routes to a Flight of the configuration and no port or path is used by more than one endpoint
+/// across the public, formation and flight endpoints.
+pub fn validate_configuration(model: &FormationConfigurationModel) -> Result<()> {
+    let mut builder = FormationConfigurationModel::builder();
+    for flight in model.flights() {
+        builder = builder.add_flight(flight.clone());
+    }
+    for item in model.allowed_providers() {
+        builder = builder.add_allowed_provider(item.clone());
+    }
+    for item in model.denied_providers() {
+        builder = builder.add_denied_provider(item.clone());
+    }
+    for item in model.allowed_regions() {
+        builder = builder.add_allowed_region(item.clone());
+    }
+    for item in model.denied_region() {
+        builder = builder.add_denied_region(item.clone());
+    }
+    builder.build()?;
+
+    let mut seen = HashSet::new();
+    for (_, key, value) in all_endpoints(model) {
+        if !seen.insert(key) {
+            return Err(CliErrorKind::EndpointCollision(key.to_string()).into_err());
+        }
+        if !model
+            .flights()
+            .iter()
+            .any(|f| f.name() == value.flight_name)
+        {
+            return Err(CliErrorKind::EndpointUnknownFlight(
+                key.to_string(),
+                value.flight_name.clone(),
+            )
+            .into_err());
+        }
+    }
+
+    Ok(())
+}
+
 impl FromDisk for Formations {
     fn set_loaded_from<P: AsRef<Path>>(&mut self, p: P) {
         self.loaded_from = Some(p.as_ref().into());
@@ -340,6 +476,9 @@ pub struct Formation {
     pub local: HashSet<Id>,
     pub in_air: HashSet<Id>,
     pub grounded: HashSet<Id>,
+    /// The sets of active configurations the CLI has sent to the Seaplane Cloud, oldest first
+    #[serde(default, skip_serializing_if = "FormationHistory::is_empty")]
+    pub history: FormationHistory,
 }
 
 impl Formation {
@@ -350,6 +489,7 @@ impl Formation {
             local: HashSet::new(),
             in_air: HashSet::new(),
             grounded: HashSet::new(),
+            history: FormationHistory::default(),
         }
     }
 
@@ -431,6 +571,14 @@ impl FormationConfiguration {
 // Possible Symbols?: ◯ ◉ ◍ ◐ ● ○ ◯
 const SYM: char = '◉';
 
+/// How many recent crashes make a Flight crash looping
+const CRASH_LOOP_CRASHES: u64 = 3;
+/// How far back a stopped container still counts as a recent crash
+const CRASH_LOOP_WINDOW_MINS: i64 = 10;
+/// Containers which stopped after running for less than this are counted as crashes even if they
+/// exited successfully
+const SHORT_UPTIME_SECS: i64 = 60;
+
 #[derive(Debug, Eq, PartialEq, Clone, Serialize)]
 pub struct FormationStatus {
     name: String,
@@ -467,6 +615,36 @@ pub struct FlightStatus {
     starting: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
     maximum: Option<u64>,
+    /// Recently stopped containers which exited with an error or after only a short uptime
+    crashes: u64,
+    /// The non-zero exit codes of those crashes, most common first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    exit_codes: Vec<ExitCodeCount>,
+}
+
+/// How many crashed containers of a Flight exited with a particular exit code
+#[derive(Debug, Copy, PartialEq, Eq, Clone, Serialize)]
+pub struct ExitCodeCount {
+    code: i32,
+    count: u64,
+}
+
+/// A change in the containers of a Flight between two checks of a Formation Instance
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct FlightStatusChange {
+    timestamp: String,
+    formation: String,
+    configuration: Uuid,
+    flight: String,
+    status: OpStatus,
+    running: u64,
+    exited: u64,
+    errored: u64,
+    starting: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    exit_codes: Vec<ExitCodeCount>,
+    #[serde(skip)]
+    prev: Option<FlightStatus>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, strum::EnumString, Serialize)]
@@ -476,6 +654,8 @@ pub enum OpStatus {
     Down,
     Degraded,
     Starting,
+    /// Containers keep stopping with errors, or shortly after being started
+    CrashLooping,
 }
 
 impl OpStatus {
@@ -484,18 +664,25 @@ impl OpStatus {
         match self {
             Up => match other {
                 Down => *self = Down,
+                CrashLooping => *self = CrashLooping,
                 Degraded => *self = Degraded,
                 Starting => *self = Starting,
                 _ => (),
             },
             Down => (),
-            Degraded => {
+            CrashLooping => {
                 if other == Down {
                     *self = Down;
                 }
             }
+            Degraded => match other {
+                Down => *self = Down,
+                CrashLooping => *self = CrashLooping,
+                _ => (),
+            },
             Starting => match other {
                 Down => *self = Down,
+                CrashLooping => *self = CrashLooping,
                 Degraded => *self = Degraded,
                 _ => (),
             },
@@ -506,7 +693,7 @@ impl OpStatus {
     pub fn print_sym(self) {
         match self {
             OpStatus::Up => cli_print!(@Green, "{SYM}"),
-            OpStatus::Down => cli_print!(@Red, "{SYM}"),
+            OpStatus::Down | OpStatus::CrashLooping => cli_print!(@Red, "{SYM}"),
             OpStatus::Degraded | OpStatus::Starting => cli_print!(@Yellow, "{SYM}"),
         }
     }
@@ -518,6 +705,7 @@ impl OpStatus {
             OpStatus::Down => cli_print!(@Red, "DOWN"),
             OpStatus::Degraded => cli_print!(@Yellow, "DEGRADED"),
             OpStatus::Starting => cli_print!(@Yellow, "STARTING"),
+            OpStatus::CrashLooping => cli_print!(@Red, "CRASH LOOPING"),
         }
     }
 
@@ -525,7 +713,7 @@ impl OpStatus {
     pub fn print_msg(self, msg: &str) {
         match self {
             OpStatus::Up => cli_print!(@Green, "{msg}"),
-            OpStatus::Down => cli_print!(@Red, "{msg}"),
+            OpStatus::Down | OpStatus::CrashLooping => cli_print!(@Red, "{msg}"),
             OpStatus::Degraded | OpStatus::Starting => cli_print!(@Yellow, "{msg}"),
         }
     }
@@ -535,6 +723,14 @@ impl Default for OpStatus {
     fn default() -> Self { OpStatus::Starting }
 }
 
+/// The state to wait for a remote Formation Instance to reach
+#[derive(Debug, Copy, Clone, PartialEq, Eq, strum::EnumString, strum::Display, clap::ValueEnum)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub enum WaitFor {
+    Up,
+    Down,
+}
+
 impl FormationStatus {
     /// Create a new FormationStatus from a given Formation name.
     pub fn new<S: Into<String>>(name: S) -> Self {
@@ -554,16 +750,26 @@ impl FormationStatus {
                 min,
                 max,
             ),
-            ContainerStatus::Started => self.configurations.add_starting_flight(
-                c.configuration_id,
-                c.flight_name.clone(),
-                min,
-                max,
-            ),
-            ContainerStatus::Stopped => self.configurations.add_stopped_flight(
+            ContainerStatus::Stopped => {
+                self.configurations.add_stopped_flight(
+                    c.configuration_id,
+                    c.flight_name.clone(),
+                    matches!(c.exit_status, Some(code) if code != 0),
+                    min,
+                    max,
+                );
+                if is_recent_crash(c, Utc::now()) {
+                    self.configurations.add_crash(
+                        c.configuration_id,
+                        &c.flight_name,
+                        c.exit_status,
+                    );
+                }
+            }
+            // Any status this version doesn't know about is treated as still starting
+            _ => self.configurations.add_starting_flight(
                 c.configuration_id,
                 c.flight_name.clone(),
-                c.exit_status == None || c.exit_status == Some(0),
                 min,
                 max,
             ),
@@ -578,6 +784,90 @@ impl FormationStatus {
         }
         self.status = status;
     }
+
+    /// Add every Flight of a configuration, so that Flights which do not have any containers yet
+    /// are still accounted for
+    pub fn add_configuration(&mut self, uuid: Uuid, model: &FormationConfigurationModel) {
+        for flight in model.flights() {
+            self.configurations.add_expected_flight(
+                uuid,
+                flight.name(),
+                flight.minimum(),
+                flight.maximum(),
+            );
+        }
+    }
+
+    /// Returns `true` if the Formation has reached the given state.
+    ///
+    /// A Formation is up when every Flight has at least its minimum (and at least one) containers
+    /// running, and down when no Flight has any containers running or starting.
+    pub fn reached(&self, target: WaitFor) -> bool {
+        match target {
+            WaitFor::Up => {
+                !self.configurations.is_empty()
+                    && self
+                        .flights()
+                        .all(|f| f.running >= std::cmp::max(f.minimum, 1))
+            }
+            WaitFor::Down => self.flights().all(|f| f.running == 0 && f.starting == 0),
+        }
+    }
+
+    /// The names of the Flights which have errored containers, are crash looping, or have fewer
+    /// running containers than their minimum (and at least one)
+    pub fn unhealthy_flights(&self) -> Vec<&str> {
+        self.flights()
+            .filter(|f| {
+                f.errored > 0 || f.running < std::cmp::max(f.minimum, 1) || f.is_crash_looping()
+            })
+            .map(|f| f.name.as_str())
+            .collect()
+    }
+
+    /// A one line summary of the running and minimum containers of each Flight
+    pub fn progress(&self) -> String {
+        self.flights()
+            .map(|f| format!("{} {}/{}", f.name, f.running, f.minimum))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn flights(&self) -> impl Iterator<Item = &FlightStatus> {
+        self.configurations
+            .inner
+            .iter()
+            .flat_map(|cfg| cfg.flights.inner.iter())
+    }
+}
+
+/// Returns `true` if the container stopped within the crash loop window, either with a non-zero
+/// exit code or after running for only a short time. Containers without a stop time can't be
+/// placed in the window, so are never counted.
+fn is_recent_crash(c: &ContainerModel, now: DateTime<Utc>) -> bool {
+    if c.status != ContainerStatus::Stopped {
+        return false;
+    }
+    match c.stop_time {
+        Some(stop) if now - stop <= Duration::minutes(CRASH_LOOP_WINDOW_MINS) => (),
+        _ => return false,
+    }
+    let errored = c.exit_status.map(|code| code != 0).unwrap_or(false);
+    let short_uptime = match (c.start_time, c.stop_time) {
+        (Some(start), Some(stop)) => stop - start < Duration::seconds(SHORT_UPTIME_SECS),
+        _ => false,
+    };
+    errored || short_uptime
+}
+
+/// The most common of the exit codes, e.g. "137 (3x), 1 (1x)"
+fn common_exit_codes(exit_codes: &[ExitCodeCount]) -> String {
+    exit_codes
+        .iter()
+        .take(3)
+        .map(|ec| format!("{} ({}x)", ec.code, ec.count))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 impl FlightStatus {
@@ -590,6 +880,34 @@ impl FlightStatus {
             starting: 0,
             errored: 0,
             maximum: None,
+            crashes: 0,
+            exit_codes: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if both have the same number of containers in each state
+    pub fn same_counts(&self, other: &Self) -> bool {
+        self.running == other.running
+            && self.exited == other.exited
+            && self.errored == other.errored
+            && self.starting == other.starting
+            && self.crashes == other.crashes
+    }
+
+    /// Returns `true` if enough containers crashed recently to consider the Flight crash looping
+    pub fn is_crash_looping(&self) -> bool { self.crashes >= CRASH_LOOP_CRASHES }
+
+    /// Counts a crashed container, along with its exit code if it was an error
+    fn add_crash(&mut self, exit_status: Option<i32>) {
+        self.crashes += 1;
+        if let Some(code) = exit_status.filter(|&code| code != 0) {
+            if let Some(ec) = self.exit_codes.iter_mut().find(|ec| ec.code == code) {
+                ec.count += 1;
+            } else {
+                self.exit_codes.push(ExitCodeCount { code, count: 1 });
+            }
+            self.exit_codes
+                .sort_by(|a, b| b.count.cmp(&a.count).then(a.code.cmp(&b.code)));
         }
     }
 
@@ -608,10 +926,67 @@ impl FlightStatus {
             // TODO even if running > minimum?
             status = OpStatus::Degraded;
         }
+        if self.is_crash_looping() {
+            status = OpStatus::CrashLooping;
+        }
         status
     }
 }
 
+impl FlightStatusChange {
+    fn new(
+        timestamp: &str,
+        formation: &str,
+        configuration: Uuid,
+        flight: &FlightStatus,
+        prev: Option<&FlightStatus>,
+    ) -> Self {
+        Self {
+            timestamp: timestamp.into(),
+            formation: formation.into(),
+            configuration,
+            flight: flight.name.clone(),
+            status: flight.get_status(),
+            running: flight.running,
+            exited: flight.exited,
+            errored: flight.errored,
+            starting: flight.starting,
+            exit_codes: flight.exit_codes.clone(),
+            prev: prev.cloned(),
+        }
+    }
+
+    /// Prints the change as a single line, with the previous count of anything that changed
+    pub fn print(&self) {
+        cli_print!("{} {} {} ", self.timestamp, self.formation, self.configuration);
+        cli_print!(@Green, "{}", self.flight);
+        cli_print!(":");
+        let mut sep = "";
+        for (count, prev_count, name) in [
+            (self.running, self.prev.as_ref().map(|f| f.running), "running"),
+            (self.exited, self.prev.as_ref().map(|f| f.exited), "exited"),
+            (self.errored, self.prev.as_ref().map(|f| f.errored), "errored"),
+            (self.starting, self.prev.as_ref().map(|f| f.starting), "starting"),
+        ] {
+            match prev_count {
+                Some(prev_count) if prev_count != count => {
+                    cli_print!("{sep} {name} {prev_count} -> ");
+                    cli_print!(@Cyan, "{count}");
+                }
+                Some(_) => continue,
+                None => cli_print!("{sep} {name} {count}"),
+            }
+            sep = ",";
+        }
+        cli_print!(" (");
+        self.status.print();
+        if self.status == OpStatus::CrashLooping && !self.exit_codes.is_empty() {
+            cli_print!(", exit codes {}", common_exit_codes(&self.exit_codes));
+        }
+        cli_println!(")");
+    }
+}
+
 impl FormationConfigStatuses {
     pub fn add_running_flight<S: Into<String>>(
         &mut self,
@@ -666,6 +1041,40 @@ impl FormationConfigStatuses {
         }
     }
 
+    pub fn add_expected_flight<S: Into<String>>(
+        &mut self,
+        uuid: Uuid,
+        name: S,
+        min: u64,
+        max: Option<u64>,
+    ) {
+        if let Some(cfg) = self.inner.iter_mut().find(|cfg| cfg.uuid == uuid) {
+            cfg.flights.add_expected(name, min, max)
+        } else {
+            let mut fs = FlightStatuses::default();
+
+            fs.add_expected(name, min, max);
+            self.inner
+                .push(FormationConfigStatus { status: OpStatus::Starting, uuid, flights: fs })
+        }
+    }
+
+    /// Counts a crashed container of a Flight that was already added as stopped
+    pub fn add_crash(&mut self, uuid: Uuid, name: &str, exit_status: Option<i32>) {
+        if let Some(flight) = self
+            .inner
+            .iter_mut()
+            .find(|cfg| cfg.uuid == uuid)
+            .and_then(|cfg| cfg.flights.inner.iter_mut().find(|f| f.name == name))
+        {
+            flight.add_crash(exit_status);
+        }
+    }
+
+    pub fn get(&self, uuid: Uuid) -> Option<&FormationConfigStatus> {
+        self.inner.iter().find(|cfg| cfg.uuid == uuid)
+    }
+
     #[inline]
     pub fn is_empty(&self) -> bool { self.inner.is_empty() }
 
@@ -682,7 +1091,9 @@ impl FormationConfigStatus {
         self.status = status;
     }
 
-    pub fn print_pretty(&self, last: bool) {
+    /// Prints the configuration as part of a tree, highlighting any counts that changed since
+    /// `prev`
+    pub fn print_pretty(&self, last: bool, prev: Option<&FormationConfigStatus>) {
         // Chars we'll need: │ ├ ─ └
         if self.flights.is_empty() {
             return;
@@ -740,24 +1151,43 @@ impl FormationConfigStatus {
             self.status.print_sym();
 
             let name = &flight.name;
-            let running = flight.running;
-            let exited = flight.exited;
-            let errored = flight.errored;
-            let starting = flight.starting;
             let minimum = flight.minimum;
             let maximum = if let Some(maximum) = flight.maximum {
                 format!("{maximum}")
             } else {
                 "AUTO".to_string()
             };
-
-            let s_after_name = nspaces!(total_slot_size - name.len());
-            let s_after_running = nspaces!(running, "RUNNING");
-            let s_after_exited = nspaces!(exited, "EXITED");
-            let s_after_errored = nspaces!(errored, "ERRORED");
-            let s_after_starting = nspaces!(starting, "STARTING");
-
-            cli_println!(" {name}{s_after_name}{running}{s_after_running}{exited}{s_after_exited}{errored}{s_after_errored}{starting}{s_after_starting}{minimum} / {maximum}");
+            let prev_flight = prev.and_then(|p| p.flights.inner.iter().find(|f| f.name == *name));
+
+            cli_print!(" {name}{}", nspaces!(total_slot_size - name.len()));
+            for (count, prev_count, header) in [
+                (flight.running, prev_flight.map(|f| f.running), "RUNNING"),
+                (flight.exited, prev_flight.map(|f| f.exited), "EXITED"),
+                (flight.errored, prev_flight.map(|f| f.errored), "ERRORED"),
+                (flight.starting, prev_flight.map(|f| f.starting), "STARTING"),
+            ] {
+                if prev_count.map(|c| c != count).unwrap_or(false) {
+                    cli_print!(@Cyan, "{count}");
+                } else {
+                    cli_print!("{count}");
+                }
+                cli_print!("{}", nspaces!(count, header));
+            }
+            cli_println!("{minimum} / {maximum}");
+            if flight.is_crash_looping() {
+                let bar = if i == self.flights.inner.len() - 1 { " " } else { "│" };
+                cli_print!("{prefix}{bar}     ");
+                cli_print!(@Red, "crash looping");
+                cli_print!(
+                    ": {} crashes in the last {CRASH_LOOP_WINDOW_MINS} minutes",
+                    flight.crashes
+                );
+                if flight.exit_codes.is_empty() {
+                    cli_println!("");
+                } else {
+                    cli_println!(", exit codes {}", common_exit_codes(&flight.exit_codes));
+                }
+            }
         }
         if last {
             cli_println!("");
@@ -781,6 +1211,8 @@ impl FlightStatuses {
                 starting: 0,
                 minimum,
                 maximum,
+                crashes: 0,
+                exit_codes: Vec::new(),
             })
         }
     }
@@ -808,6 +1240,8 @@ impl FlightStatuses {
                 errored: if error { 1 } else { 0 },
                 minimum,
                 maximum,
+                crashes: 0,
+                exit_codes: Vec::new(),
             })
         }
     }
@@ -825,10 +1259,21 @@ impl FlightStatuses {
                 errored: 0,
                 minimum,
                 maximum,
+                crashes: 0,
+                exit_codes: Vec::new(),
             })
         }
     }
 
+    /// Adds the Flight without any containers if it isn't already known
+    pub fn add_expected<S: Into<String>>(&mut self, name: S, minimum: u64, maximum: Option<u64>) {
+        let name = name.into();
+        if !self.inner.iter().any(|f| f.name == name) {
+            self.inner
+                .push(FlightStatus { minimum, maximum, ..FlightStatus::new(name) })
+        }
+    }
+
     #[inline]
     pub fn is_empty(&self) -> bool { self.inner.is_empty() }
 }
@@ -841,6 +1286,15 @@ impl Output for FormationStatus {
     }
 
     fn print_table(&self, _ctx: &Ctx) -> Result<()> {
+        self.print_tree(None);
+
+        Ok(())
+    }
+}
+
+impl FormationStatus {
+    /// Prints the Formation as a tree, highlighting any counts that changed since `prev`
+    pub fn print_tree(&self, prev: Option<&FormationStatus>) {
         // Chars we'll need: │ ├ ─ └
         if !self.configurations.is_empty() {
             self.status.print_sym();
@@ -850,7 +1304,8 @@ impl Output for FormationStatus {
             cli_println!("│");
 
             for (i, cfg) in self.configurations.inner.iter().enumerate() {
-                cfg.print_pretty(i == self.configurations.len() - 1)
+                let prev_cfg = prev.and_then(|p| p.configurations.get(cfg.uuid));
+                cfg.print_pretty(i == self.configurations.len() - 1, prev_cfg)
             }
         } else {
             // If we have no configurations to display we assume the Formation is down
@@ -863,8 +1318,58 @@ impl Output for FormationStatus {
             fs.status.print();
             cli_println!("");
         }
+    }
 
-        Ok(())
+    /// The Flights whose containers changed since `prev`, or every Flight without a `prev`.
+    /// Flights which no longer have any containers are reported with all counts at zero.
+    pub fn changes(
+        &self,
+        prev: Option<&FormationStatus>,
+        timestamp: &str,
+    ) -> Vec<FlightStatusChange> {
+        let mut changes = Vec::new();
+        for cfg in self.configurations.inner.iter() {
+            for flight in cfg.flights.inner.iter() {
+                let prev_flight = prev
+                    .and_then(|p| p.configurations.get(cfg.uuid))
+                    .and_then(|c| c.flights.inner.iter().find(|f| f.name == flight.name));
+                if prev_flight.map(|f| !f.same_counts(flight)).unwrap_or(true) {
+                    changes.push(FlightStatusChange::new(
+                        timestamp,
+                        &self.name,
+                        cfg.uuid,
+                        flight,
+                        prev_flight,
+                    ));
+                }
+            }
+        }
+        if let Some(prev) = prev {
+            for cfg in prev.configurations.inner.iter() {
+                for flight in cfg.flights.inner.iter() {
+                    let gone = self
+                        .configurations
+                        .get(cfg.uuid)
+                        .map(|c| !c.flights.inner.iter().any(|f| f.name == flight.name))
+                        .unwrap_or(true);
+                    if gone {
+                        let now = FlightStatus {
+                            minimum: flight.minimum,
+                            maximum: flight.maximum,
+                            ..FlightStatus::new(&flight.name)
+                        };
+                        changes.push(FlightStatusChange::new(
+                            timestamp,
+                            &self.name,
+                            cfg.uuid,
+                            &now,
+                            Some(flight),
+                        ));
+                    }
+                }
+            }
+        }
+        changes
     }
 }
 
@@ -917,3 +1422,118 @@ impl Output for Vec<FormationStatus> {
 //         statuses
 //     }
 // }
+
+#[cfg(test)]
+mod status_test {
+    use super::*;
+
+    const UUID: &str = "46c5d58c-7b8b-4e8d-9e98-26bb31b9ab8f";
+
+    fn status(running: &[&str], starting: &[&str]) -> FormationStatus {
+        let uuid = UUID.parse().unwrap();
+        let mut fs = FormationStatus::new("stubb");
+        for flight in running {
+            fs.configurations.add_running_flight(uuid, *flight, 1, None);
+        }
+        for flight in starting {
+            fs.configurations
+                .add_starting_flight(uuid, *flight, 1, None);
+        }
+        fs.update_status();
+        fs
+    }
+
+    #[test]
+    fn status_changes_without_prev() {
+        let now = status(&["flask"], &["pequod"]);
+        let changes = now.changes(None, "now");
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            serde_json::to_value(&changes[0]).unwrap(),
+            serde_json::json!({
+                "timestamp": "now",
+                "formation": "stubb",
+                "configuration": UUID,
+                "flight": "flask",
+                "status": "Up",
+                "running": 1,
+                "exited": 0,
+                "errored": 0,
+                "starting": 0,
+            })
+        );
+    }
+
+    #[test]
+    fn status_changes_only_changed_flights() {
+        let prev = status(&["flask"], &["pequod"]);
+        assert!(prev.changes(Some(&prev), "now").is_empty());
+
+        let now = status(&["flask", "pequod"], &[]);
+        let changes = now.changes(Some(&prev), "now");
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].flight, "pequod");
+        assert_eq!((changes[0].running, changes[0].starting), (1, 0));
+        assert_eq!(changes[0].prev.as_ref().unwrap().starting, 1);
+    }
+
+    fn stopped(exit_status: Option<i32>, uptime_secs: i64, mins_ago: i64) -> ContainerModel {
+        let stop = Utc::now() - Duration::minutes(mins_ago);
+        serde_json::from_value(serde_json::json!({
+            "container_id": "557f87c3-b26c-428c-b970-cb8acac2bd68",
+            "status": "stopped",
+            "flight_name": "flask",
+            "configuration_id": UUID,
+            "exit_status": exit_status,
+            "start_time": (stop - Duration::seconds(uptime_secs)).to_rfc3339(),
+            "stop_time": stop.to_rfc3339(),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn recent_crashes() {
+        let now = Utc::now();
+        assert!(is_recent_crash(&stopped(Some(1), 3600, 1), now));
+        assert!(is_recent_crash(&stopped(Some(0), 5, 1), now));
+        assert!(is_recent_crash(&stopped(None, 5, 1), now));
+        assert!(!is_recent_crash(&stopped(Some(0), 3600, 1), now));
+        // Crashes outside of the window are in the past
+        assert!(!is_recent_crash(&stopped(Some(1), 5, CRASH_LOOP_WINDOW_MINS + 1), now));
+        // Without a stop time there's no telling when it crashed
+        let mut unknown = stopped(Some(1), 5, 1);
+        unknown.stop_time = None;
+        assert!(!is_recent_crash(&unknown, now));
+    }
+
+    #[test]
+    fn crash_looping_status() {
+        let mut fs = status(&["flask"], &[]);
+        for exit_status in [Some(1), Some(0)] {
+            fs.add_container(&stopped(exit_status, 5, 1), 1, None);
+        }
+        fs.update_status();
+        assert_eq!(fs.status, OpStatus::Degraded);
+
+        fs.add_container(&stopped(Some(1), 5, 1), 1, None);
+        fs.update_status();
+        assert_eq!(fs.status, OpStatus::CrashLooping);
+        assert_eq!(fs.unhealthy_flights(), vec!["flask"]);
+        let flask = fs.flights().next().unwrap();
+        assert_eq!(flask.crashes, 3);
+        assert_eq!(flask.exit_codes, vec![ExitCodeCount { code: 1, count: 2 }]);
+
+        let changes = fs.changes(Some(&status(&["flask"], &[])), "now");
+        assert_eq!(changes[0].status, OpStatus::CrashLooping);
+    }
+
+    #[test]
+    fn status_changes_removed_flight() {
+        let prev = status(&["flask", "pequod"], &[]);
+        let now = status(&["flask"], &[]);
+        let changes = now.changes(Some(&prev), "now");
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].flight, "pequod");
+        assert_eq!(changes[0].running, 0);
+    }
+}
@@ -1,10 +1,118 @@
-use std::{result::Result as StdResult, str::FromStr};
+use std::{fmt, io::Write, result::Result as StdResult, str::FromStr};
 
 use seaplane::api::compute::v1::{
     EndpointKey as EndpointKeyModel, EndpointValue as EndpointValueModel,
+    FormationConfiguration as FormationConfigurationModel,
 };
+use serde::Serialize;
+use tabwriter::TabWriter;
 
-use crate::cli::validator::validate_flight_name;
+use crate::{
+    cli::validator::validate_flight_name,
+    context::Ctx,
+    error::{CliError, Result},
+    printer::Output,
+};
+
+/// Which of the endpoint maps of a Formation Configuration an endpoint belongs to
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndpointKind {
+    Public,
+    Formation,
+    Flight,
+}
+
+impl fmt::Display for EndpointKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EndpointKind::Public => write!(f, "public"),
+            EndpointKind::Formation => write!(f, "formation"),
+            EndpointKind::Flight => write!(f, "flight"),
+        }
+    }
+}
+
+/// Returns all the endpoints of a Formation Configuration along with the map they belong to
+#[cfg_attr(not(feature = "unstable"), allow(clippy::let_and_return))]
+pub fn all_endpoints(
+    model: &FormationConfigurationModel,
+) -> impl Iterator<Item = (EndpointKind, &EndpointKeyModel, &EndpointValueModel)> {
+    let endpoints = model
+        .public_endpoints()
+        .map(|(k, v)| (EndpointKind::Public, k, v))
+        .chain(
+            model
+                .flight_endpoints()
+                .map(|(k, v)| (EndpointKind::Flight, k, v)),
+        );
+    #[cfg(feature = "unstable")]
+    let endpoints = endpoints.chain(
+        model
+            .formation_endpoints()
+            .map(|(k, v)| (EndpointKind::Formation, k, v)),
+    );
+    endpoints
+}
+
+/// A single endpoint of a Formation Configuration, as displayed by `formation endpoint list`
+#[derive(Debug, Clone, Serialize)]
+pub struct FormationEndpoint {
+    pub kind: EndpointKind,
+    pub endpoint: EndpointKeyModel,
+    pub flight: String,
+    pub port: u16,
+}
+
+/// The endpoints of a Formation Configuration, sorted by kind and then endpoint
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct FormationEndpoints {
+    pub inner: Vec<FormationEndpoint>,
+}
+
+impl FormationEndpoints {
+    pub fn new(model: &FormationConfigurationModel) -> Self {
+        let mut inner: Vec<_> = all_endpoints(model)
+            .map(|(kind, key, value)| FormationEndpoint {
+                kind,
+                endpoint: key.clone(),
+                flight: value.flight_name.clone(),
+                port: value.port,
+            })
+            .collect();
+        inner.sort_by_key(|ep| (ep.kind, ep.endpoint.to_string()));
+        Self { inner }
+    }
+}
+
+impl Output for FormationEndpoints {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn print_table(&self, ctx: &Ctx) -> Result<()> {
+        let mut tw = TabWriter::new(Vec::new());
+        if !ctx.formation_ctx.get_or_init().no_header {
+            writeln!(tw, "KIND\tENDPOINT\tFLIGHT\tPORT")?;
+        }
+        for ep in &self.inner {
+            writeln!(tw, "{}\t{}\t{}\t{}", ep.kind, ep.endpoint, ep.flight, ep.port)?;
+        }
+        tw.flush()?;
+
+        cli_print!(
+            "{}",
+            String::from_utf8_lossy(
+                &tw.into_inner()
+                    .map_err(|_| CliError::bail("IO flush error"))?
+            )
+        );
+
+        Ok(())
+    }
+}
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Endpoint {
@@ -13,16 +121,22 @@ pub struct Endpoint {
 }
 
 impl Endpoint {
-    pub fn key(&self) -> EndpointKeyModel {
-        match &self.src {
-            EndpointSrc::Http(p) => EndpointKeyModel::Http { path: p.to_owned() },
-            EndpointSrc::Tcp(p) => EndpointKeyModel::Tcp { port: *p },
-            EndpointSrc::Udp(p) => EndpointKeyModel::Udp { port: *p },
-        }
-    }
+    pub fn key(&self) -> EndpointKeyModel { self.src.key() }
     pub fn value(&self) -> EndpointValueModel {
         EndpointValueModel { flight_name: self.dst.flight.clone(), port: self.dst.port }
     }
+    pub fn flight(&self) -> &str { &self.dst.flight }
+}
+
+impl From<(&EndpointKeyModel, &EndpointValueModel)> for Endpoint {
+    fn from((key, value): (&EndpointKeyModel, &EndpointValueModel)) -> Self {
+        let src = match key {
+            EndpointKeyModel::Http { path } => EndpointSrc::Http(path.to_owned()),
+            EndpointKeyModel::Tcp { port } => EndpointSrc::Tcp(*port),
+            EndpointKeyModel::Udp { port } => EndpointSrc::Udp(*port),
+        };
+        Self { src, dst: EndpointDst { flight: value.flight_name.clone(), port: value.port } }
+    }
 }
 
 impl FromStr for Endpoint {
@@ -50,6 +164,16 @@ pub enum EndpointSrc {
     Udp(u16),
 }
 
+impl EndpointSrc {
+    pub fn key(&self) -> EndpointKeyModel {
+        match self {
+            EndpointSrc::Http(p) => EndpointKeyModel::Http { path: p.to_owned() },
+            EndpointSrc::Tcp(p) => EndpointKeyModel::Tcp { port: *p },
+            EndpointSrc::Udp(p) => EndpointKeyModel::Udp { port: *p },
+        }
+    }
+}
+
 impl FromStr for EndpointSrc {
     type Err = String;
 
@@ -0,0 +1,115 @@
+use std::io::Write;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use seaplane::api::compute::v1::{ActiveConfiguration, ActiveConfigurations};
+use serde::{Deserialize, Serialize};
+use tabwriter::TabWriter;
+use uuid::Uuid;
+
+use crate::{
+    context::Ctx,
+    error::{CliError, Result},
+    ops::Id,
+    printer::Output,
+};
+
+/// A configuration that was part of an [`ActiveSet`]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActiveSetConfiguration {
+    pub uuid: Uuid,
+    pub traffic_weight: f32,
+    /// The local configuration with this UUID, used to upload it again if it has since been
+    /// removed from the Seaplane Cloud
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Id>,
+}
+
+/// The configurations of a Formation Instance made active by the CLI at some point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActiveSet {
+    pub timestamp: DateTime<Utc>,
+    /// Empty when the Formation Instance was landed
+    pub configurations: Vec<ActiveSetConfiguration>,
+}
+
+impl ActiveSet {
+    pub fn new(configurations: Vec<ActiveSetConfiguration>) -> Self {
+        Self { timestamp: Utc::now(), configurations }
+    }
+
+    /// Returns true if the Formation Instance was landed
+    pub fn is_landed(&self) -> bool { self.configurations.is_empty() }
+
+    /// Builds the model of these active configurations
+    pub fn to_model(&self) -> Result<ActiveConfigurations> {
+        let mut cfgs = ActiveConfigurations::new();
+        for cfg in &self.configurations {
+            #[cfg_attr(not(feature = "unstable"), allow(unused_mut))]
+            let mut builder = ActiveConfiguration::builder().uuid(cfg.uuid);
+            #[cfg(feature = "unstable")]
+            {
+                builder = builder.traffic_weight(cfg.traffic_weight);
+            }
+            cfgs.add_configuration_mut(builder.build()?);
+        }
+        Ok(cfgs)
+    }
+}
+
+/// Every set of active configurations of a Formation Instance, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FormationHistory {
+    inner: Vec<ActiveSet>,
+}
+
+impl FormationHistory {
+    pub fn push(&mut self, set: ActiveSet) { self.inner.push(set) }
+
+    /// Returns the `n`th set, counting from 1
+    pub fn get(&self, n: usize) -> Option<&ActiveSet> {
+        n.checked_sub(1).and_then(|i| self.inner.get(i))
+    }
+
+    pub fn is_empty(&self) -> bool { self.inner.is_empty() }
+
+    pub fn len(&self) -> usize { self.inner.len() }
+}
+
+impl Output for FormationHistory {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn print_table(&self, ctx: &Ctx) -> Result<()> {
+        let mut tw = TabWriter::new(Vec::new());
+        if !ctx.formation_ctx.get_or_init().no_header {
+            writeln!(tw, "#\tTIMESTAMP\tCONFIGURATION\tWEIGHT")?;
+        }
+        for (i, set) in self.inner.iter().enumerate() {
+            let timestamp = set.timestamp.to_rfc3339_opts(SecondsFormat::Secs, true);
+            if set.is_landed() {
+                writeln!(tw, "{}\t{timestamp}\t(landed)\t-", i + 1)?;
+            }
+            for (j, cfg) in set.configurations.iter().enumerate() {
+                if j == 0 {
+                    writeln!(tw, "{}\t{timestamp}\t{}\t{}", i + 1, cfg.uuid, cfg.traffic_weight)?;
+                } else {
+                    writeln!(tw, "\t\t{}\t{}", cfg.uuid, cfg.traffic_weight)?;
+                }
+            }
+        }
+        tw.flush()?;
+
+        cli_print!(
+            "{}",
+            String::from_utf8_lossy(
+                &tw.into_inner()
+                    .map_err(|_| CliError::bail("IO flush error"))?
+            )
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use std::io::Write;
+
+use seaplane::api::{
+    compute::v1::{
+        Container as ContainerModel, FormationConfiguration as FormationConfigurationModel,
+    },
+    shared::v1::{Provider, Region},
+};
+use serde::Serialize;
+use tabwriter::TabWriter;
+use uuid::Uuid;
+
+use crate::{
+    context::Ctx,
+    error::{CliError, Result},
+    printer::Output,
+};
+
+/// Where a single container of a Formation Instance runs, and how that breaks the provider and
+/// region constraints of its configuration if at all
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContainerPlacement {
+    configuration: Uuid,
+    flight: String,
+    container: Uuid,
+    provider: Option<Provider>,
+    region: Option<Region>,
+    country: Option<String>,
+    iata: Option<String>,
+    violations: Vec<String>,
+}
+
+impl ContainerPlacement {
+    /// Checks the host of the container against the constraints of the configuration `model`
+    pub fn new(c: &ContainerModel, model: &FormationConfigurationModel) -> Self {
+        let host = c.host_info.as_ref();
+        let provider = host.and_then(|h| h.host_provider.clone());
+        let region = host.and_then(|h| h.host_region.clone());
+
+        let mut violations = Vec::new();
+        let allowed: Vec<_> = model.allowed_providers().collect();
+        let denied: Vec<_> = model.denied_providers().collect();
+        match &provider {
+            Some(p) if denied.contains(&p) => violations.push(format!("provider {p} is denied")),
+            Some(p) if !allowed.is_empty() && !allowed.contains(&p) => {
+                violations.push(format!("provider {p} is not allowed"))
+            }
+            None if !allowed.is_empty() || !denied.is_empty() => {
+                violations.push("provider is unknown".into())
+            }
+            _ => (),
+        }
+        let allowed: Vec<_> = model.allowed_regions().collect();
+        let denied: Vec<_> = model.denied_region().collect();
+        match &region {
+            Some(r) if denied.contains(&r) => violations.push(format!("region {r} is denied")),
+            Some(r) if !allowed.is_empty() && !allowed.contains(&r) => {
+                violations.push(format!("region {r} is not allowed"))
+            }
+            None if !allowed.is_empty() || !denied.is_empty() => {
+                violations.push("region is unknown".into())
+            }
+            _ => (),
+        }
+
+        Self {
+            configuration: c.configuration_id,
+            flight: c.flight_name.clone(),
+            container: c.container_id,
+            provider,
+            region,
+            country: host.and_then(|h| h.host_country.clone()),
+            iata: host.and_then(|h| h.host_iata.clone()),
+            violations,
+        }
+    }
+
+    pub fn is_compliant(&self) -> bool { self.violations.is_empty() }
+}
+
+/// The placement of every container of a Formation Instance
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct FormationPlacement {
+    inner: Vec<ContainerPlacement>,
+}
+
+impl FormationPlacement {
+    pub fn push(&mut self, placement: ContainerPlacement) { self.inner.push(placement) }
+
+    /// The number of containers which do not comply with their configuration
+    pub fn violations(&self) -> usize { self.inner.iter().filter(|p| !p.is_compliant()).count() }
+}
+
+impl Output for FormationPlacement {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn print_table(&self, ctx: &Ctx) -> Result<()> {
+        let or_dash = |s: Option<String>| s.unwrap_or_else(|| "-".into());
+        let mut tw = TabWriter::new(Vec::new());
+        if !ctx.formation_ctx.get_or_init().no_header {
+            writeln!(tw, "FLIGHT\tCONTAINER\tPROVIDER\tREGION\tCOUNTRY\tIATA\tVIOLATIONS")?;
+        }
+        for p in &self.inner {
+            writeln!(
+                tw,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                p.flight,
+                p.container,
+                or_dash(p.provider.as_ref().map(ToString::to_string)),
+                or_dash(p.region.as_ref().map(ToString::to_string)),
+                or_dash(p.country.clone()),
+                or_dash(p.iata.clone()),
+                if p.is_compliant() { "-".into() } else { p.violations.join(", ") },
+            )?;
+        }
+        tw.flush()?;
+
+        cli_print!(
+            "{}",
+            String::from_utf8_lossy(
+                &tw.into_inner()
+                    .map_err(|_| CliError::bail("IO flush error"))?
+            )
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod placement_test {
+    use super::*;
+
+    fn container(provider: &str, region: &str) -> ContainerModel {
+        serde_json::from_value(serde_json::json!({
+            "container_id": "557f87c3-b26c-428c-b970-cb8acac2bd68",
+            "status": "running",
+            "flight_name": "flask",
+            "configuration_id": "46c5d58c-7b8b-4e8d-9e98-26bb31b9ab8f",
+            "host_provider": provider,
+            "host_region": region,
+        }))
+        .unwrap()
+    }
+
+    fn model(constraints: serde_json::Value) -> FormationConfigurationModel {
+        let mut cfg = serde_json::json!({
+            "flights": [{"name": "flask", "image": "registry.cplane.cloud/seaplane/flask:latest"}],
+        });
+        cfg.as_object_mut()
+            .unwrap()
+            .extend(constraints.as_object().unwrap().clone());
+        // The image reference can only be deserialized from a string
+        serde_json::from_str(&cfg.to_string()).unwrap()
+    }
+
+    #[test]
+    fn unknown_values_ignore_case() {
+        let allowed = model(serde_json::json!({
+            "providers_allowed": ["Hetzner"],
+            "regions_allowed": ["XM"],
+        }));
+        assert!(ContainerPlacement::new(&container("hetzner", "xm"), &allowed).is_compliant());
+        let placement = ContainerPlacement::new(&container("ovh", "xm"), &allowed);
+        assert_eq!(placement.violations, vec!["provider ovh is not allowed"]);
+
+        let denied = model(serde_json::json!({
+            "providers_denied": ["HETZNER"],
+            "regions_denied": ["xm"],
+        }));
+        let placement = ContainerPlacement::new(&container("Hetzner", "XM"), &denied);
+        assert_eq!(placement.violations, vec!["provider Hetzner is denied", "region XM is denied"]);
+    }
+}
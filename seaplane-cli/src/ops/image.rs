@@ -0,0 +1,240 @@
+use std::io::Write;
+
+use seaplane::api::{
+    compute::v1::{Architecture, Flight as FlightModel, ImageReference},
+    registry::v2::ImageManifest,
+};
+use serde::Serialize;
+use tabwriter::TabWriter;
+
+use crate::{
+    api::RegistryReq,
+    context::Ctx,
+    error::{CliError, CliErrorKind, Context, Result},
+    ops::Id,
+    printer::{Color, Output},
+};
+
+/// Returns `image` pinned to the digest its tag currently points to in the registry. An image
+/// without a tag keeps its digest after it has been checked to exist.
+pub fn pin_image(ctx: &Ctx, image: &ImageReference) -> Result<ImageReference> {
+    let unpinned = if image.tag.is_some() {
+        ImageReference { digest: None, ..image.clone() }
+    } else {
+        image.clone()
+    };
+    let digest = RegistryReq::new(ctx, &unpinned)?.resolve_digest()?;
+    Ok(unpinned.with_digest(digest))
+}
+
+/// Pins the image of Flight `name` if it isn't pinned to a digest already, returning the pinned
+/// image or `None` if there was nothing to do
+pub fn pin_flight_image(
+    ctx: &Ctx,
+    name: &str,
+    image: &ImageReference,
+) -> Result<Option<ImageReference>> {
+    if image.digest.is_some() {
+        return Ok(None);
+    }
+    let pinned = pin_image(ctx, image)?;
+    cli_println!("Pinned Flight Plan {name} to {pinned}");
+    Ok(Some(pinned))
+}
+
+/// Pins the image of the local Flight Plan with the given ID
+pub fn pin_local_flight(ctx: &mut Ctx, id: &Id) -> Result<()> {
+    // re unwrap: the ID came from the Flights DB
+    let flight = ctx.db.flights.find_id(id).unwrap();
+    if let Some(pinned) = pin_flight_image(ctx, flight.model.name(), flight.model.image())? {
+        ctx.db
+            .flights
+            .find_id_mut(id)
+            .unwrap()
+            .model
+            .set_image(pinned);
+    }
+    Ok(())
+}
+
+/// Checks the architectures `flight` declares against the platforms its image is published for,
+/// failing if any are missing. With `infer` the published architectures replace the declared ones
+/// instead, and are returned if they differ.
+pub fn check_flight_architectures(
+    ctx: &Ctx,
+    flight: &FlightModel,
+    infer: bool,
+) -> Result<Option<Vec<Architecture>>> {
+    let manifest = RegistryReq::new(ctx, flight.image())?.get_manifest()?;
+    let published = manifest.architectures();
+
+    if infer {
+        // Only architectures known to this version may be requested
+        let inferred: Vec<Architecture> = published
+            .iter()
+            .filter_map(|arch| match arch.parse() {
+                Ok(Architecture::Unknown(_)) | Err(_) => None,
+                Ok(arch) => Some(arch),
+            })
+            .collect();
+        if inferred.is_empty() {
+            cli_warnln!(
+                "image '{}' is not published for any supported architecture, keeping the architectures of Flight Plan {}",
+                flight.image(),
+                flight.name()
+            );
+            return Ok(None);
+        }
+        if inferred.len() == flight.architecture().count()
+            && inferred
+                .iter()
+                .all(|arch| flight.architecture().any(|a| a == arch))
+        {
+            return Ok(None);
+        }
+        cli_println!(
+            "Inferred architectures {} for Flight Plan {}",
+            inferred
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+            flight.name()
+        );
+        return Ok(Some(inferred));
+    }
+
+    // Flights without architectures have them detected from the image
+    if flight.architecture().count() == 0 {
+        return Ok(None);
+    }
+
+    let missing: Vec<String> = flight
+        .architecture()
+        .map(ToString::to_string)
+        .filter(|arch| !published.iter().any(|p| p.eq_ignore_ascii_case(arch)))
+        .collect();
+    if !missing.is_empty() {
+        return Err(CliErrorKind::ImageMissingArchitectures(missing)
+            .into_err()
+            .context("\tFlight Plan: ")
+            .color_context(Color::Yellow, flight.name())
+            .context("\n\timage: ")
+            .color_context(Color::Yellow, flight.image().to_string())
+            .context("\n\tpublished architectures: ")
+            .color_context(Color::Green, published.join(", "))
+            .context("\n(hint: use '")
+            .color_context(Color::Green, "--infer-arch")
+            .context("' to declare the architectures the image is published for)\n"));
+    }
+    Ok(None)
+}
+
+/// Checks (or with `infer`, infers) the architectures of the local Flight Plan with the given ID
+pub fn check_local_flight(ctx: &mut Ctx, id: &Id, infer: bool) -> Result<()> {
+    // re unwrap: the ID came from the Flights DB
+    let flight = ctx.db.flights.find_id(id).unwrap();
+    if let Some(archs) = check_flight_architectures(ctx, &flight.model, infer)? {
+        ctx.db
+            .flights
+            .find_id_mut(id)
+            .unwrap()
+            .model
+            .set_architecture(archs);
+    }
+    Ok(())
+}
+
+/// An image reference resolved to the digest it points to
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedImage {
+    pub image: ImageReference,
+    pub digest: String,
+}
+
+impl Output for ResolvedImage {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn print_table(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", self.image.clone().with_digest(&self.digest));
+        Ok(())
+    }
+}
+
+/// The tags of an image repository
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageTags {
+    pub image: String,
+    pub tags: Vec<String>,
+}
+
+impl Output for ImageTags {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn print_table(&self, ctx: &Ctx) -> Result<()> {
+        if !ctx.image_ctx.get_or_init().no_header {
+            cli_println!("TAG");
+        }
+        for tag in &self.tags {
+            cli_println!("{tag}");
+        }
+        Ok(())
+    }
+}
+
+/// The manifest of an image, along with the reference it was requested by
+#[derive(Debug, Clone, Serialize)]
+pub struct Manifest {
+    pub image: ImageReference,
+    #[serde(flatten)]
+    pub manifest: ImageManifest,
+}
+
+impl Output for Manifest {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn print_table(&self, ctx: &Ctx) -> Result<()> {
+        cli_println!("Image: {}", self.image);
+        cli_println!("Digest: {}", self.manifest.digest);
+        cli_println!("Media Type: {}", self.manifest.media_type);
+        cli_println!("");
+
+        let mut tw = TabWriter::new(Vec::new());
+        if !ctx.image_ctx.get_or_init().no_header {
+            writeln!(tw, "PLATFORM\tDIGEST")?;
+        }
+        for m in self
+            .manifest
+            .manifests
+            .iter()
+            .filter(|m| m.platform.is_known())
+        {
+            let mut platform = format!("{}/{}", m.platform.os, m.platform.architecture);
+            if let Some(variant) = &m.platform.variant {
+                platform.push('/');
+                platform.push_str(variant);
+            }
+            writeln!(tw, "{platform}\t{}", m.digest)?;
+        }
+        tw.flush()?;
+
+        cli_print!(
+            "{}",
+            String::from_utf8_lossy(
+                &tw.into_inner()
+                    .map_err(|_| CliError::bail("IO flush error"))?
+            )
+        );
+
+        Ok(())
+    }
+}
@@ -62,6 +62,37 @@ impl Output for HeldLock {
     }
 }
 
+/// A held slot of a semaphore, which is an ordinary lock with its own name
+#[derive(Debug, Serialize)]
+pub struct HeldSlot {
+    pub lock_name: String,
+    pub lock_id: String,
+    pub sequencer: u32,
+}
+
+impl Output for HeldSlot {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn print_table(&self, ctx: &Ctx) -> Result<()> {
+        let show_headers = !ctx.locks_ctx.get_or_init().no_header;
+        let mut ptr = printer();
+
+        let name_prefix = if show_headers { "LOCK-NAME: " } else { "" };
+        let id_prefix = if show_headers { "LOCK-ID: " } else { "" };
+        let seq_prefix = if show_headers { "SEQUENCER: " } else { "" };
+        writeln!(ptr, "{name_prefix}{}", self.lock_name)?;
+        writeln!(ptr, "{id_prefix}{}", self.lock_id)?;
+        writeln!(ptr, "{seq_prefix}{}", self.sequencer)?;
+
+        ptr.flush()?;
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct ListedLockInfoInner {
     pub ttl: u32,
@@ -78,9 +109,9 @@ impl From<LockInfoInner> for ListedLockInfoInner {
 
 #[derive(Debug, Serialize)]
 pub struct ListedLock {
-    name: EncodedString,
-    id: String,
-    info: ListedLockInfoInner,
+    pub name: EncodedString,
+    pub id: String,
+    pub info: ListedLockInfoInner,
 }
 
 impl From<LockInfo> for ListedLock {
@@ -94,6 +125,48 @@ impl From<LockInfo> for ListedLock {
     }
 }
 
+/// Filters which listed locks must match in order to be printed
+#[derive(Debug, Default, Clone)]
+pub struct LockFilter {
+    pub client_id: Option<String>,
+    pub ip: Option<String>,
+    /// The minimum remaining TTL in seconds (inclusive)
+    pub min_ttl: Option<u32>,
+    /// The maximum remaining TTL in seconds (inclusive)
+    pub max_ttl: Option<u32>,
+}
+
+impl LockFilter {
+    /// Returns `true` if the lock matches all of the filters which are set
+    pub fn matches(&self, info: &LockInfoInner) -> bool {
+        !(matches!(&self.client_id, Some(client_id) if *client_id != info.client_id)
+            || matches!(&self.ip, Some(ip) if *ip != info.ip)
+            || matches!(self.min_ttl, Some(ttl) if info.ttl < ttl)
+            || matches!(self.max_ttl, Some(ttl) if info.ttl > ttl))
+    }
+}
+
+/// Prints the details of a single lock, one per line
+pub fn print_lock_details(headers: bool, lock: &ListedLock, ctx: &Ctx) -> Result<()> {
+    let mut ptr = printer();
+    let prefix = |header: &'static str| if headers { header } else { "" };
+
+    write!(ptr, "{}", prefix("LOCK-NAME: "))?;
+    if ctx.locks_ctx.get_or_init().decode {
+        ptr.write_all(&lock.name.decoded()?)?;
+        writeln!(ptr)?;
+    } else {
+        writeln!(ptr, "{}", lock.name)?;
+    }
+    writeln!(ptr, "{}{}", prefix("LOCK-ID: "), lock.id)?;
+    writeln!(ptr, "{}{}", prefix("CLIENT-ID: "), lock.info.client_id)?;
+    writeln!(ptr, "{}{}", prefix("CLIENT-IP: "), lock.info.ip)?;
+    writeln!(ptr, "{}{}", prefix("TTL: "), lock.info.ttl)?;
+    ptr.flush()?;
+
+    Ok(())
+}
+
 pub fn print_lock_table<I>(headers: bool, chunk: I, ctx: &Ctx) -> Result<()>
 where
     I: IntoIterator<Item = ListedLock>,
@@ -127,3 +200,30 @@ where
 
     Ok(())
 }
+
+/// A change in leadership printed by `seaplane locks elect`
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum LeadershipTransition {
+    Elected { lock_id: String, sequencer: u32 },
+    Lost { reason: String },
+    SteppedDown,
+}
+
+impl Output for LeadershipTransition {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn print_table(&self, _ctx: &Ctx) -> Result<()> {
+        match self {
+            Self::Elected { lock_id, sequencer } => {
+                cli_println!("Elected leader (LOCK-ID: {lock_id}, SEQUENCER: {sequencer})")
+            }
+            Self::Lost { reason } => cli_println!("Lost leadership: {reason}"),
+            Self::SteppedDown => cli_println!("Stepped down"),
+        }
+        Ok(())
+    }
+}
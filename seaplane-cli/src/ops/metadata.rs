@@ -1,17 +1,28 @@
-use std::io::Write;
+use std::{collections::HashMap, io::Write};
 
-use seaplane::api::metadata::v1::KeyValue as KeyValueModel;
+use seaplane::{
+    api::metadata::v1::{
+        is_encrypted, validate_encoded_key, validate_encoded_value, KeyValue as KeyValueModel,
+        Keyring, SizeLimits,
+    },
+    error::SeaplaneError,
+};
 use serde::Serialize;
 use tabwriter::TabWriter;
 use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{
     context::Ctx,
-    error::{CliError, Result},
-    ops::EncodedString,
-    printer::{printer, Output},
+    error::{CliError, CliErrorKind, Context, Result},
+    ops::{EncodedString, SyncAction},
+    printer::{printer, Color, Output},
 };
 
+/// Keys and values larger than these (in bytes, as stored) are unusual enough to probably be a
+/// mistake, such as syncing the wrong directory. The Metadata API doesn't document its limits so
+/// they only print a warning, and the API has the final say.
+const LARGE: SizeLimits = SizeLimits { max_key_len: 1024, max_value_len: 64 * 1024 };
+
 /// We use our own KeyValue instead of the models because we need to *not* enforce base64 encoding,
 /// and implement a bunch of additional methods and traits that wouldn't make sense for the models
 ///
@@ -52,6 +63,42 @@ impl KeyValue {
     pub fn set_value<S: Into<String>>(&mut self, value: S) {
         self.value = EncodedString::new(value.into())
     }
+
+    /// Checks the key and value would be accepted by the Metadata API, so problems can be caught
+    /// before any requests are made
+    pub fn validate(&self) -> Result<()> {
+        validate_encoded_key(self.key.to_string())
+            .and_then(|_| validate_encoded_value(self.value.to_string()))
+            .map_err(|e| CliError::from(SeaplaneError::from(e)))
+            .context("\tkey: ")
+            .with_color_context(|| {
+                let key = self
+                    .key
+                    .decoded_safe()
+                    .unwrap_or_else(|_| self.key.to_string());
+                (Color::Yellow, format!("{key}\n"))
+            })
+    }
+
+    /// Prints a warning if the key, or the value as it will be stored (i.e. after any
+    /// encryption), is unusually large
+    pub fn warn_if_large(&self) -> Result<()> { warn_if_large(&self.key, &self.value) }
+}
+
+fn warn_if_large(key: &EncodedString, value: &EncodedString) -> Result<()> {
+    let (key_bytes, value_bytes) = (key.decoded()?, value.decoded()?);
+    if LARGE.check(&key_bytes, &value_bytes).is_err() {
+        let (key_len, value_len) = (key_bytes.len(), value_bytes.len());
+        let key = key.decoded_safe()?;
+        cli_warn!(@prefix, "key '");
+        cli_warn!(@Yellow, "{key}");
+        cli_warnln!(
+            @noprefix,
+            "' ({key_len} bytes) or its value ({value_len} bytes) is unusually large and may be \
+            rejected by the Metadata API"
+        );
+    }
+    Ok(())
 }
 
 #[derive(Debug, Default, Clone, Serialize)]
@@ -84,6 +131,171 @@ impl KeyValues {
     pub fn keys(&self) -> impl Iterator<Item = EncodedString> + '_ {
         self.inner.iter().map(|kv| kv.key.clone())
     }
+
+    /// Decrypts any encrypted values in place, leaving plain values untouched. If no keys were
+    /// provided encrypted values are also left as is, and a warning is printed.
+    pub fn decrypt_values(&mut self, keyring: Option<&Keyring>) -> Result<()> {
+        let mut warned = false;
+        for kv in self.inner.iter_mut() {
+            let bytes = kv.value.decoded()?;
+            if !is_encrypted(&bytes) {
+                continue;
+            }
+            if let Some(keyring) = keyring {
+                let plaintext = keyring
+                    .decrypt(bytes)
+                    .map_err(CliError::from)
+                    .context("\tkey: ")
+                    .with_color_context(|| (Color::Yellow, format!("{}\n", kv.key)))?;
+                kv.set_value(base64::encode_config(plaintext, base64::URL_SAFE_NO_PAD));
+            } else if !warned {
+                cli_warnln!("some values are encrypted but no keys were provided to decrypt them");
+                cli_warn!("(hint: provide the keys with '");
+                cli_warn!(@Yellow, "--key-file");
+                cli_warnln!(@noprefix, "')");
+                warned = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncChange {
+    pub action: SyncAction,
+    pub key: EncodedString,
+    /// The value to upload, `None` when deleting
+    #[serde(skip)]
+    pub value: Option<EncodedString>,
+}
+
+/// The set of changes required to make a remote metadata directory match a local one
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct SyncPlan {
+    pub dry_run: bool,
+    pub changes: Vec<SyncChange>,
+    pub unchanged: usize,
+}
+
+impl SyncPlan {
+    /// Compares the `local` key-values (whose values are *not* encrypted) against the `remote`
+    /// ones, only keeping the keys whose contents differ.
+    ///
+    /// Encrypted remote values are decrypted with `keyring` before comparing them, and when
+    /// `encrypt` is set any values to upload are encrypted with it. Remote keys which do not exist
+    /// locally are only removed when `delete` is set.
+    pub fn new(
+        local: &KeyValues,
+        remote: &KeyValues,
+        delete: bool,
+        encrypt: bool,
+        keyring: Option<&Keyring>,
+    ) -> Result<Self> {
+        let mut remote_values = HashMap::with_capacity(remote.inner.len());
+        for kv in remote.iter() {
+            remote_values.insert(kv.key.decoded()?, kv.value.decoded()?);
+        }
+
+        let mut plan = SyncPlan::default();
+        for kv in local.iter() {
+            let key = kv.key.decoded()?;
+            let plaintext = kv.value.decoded()?;
+            let action = match remote_values.remove(&key) {
+                None => Some(SyncAction::Create),
+                Some(current) => {
+                    // A value needs updating if either the contents, or whether it's encrypted
+                    // differs
+                    let matches = match (is_encrypted(&current), encrypt, keyring) {
+                        (true, true, Some(keyring)) => keyring
+                            .decrypt(&current)
+                            .map(|v| v == plaintext)
+                            .unwrap_or(false),
+                        (false, false, _) => current == plaintext,
+                        _ => false,
+                    };
+                    if matches {
+                        None
+                    } else {
+                        Some(SyncAction::Update)
+                    }
+                }
+            };
+
+            if let Some(action) = action {
+                let value = match (encrypt, keyring) {
+                    (true, Some(keyring)) => keyring.encrypt(plaintext)?,
+                    (true, None) => {
+                        return Err(CliErrorKind::CliArgNotUsed("--key-file").into_err());
+                    }
+                    (false, _) => plaintext,
+                };
+                let value =
+                    EncodedString::new(base64::encode_config(value, base64::URL_SAFE_NO_PAD));
+                warn_if_large(&kv.key, &value)?;
+                plan.changes
+                    .push(SyncChange { action, key: kv.key.clone(), value: Some(value) });
+            } else {
+                plan.unchanged += 1;
+            }
+        }
+
+        if delete {
+            // Iterate the remote values in order so the plan is stable
+            for kv in remote.iter() {
+                if remote_values.contains_key(&kv.key.decoded()?) {
+                    plan.changes.push(SyncChange {
+                        action: SyncAction::Delete,
+                        key: kv.key.clone(),
+                        value: None,
+                    });
+                }
+            }
+        }
+
+        Ok(plan)
+    }
+
+    pub fn is_empty(&self) -> bool { self.changes.is_empty() }
+
+    /// The number of changes of a given kind
+    pub fn count(&self, action: SyncAction) -> usize {
+        self.changes.iter().filter(|c| c.action == action).count()
+    }
+}
+
+impl Output for SyncPlan {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn print_table(&self, _ctx: &Ctx) -> Result<()> {
+        for change in &self.changes {
+            cli_println!("{} {}", change.action.symbol(), change.key.decoded_safe()?);
+        }
+
+        let (create, update, delete) = (
+            self.count(SyncAction::Create),
+            self.count(SyncAction::Update),
+            self.count(SyncAction::Delete),
+        );
+        if !self.is_empty() {
+            cli_println!("");
+        }
+        if self.dry_run {
+            cli_println!(
+                "Dry run: {create} to create, {update} to update, {delete} to delete, {} unchanged",
+                self.unchanged
+            );
+        } else {
+            cli_println!(
+                "Successfully synced: {create} created, {update} updated, {delete} deleted, {} unchanged",
+                self.unchanged
+            );
+        }
+
+        Ok(())
+    }
 }
 
 impl Output for KeyValues {
@@ -1,14 +1,25 @@
-use std::{collections::BTreeSet, fmt::Display, io::Write};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+    fs,
+    io::Write,
+    path::Path,
+};
 
-use seaplane::api::restrict::v1::{RestrictedDirectory as RestrictedDirectoryModel, Restriction};
-use serde::Serialize;
+use seaplane::api::{
+    restrict::v1::{
+        Api, RestrictedDirectory as RestrictedDirectoryModel, Restriction, RestrictionDetails,
+    },
+    shared::v1::{Provider as ProviderModel, Region as RegionModel},
+};
+use serde::{Deserialize, Serialize};
 use tabwriter::TabWriter;
 
-use super::EncodedString;
+use super::{EncodedString, SyncAction};
 use crate::{
     context::Ctx,
-    error::{CliError, Result},
-    printer::{printer, Output},
+    error::{CliError, Context, Result},
+    printer::{printer, Color, Output},
 };
 
 /// We use our own RestrictedDirectory instead of the models because we need to
@@ -118,3 +129,632 @@ impl Output for Restrictions {
         self.impl_print_table(!restrict_ctx.no_header, restrict_ctx.decode)
     }
 }
+
+/// Returns the most specific of `restrictions` whose directory contains `path`, i.e. the one with
+/// the longest directory that `path` is somewhere below. A path ending in a `/` is treated as a
+/// directory, and so is also governed by a restriction on that very directory.
+pub fn governing_restriction<'a>(
+    restrictions: &'a [Restriction],
+    path: &[u8],
+) -> Option<&'a Restriction> {
+    restrictions
+        .iter()
+        .map(|r| (r, r.directory.decode()))
+        .filter(|(_, dir)| {
+            let dir = dir.strip_suffix(b"/").unwrap_or(dir);
+            path.len() > dir.len() && path.starts_with(dir) && path[dir.len()] == b'/'
+        })
+        .max_by_key(|(_, dir)| dir.len())
+        .map(|(r, _)| r)
+}
+
+/// Which restriction, if any, governs a metadata key or lock name
+#[derive(Debug, Clone, Serialize)]
+pub struct RestrictionExplanation {
+    pub api: String,
+    pub key: EncodedString,
+    pub restriction: Option<Restriction>,
+}
+
+impl Output for RestrictionExplanation {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn print_table(&self, ctx: &Ctx) -> Result<()> {
+        let restrict_ctx = ctx.restrict_ctx.get_or_init();
+        let display = |es: &EncodedString| -> Result<String> {
+            if restrict_ctx.decode {
+                es.decoded_safe()
+            } else {
+                Ok(es.to_string())
+            }
+        };
+        let key = display(&self.key)?;
+
+        match &self.restriction {
+            Some(restriction) => {
+                let dir = display(&EncodedString::new(restriction.directory.to_string()))?;
+                cli_println!(
+                    "{key} in {} API is governed by the restriction on directory {dir}\n",
+                    self.api
+                );
+                Restrictions::from_model(vec![restriction.clone()])
+                    .impl_print_table(!restrict_ctx.no_header, restrict_ctx.decode)?;
+            }
+            None => cli_println!(
+                "No restriction governs {key} in {} API, so it may be placed in any region or provider",
+                self.api
+            ),
+        }
+
+        Ok(())
+    }
+}
+
+/// The metadata directories which are not governed by any restriction
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(transparent)]
+pub struct UnrestrictedDirectories {
+    inner: Vec<EncodedString>,
+}
+
+impl UnrestrictedDirectories {
+    /// Finds every directory containing one of the `keys` (which are URL safe base64 encoded) that
+    /// is not governed by any of the `restrictions` on the Config API
+    pub fn new(
+        keys: impl Iterator<Item = EncodedString>,
+        restrictions: &[Restriction],
+    ) -> Result<Self> {
+        let restrictions: Vec<Restriction> = restrictions
+            .iter()
+            .filter(|r| r.api == Api::Config)
+            .cloned()
+            .collect();
+
+        let mut dirs = BTreeSet::new();
+        for key in keys {
+            let key = key.decoded()?;
+            for (i, _) in key.iter().enumerate().filter(|(_, b)| **b == b'/') {
+                dirs.insert(key[..=i].to_vec());
+            }
+        }
+
+        let inner = dirs
+            .into_iter()
+            .filter(|dir| governing_restriction(&restrictions, dir).is_none())
+            .map(|dir| {
+                EncodedString::new(base64::encode_config(
+                    &dir[..dir.len() - 1],
+                    base64::URL_SAFE_NO_PAD,
+                ))
+            })
+            .collect();
+
+        Ok(Self { inner })
+    }
+
+    pub fn is_empty(&self) -> bool { self.inner.is_empty() }
+}
+
+impl Output for UnrestrictedDirectories {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn print_table(&self, ctx: &Ctx) -> Result<()> {
+        if self.is_empty() {
+            cli_println!("All metadata directories are governed by a restriction");
+            return Ok(());
+        }
+
+        let restrict_ctx = ctx.restrict_ctx.get_or_init();
+        if !restrict_ctx.no_header {
+            cli_println!("DIRECTORY");
+        }
+        for dir in &self.inner {
+            if restrict_ctx.decode {
+                cli_println!("{}", dir.decoded_safe()?);
+            } else {
+                cli_println!("{dir}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A single restriction as written in a policy file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct PolicyEntry {
+    api: Api,
+    directory: String,
+    /// Is the directory already URL safe base64 encoded
+    #[serde(default)]
+    base64: bool,
+    #[serde(default)]
+    regions_allowed: BTreeSet<RegionModel>,
+    #[serde(default)]
+    regions_denied: BTreeSet<RegionModel>,
+    #[serde(default)]
+    providers_allowed: BTreeSet<ProviderModel>,
+    #[serde(default)]
+    providers_denied: BTreeSet<ProviderModel>,
+}
+
+impl PolicyEntry {
+    /// Returns the first API, region or provider which this version doesn't know about, if any
+    fn unknown_value(&self) -> Option<String> {
+        if let Api::Unknown(api) = &self.api {
+            return Some(api.to_string());
+        }
+        self.regions_allowed
+            .iter()
+            .chain(&self.regions_denied)
+            .find_map(|r| match r {
+                RegionModel::Unknown(r) => Some(r.to_string()),
+                _ => None,
+            })
+            .or_else(|| {
+                self.providers_allowed
+                    .iter()
+                    .chain(&self.providers_denied)
+                    .find_map(|p| match p {
+                        ProviderModel::Unknown(p) => Some(p.to_string()),
+                        _ => None,
+                    })
+            })
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyFile {
+    #[serde(default, rename = "restriction")]
+    restrictions: Vec<PolicyEntry>,
+}
+
+/// A restriction which should exist according to a policy file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyRestriction {
+    pub api: Api,
+    pub directory: RestrictedDirectoryModel,
+    pub details: RestrictionDetails,
+}
+
+/// The complete set of restrictions a tenant should have, as read from a TOML policy file such as:
+///
+/// ```toml
+/// [[restriction]]
+/// api = "config"
+/// directory = "foo/bar"
+/// regions-allowed = ["xe"]
+/// providers-denied = ["aws"]
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RestrictionPolicy {
+    pub restrictions: Vec<PolicyRestriction>,
+}
+
+impl RestrictionPolicy {
+    /// Reads and validates a policy file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        fs::read_to_string(path)
+            .map_err(CliError::from)
+            .and_then(|s| Self::from_toml(&s))
+            .context("\n\tpath: ")
+            .with_color_context(|| (Color::Yellow, path.display().to_string()))
+    }
+
+    /// Parses and validates the TOML contents of a policy file
+    pub fn from_toml(s: &str) -> Result<Self> {
+        let file: PolicyFile = toml::from_str(s)?;
+
+        let mut restrictions: Vec<PolicyRestriction> = Vec::with_capacity(file.restrictions.len());
+        for entry in file.restrictions {
+            // Values unknown to this version of the CLI are most likely typos
+            if let Some(unknown) = entry.unknown_value() {
+                return Err(CliError::bail("the policy contains an unknown value")
+                    .context("\n\tvalue: ")
+                    .color_context(Color::Yellow, unknown)
+                    .context("\n\tdirectory: ")
+                    .color_context(Color::Yellow, &entry.directory)
+                    .context("\n"));
+            }
+
+            let directory = if entry.base64 {
+                // Check that what the user wrote really is valid base64
+                let _ = base64::decode_config(&entry.directory, base64::URL_SAFE_NO_PAD)?;
+                RestrictedDirectoryModel::from_encoded(entry.directory.clone())
+            } else {
+                RestrictedDirectoryModel::from_unencoded(&entry.directory)
+            };
+
+            if restrictions
+                .iter()
+                .any(|r| r.api == entry.api && r.directory == directory)
+            {
+                return Err(CliError::bail(
+                    "the policy restricts the same directory more than once",
+                )
+                .context("\n\tdirectory: ")
+                .color_context(Color::Yellow, &entry.directory)
+                .context(" in ")
+                .color_context(Color::Yellow, entry.api.to_string())
+                .context(" API\n"));
+            }
+
+            let details = RestrictionDetails {
+                regions_allowed: entry.regions_allowed,
+                regions_denied: entry.regions_denied,
+                providers_allowed: entry.providers_allowed,
+                providers_denied: entry.providers_denied,
+            };
+            // Rebuild the details in order to reject conflicting requirements up front
+            let mut builder = RestrictionDetails::builder();
+            for item in &details.providers_allowed {
+                builder = builder.add_allowed_provider(item.clone());
+            }
+            for item in &details.providers_denied {
+                builder = builder.add_denied_provider(item.clone());
+            }
+            for item in &details.regions_allowed {
+                builder = builder.add_allowed_region(item.clone());
+            }
+            for item in &details.regions_denied {
+                builder = builder.add_denied_region(item.clone());
+            }
+            let details = builder
+                .build()
+                .map_err(CliError::from)
+                .context("\n\tdirectory: ")
+                .with_color_context(|| (Color::Yellow, &entry.directory))?;
+
+            restrictions.push(PolicyRestriction { api: entry.api, directory, details });
+        }
+
+        Ok(Self { restrictions })
+    }
+}
+
+/// The change required to bring a single remote restriction in line with the policy
+#[derive(Debug, Clone, Serialize)]
+pub struct RestrictChange {
+    pub action: SyncAction,
+    pub api: Api,
+    pub directory: EncodedString,
+    /// The details to set, `None` when deleting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<RestrictionDetails>,
+}
+
+/// The set of changes required to make the remote restrictions match a policy
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct RestrictPlan {
+    pub dry_run: bool,
+    pub changes: Vec<RestrictChange>,
+    pub unchanged: usize,
+    /// Remote restrictions a policy can't express, which are left as is
+    pub unmanaged: usize,
+}
+
+/// Whether the API, or any region or provider of the remote restriction `r` is unknown to this
+/// version. Policies only hold known values, so such a restriction could never match one, and
+/// setting it from a policy would drop the unknown values.
+fn is_unmanaged(r: &Restriction) -> bool {
+    let d = &r.details;
+    matches!(r.api, Api::Unknown(_))
+        || d.regions_allowed
+            .iter()
+            .chain(&d.regions_denied)
+            .any(|r| matches!(r, RegionModel::Unknown(_)))
+        || d.providers_allowed
+            .iter()
+            .chain(&d.providers_denied)
+            .any(|p| matches!(p, ProviderModel::Unknown(_)))
+}
+
+impl RestrictPlan {
+    /// Compares the `policy` against all `remote` restrictions. Any remote restriction not in the
+    /// policy is deleted, since the policy is meant to be the complete set of restrictions, except
+    /// for those which are unmanaged (see [`is_unmanaged`]) which are never touched.
+    pub fn new(policy: &RestrictionPolicy, remote: &[Restriction]) -> Self {
+        let key = |api: &Api, dir: &RestrictedDirectoryModel| (api.to_string(), dir.to_string());
+        let mut remote_by_key: HashMap<_, _> = remote
+            .iter()
+            .map(|r| (key(&r.api, &r.directory), r))
+            .collect();
+
+        let mut plan = RestrictPlan::default();
+        for wanted in &policy.restrictions {
+            let action = match remote_by_key.remove(&key(&wanted.api, &wanted.directory)) {
+                None => SyncAction::Create,
+                // Counted below
+                Some(r) if is_unmanaged(r) => continue,
+                Some(r) if r.details == wanted.details => {
+                    plan.unchanged += 1;
+                    continue;
+                }
+                Some(_) => SyncAction::Update,
+            };
+            plan.changes.push(RestrictChange {
+                action,
+                api: wanted.api.clone(),
+                directory: EncodedString::new(wanted.directory.to_string()),
+                details: Some(wanted.details.clone()),
+            });
+        }
+
+        // Iterate the remote restrictions in order so the plan is stable
+        for r in remote {
+            if is_unmanaged(r) {
+                plan.unmanaged += 1;
+                continue;
+            }
+            if remote_by_key.contains_key(&key(&r.api, &r.directory)) {
+                plan.changes.push(RestrictChange {
+                    action: SyncAction::Delete,
+                    api: r.api.clone(),
+                    directory: EncodedString::new(r.directory.to_string()),
+                    details: None,
+                });
+            }
+        }
+
+        plan
+    }
+
+    pub fn is_empty(&self) -> bool { self.changes.is_empty() }
+
+    /// The number of changes of a given kind
+    pub fn count(&self, action: SyncAction) -> usize {
+        self.changes.iter().filter(|c| c.action == action).count()
+    }
+}
+
+impl Output for RestrictPlan {
+    fn print_json(&self, _ctx: &Ctx) -> Result<()> {
+        cli_println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn print_table(&self, _ctx: &Ctx) -> Result<()> {
+        for change in &self.changes {
+            cli_println!(
+                "{} {} {}",
+                change.action.symbol(),
+                change.api,
+                change.directory.decoded_safe()?
+            );
+        }
+
+        let (create, update, delete) = (
+            self.count(SyncAction::Create),
+            self.count(SyncAction::Update),
+            self.count(SyncAction::Delete),
+        );
+        if !self.is_empty() {
+            cli_println!("");
+        }
+        if self.dry_run {
+            cli_println!(
+                "Plan: {create} to create, {update} to update, {delete} to delete, {} unchanged",
+                self.unchanged
+            );
+        } else {
+            cli_println!(
+                "Successfully applied: {create} created, {update} updated, {delete} deleted, {} unchanged",
+                self.unchanged
+            );
+        }
+        if self.unmanaged > 0 {
+            cli_println!(
+                "Left {} restriction(s) with APIs, regions or providers unknown to this version of the CLI as is",
+                self.unmanaged
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod restrict_test {
+    use seaplane::api::restrict::v1::RestrictionState;
+
+    use super::*;
+
+    #[test]
+    fn policy_from_toml() {
+        let policy = RestrictionPolicy::from_toml(
+            r#"
+[[restriction]]
+api = "config"
+directory = "foo/bar"
+regions-allowed = ["xe", "XN"]
+
+[[restriction]]
+api = "Locks"
+directory = "Zm9v"
+base64 = true
+providers-denied = ["aws"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(policy.restrictions.len(), 2);
+        assert_eq!(policy.restrictions[0].api, Api::Config);
+        assert_eq!(policy.restrictions[0].directory.encoded(), "Zm9vL2Jhcg");
+        assert_eq!(
+            policy.restrictions[0].details.regions_allowed,
+            BTreeSet::from([RegionModel::XE, RegionModel::XN])
+        );
+        assert_eq!(policy.restrictions[1].api, Api::Locks);
+        assert_eq!(policy.restrictions[1].directory.encoded(), "Zm9v");
+        assert_eq!(
+            policy.restrictions[1].details.providers_denied,
+            BTreeSet::from([ProviderModel::AWS])
+        );
+    }
+
+    fn restriction(dir: &str) -> Restriction { api_restriction(Api::Config, dir) }
+
+    fn api_restriction(api: Api, dir: &str) -> Restriction {
+        Restriction {
+            api,
+            directory: RestrictedDirectoryModel::from_unencoded(dir),
+            details: RestrictionDetails::builder().build().unwrap(),
+            state: RestrictionState::Enforced,
+        }
+    }
+
+    #[test]
+    fn most_specific_restriction_governs() {
+        let restrictions = vec![restriction("foo"), restriction("foo/bar"), restriction("foobar")];
+        let governing = |path: &str| {
+            governing_restriction(&restrictions, path.as_bytes())
+                .map(|r| String::from_utf8(r.directory.decode()).unwrap())
+        };
+
+        assert_eq!(governing("foo/bar/baz").as_deref(), Some("foo/bar"));
+        assert_eq!(governing("foo/baz").as_deref(), Some("foo"));
+        assert_eq!(governing("foo/bar/").as_deref(), Some("foo/bar"));
+        assert_eq!(governing("foobar/baz").as_deref(), Some("foobar"));
+        // A key named the same as the directory is not in it
+        assert_eq!(governing("foo"), None);
+        assert_eq!(governing("fo/bar"), None);
+    }
+
+    #[test]
+    fn unrestricted_directories() {
+        let keys = ["foo/a", "foo/bar/b", "baz/qux/c", "top"]
+            .iter()
+            .map(|k| EncodedString::new(base64::encode_config(k, base64::URL_SAFE_NO_PAD)));
+        let dirs = UnrestrictedDirectories::new(keys, &[restriction("foo")]).unwrap();
+        let dirs: Vec<_> = dirs
+            .inner
+            .iter()
+            .map(|d| d.decoded_safe().unwrap())
+            .collect();
+
+        assert_eq!(dirs, vec!["baz".to_string(), "baz/qux".to_string()]);
+    }
+
+    #[test]
+    fn plan_leaves_unknown_apis() {
+        let policy = RestrictionPolicy::from_toml(
+            r#"
+[[restriction]]
+api = "config"
+directory = "foo"
+"#,
+        )
+        .unwrap();
+        let remote = vec![
+            restriction("foo"),
+            api_restriction(Api::Locks, "foo"),
+            api_restriction(Api::Unknown("Queues".into()), "foo"),
+        ];
+
+        let plan = RestrictPlan::new(&policy, &remote);
+        assert_eq!(plan.unchanged, 1);
+        assert_eq!(plan.unmanaged, 1);
+        assert_eq!(plan.changes.len(), 1);
+        assert_eq!(plan.changes[0].action, SyncAction::Delete);
+        assert_eq!(plan.changes[0].api, Api::Locks);
+    }
+
+    #[test]
+    fn plan_leaves_unknown_details() {
+        let policy = RestrictionPolicy::from_toml(
+            r#"
+[[restriction]]
+api = "config"
+directory = "foo"
+regions-allowed = ["xe"]
+
+[[restriction]]
+api = "config"
+directory = "bar"
+"#,
+        )
+        .unwrap();
+        let mut unknown_region = restriction("foo");
+        unknown_region
+            .details
+            .regions_allowed
+            .insert(RegionModel::Unknown("XM".into()));
+        let mut unknown_provider = restriction("bar");
+        unknown_provider
+            .details
+            .providers_denied
+            .insert(ProviderModel::Unknown("Hetzner".into()));
+        let mut other = restriction("baz");
+        other
+            .details
+            .providers_denied
+            .insert(ProviderModel::Unknown("Hetzner".into()));
+
+        // Neither updated to match the policy, nor deleted
+        let plan = RestrictPlan::new(&policy, &[unknown_region, unknown_provider, other]);
+        assert_eq!(plan.unchanged, 0);
+        assert_eq!(plan.unmanaged, 3);
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn policy_rejects_invalid() {
+        // Duplicate directories
+        assert!(RestrictionPolicy::from_toml(
+            r#"
+[[restriction]]
+api = "config"
+directory = "foo"
+
+[[restriction]]
+api = "config"
+directory = "Zm9v"
+base64 = true
+"#
+        )
+        .is_err());
+
+        // Conflicting requirements
+        assert!(RestrictionPolicy::from_toml(
+            r#"
+[[restriction]]
+api = "config"
+directory = "foo"
+regions-allowed = ["xe"]
+regions-denied = ["xe"]
+"#
+        )
+        .is_err());
+
+        // Unknown regions
+        assert!(RestrictionPolicy::from_toml(
+            r#"
+[[restriction]]
+api = "config"
+directory = "foo"
+regions-allowed = ["europe"]
+"#
+        )
+        .is_err());
+
+        // Unknown fields
+        assert!(RestrictionPolicy::from_toml(
+            r#"
+[[restriction]]
+api = "config"
+directory = "foo"
+region = ["xe"]
+"#
+        )
+        .is_err());
+    }
+}
@@ -21,7 +21,7 @@ static GLOBAL_PRINTER: OnceCell<Mutex<self::_printer::Printer>> = OnceCell::new(
 #[cfg_attr(feature = "api_tests", allow(dead_code))]
 static GLOBAL_EPRINTER: OnceCell<Mutex<self::_printer::Printer>> = OnceCell::new();
 
-pub use self::_printer::{eprinter, printer, Printer};
+pub use self::_printer::{eprinter, printer, stdout_is_tty, Printer};
 
 /// We wrap the progress bar to be able to hide output when we need to
 #[allow(missing_debug_implementations)]
@@ -142,6 +142,9 @@ mod _printer {
         }
     }
 
+    /// Returns `true` if standard output is a terminal
+    pub fn stdout_is_tty() -> bool { atty::is(Stream::Stdout) }
+
     #[allow(missing_debug_implementations)]
     pub struct Printer(StandardStream);
 
@@ -215,6 +218,10 @@ mod _printer {
         Stderr(io::Stderr),
     }
 
+    /// Returns `true` if standard output is a terminal, which without the `color` feature is
+    /// never assumed
+    pub fn stdout_is_tty() -> bool { false }
+
     #[allow(missing_debug_implementations)]
     pub struct Printer(StandardStream);
 
@@ -272,6 +279,8 @@ mod _printer {
     #[allow(missing_debug_implementations)]
     pub struct Printer(pub Vec<u8>);
 
+    pub fn stdout_is_tty() -> bool { false }
+
     pub fn printer() -> MutexGuard<'static, Printer> {
         GLOBAL_TEST_PRINTER
             .get_or_init(|| Mutex::new(Printer(Vec::new())))
@@ -1,17 +1,25 @@
-use httpmock::prelude::*;
-use seaplane::api::{
-    compute::v1::FormationConfiguration as FormationConfigurationModel,
-    shared::v1::{Provider, Region},
+use chrono::{Duration, Utc};
+use httpmock::{prelude::*, Method};
+use seaplane::{
+    api::{
+        compute::v1::FormationConfiguration as FormationConfigurationModel,
+        shared::v1::{Provider, Region},
+        ApiErrorKind,
+    },
+    error::SeaplaneError,
 };
 use seaplane_cli::{
     context::Ctx,
-    ops::formation::{Formation, FormationConfiguration},
+    error::CliErrorKind,
+    ops::formation::{
+        ActiveSet, ActiveSetConfiguration, Formation, FormationConfiguration, WaitFor,
+    },
     printer::printer,
 };
 use serde_json::json;
 use wildmatch::WildMatch;
 
-use super::{test_main_with_ctx, then, when, when_json, MOCK_SERVER};
+use super::{test_main_with_ctx, test_main_with_ctx_mut, then, when, when_json, MOCK_SERVER};
 
 // The ARGV must use the name `stubb` as these are just tests, no need to spend brainpower trying to
 // figure out how to make it perfectly generic.
@@ -22,8 +30,11 @@ use super::{test_main_with_ctx, then, when, when_json, MOCK_SERVER};
 const DEFAULT_CFG_UUID: &str = "46c5d58c-7b8b-4e8d-9e98-26bb31b9ab8f";
 
 fn build_ctx_with_default_formation(local_only: bool) -> Ctx {
-    let fcm: FormationConfigurationModel =
-        serde_json::from_str(&default_cfg_json().to_string()).unwrap();
+    build_ctx_with_formation(default_cfg_json(), local_only)
+}
+
+fn build_ctx_with_formation(cfg: serde_json::Value, local_only: bool) -> Ctx {
+    let fcm: FormationConfigurationModel = serde_json::from_str(&cfg.to_string()).unwrap();
     let fc = FormationConfiguration::new(fcm);
     let mut f = Formation::new("stubb");
     f.local.insert(fc.id);
@@ -460,7 +471,10 @@ Successfully fetched 3 items"#;
         let inline_correct_out: &str = r#"Successfully created Flight Plan 'flask' with ID '????????'
 Successfully created Flight Plan 'pequod' with ID '????????'
 Successfully created local Formation Plan 'stubb' with ID '????????'"#;
-        let launched_correct_out: String = r#"Successfully Launched remote Formation Instance 'stubb' with Configuration UUIDs:
+        let launched_correct_out: String = r#"warn: Flight '*' uses image 'registry.cplane.cloud/stubb/alpine:latest' which is not pinned to a digest
+warn: Flight '*' uses image 'registry.cplane.cloud/stubb/alpine:latest' which is not pinned to a digest
+(hint: re-launching later may run different images, use '--pin-digest' to pin them)
+Successfully Launched remote Formation Instance 'stubb' with Configuration UUIDs:
 ????????-????-????-????-????????????
 The remote Formation Instance URL is https://stubb--bar.on.cplane.cloud/
 (hint: it may take up to a minute for the Formation to become fully online)
@@ -943,3 +957,1099 @@ fn formation_status_all_no_fetch() {
 fn formation_status_one_no_fetch() {
     test_fn_status!("formation status stubb --no-fetch", ctx_with_remote_id());
 }
+
+#[test]
+fn formation_status_crash_loop() {
+    // pequod keeps crashing shortly after being started, flask is fine
+    let stopped = |exit_status: i32| {
+        let stop = Utc::now() - Duration::minutes(1);
+        let mut c = container_json("pequod", "stopped");
+        c["exit_status"] = json!(exit_status);
+        c["start_time"] = json!((stop - Duration::seconds(10)).to_rfc3339());
+        c["stop_time"] = json!(stop.to_rfc3339());
+        c
+    };
+    let containers =
+        json!([container_json("flask", "running"), stopped(137), stopped(1), stopped(137)]);
+    let mut get_containers = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/formations/stubb/containers");
+        then(t, &containers);
+    });
+
+    let res = test_main_with_ctx(&cli!("formation status stubb --no-fetch"), ctx_with_remote_id());
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(
+        printer().as_string().trim(),
+        format!(
+            "◉ Formation stubb: CRASH LOOPING
+│
+└─◉ Configuration {DEFAULT_CFG_UUID}: CRASH LOOPING
+  │
+  │   FLIGHT    RUNNING    EXITED    ERRORED    STARTING    MIN / MAX
+  ├─◉ flask     1          0         0          0           1 / AUTO
+  └─◉ pequod    0          0         3          0           1 / AUTO
+        crash looping: 3 crashes in the last 10 minutes, exit codes 137 (2x), 1 (1x)"
+        )
+    );
+    printer().clear();
+
+    let res = test_main_with_ctx(
+        &cli!("formation status stubb --no-fetch --format json"),
+        ctx_with_remote_id(),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    let status: serde_json::Value = serde_json::from_str(&printer().as_string()).unwrap();
+    let cfg = &status[0]["configurations"][0];
+    assert_eq!(cfg["status"], json!("CrashLooping"));
+    assert_eq!(cfg["flights"][0]["crashes"], json!(0));
+    assert_eq!(cfg["flights"][1]["crashes"], json!(3));
+    assert_eq!(
+        cfg["flights"][1]["exit_codes"],
+        json!([{"code": 137, "count": 2}, {"code": 1, "count": 1}])
+    );
+    printer().clear();
+    get_containers.delete();
+}
+
+#[test]
+fn formation_status_exited_errored() {
+    // Long stopped pequod containers, one exited cleanly and the other with an error
+    let stopped = |exit_status: i32| {
+        let stop = Utc::now() - Duration::hours(1);
+        let mut c = container_json("pequod", "stopped");
+        c["exit_status"] = json!(exit_status);
+        c["start_time"] = json!((stop - Duration::hours(1)).to_rfc3339());
+        c["stop_time"] = json!(stop.to_rfc3339());
+        c
+    };
+    let containers = json!([container_json("flask", "running"), stopped(0), stopped(1)]);
+    let mut get_containers = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/formations/stubb/containers");
+        then(t, &containers);
+    });
+
+    let res = test_main_with_ctx(
+        &cli!("formation status stubb --no-fetch --format json"),
+        ctx_with_remote_id(),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    let status: serde_json::Value = serde_json::from_str(&printer().as_string()).unwrap();
+    let pequod = &status[0]["configurations"][0]["flights"][1];
+    assert_eq!(pequod["name"], json!("pequod"));
+    assert_eq!(pequod["exited"], json!(1));
+    assert_eq!(pequod["errored"], json!(1));
+    assert_eq!(pequod["crashes"], json!(0));
+    printer().clear();
+    get_containers.delete();
+}
+
+#[test]
+fn formation_launch_pin_digest() {
+    const DIGEST: &str = "sha256:7cc4b5aefd1d0cadf8d97d4350462ba51c694ebca145b08d7d41b41acc8db5aa";
+    // The mock server is a local registry, so it is reached over HTTP
+    let image = format!("{}:{}/stubb/alpine:latest", MOCK_SERVER.host(), MOCK_SERVER.port());
+    let cfg_json = |image: &str| {
+        let mut cfg = default_cfg_json();
+        for flight in cfg["flights"].as_array_mut().unwrap() {
+            flight["image"] = json!(image);
+        }
+        cfg
+    };
+
+    let fcm: FormationConfigurationModel =
+        serde_json::from_str(&cfg_json(&image).to_string()).unwrap();
+    let fc = FormationConfiguration::new(fcm);
+    let mut f = Formation::new("stubb");
+    f.local.insert(fc.id);
+    let mut ctx = Ctx::default();
+    ctx.compute_url = Some(MOCK_SERVER.base_url().parse().unwrap());
+    ctx.identity_url = Some(MOCK_SERVER.base_url().parse().unwrap());
+    ctx.db.formations.formations.push(f);
+    ctx.db.formations.configurations.push(fc);
+
+    let mut resolve = MOCK_SERVER.mock(|w, t| {
+        w.method(Method::HEAD)
+            .path("/v2/stubb/alpine/manifests/latest");
+        t.status(200).header("docker-content-digest", DIGEST);
+    });
+    let mut try_to_add_cfg = MOCK_SERVER.mock(|w, t| {
+        when_json(w, POST, "/v1/formations/stubb/configurations").query_param("active", "false");
+        t.status(404);
+    });
+    // Only the pinned images may be uploaded
+    let pinned = format!("{image}@{DIGEST}");
+    let mut create_new_formation = MOCK_SERVER.mock(|w, t| {
+        when_json(w, POST, "/v1/formations/stubb")
+            .query_param("active", "false")
+            .json_body_obj(&cfg_json(&pinned));
+        t.status(201).json_body_obj(&json!([DEFAULT_CFG_UUID]));
+    });
+    let mut get_subdomain = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/formations/stubb");
+        then(t, &json!({"url":"https://stubb--bar.on.cplane.cloud/"}));
+    });
+
+    let res = test_main_with_ctx(
+        &seaplane_cli::test_run(
+            "seaplane --stateless --api-key abc123 formation launch stubb --grounded --pin-digest"
+                .split(' '),
+        )
+        .unwrap(),
+        ctx,
+    );
+    assert!(res.is_ok(), "{res:?}");
+    resolve.assert_hits(2);
+    try_to_add_cfg.assert_hits(1);
+    create_new_formation.assert_hits(1);
+    get_subdomain.assert_hits(1);
+
+    let correct_out = format!(
+        "Pinned Flight Plan flask to {pinned}
+Pinned Flight Plan pequod to {pinned}
+Successfully Launched remote Formation Instance 'stubb' with Configuration UUIDs:
+{DEFAULT_CFG_UUID}
+*"
+    );
+    let actual_out = printer().as_string().trim().to_string();
+    assert!(
+        WildMatch::new(&correct_out).matches(&actual_out),
+        "\ncorrect:\n{correct_out}\n\nactual:\n{actual_out}"
+    );
+    printer().clear();
+    resolve.delete();
+    try_to_add_cfg.delete();
+    create_new_formation.delete();
+    get_subdomain.delete();
+}
+
+#[test]
+fn formation_launch_check_arch() {
+    let image = format!("{}:{}/stubb/arch:latest", MOCK_SERVER.host(), MOCK_SERVER.port());
+    let mut cfg = default_cfg_json();
+    for flight in cfg["flights"].as_array_mut().unwrap() {
+        flight["image"] = json!(image);
+        flight["architecture"] = json!(["arm64"]);
+    }
+    let fcm: FormationConfigurationModel = serde_json::from_str(&cfg.to_string()).unwrap();
+    let fc = FormationConfiguration::new(fcm);
+    let mut f = Formation::new("stubb");
+    f.local.insert(fc.id);
+    let mut ctx = Ctx::default();
+    ctx.compute_url = Some(MOCK_SERVER.base_url().parse().unwrap());
+    ctx.identity_url = Some(MOCK_SERVER.base_url().parse().unwrap());
+    ctx.db.formations.formations.push(f);
+    ctx.db.formations.configurations.push(fc);
+
+    let mut manifest = MOCK_SERVER.mock(|w, t| {
+        w.method(GET).path("/v2/stubb/arch/manifests/latest");
+        t.status(200)
+            .header("content-type", "application/vnd.oci.image.index.v1+json")
+            .header("docker-content-digest", "sha256:abc")
+            .json_body(json!({
+                "schemaVersion": 2,
+                "mediaType": "application/vnd.oci.image.index.v1+json",
+                "manifests": [
+                    {"digest": "sha256:aaa", "platform": {"architecture": "amd64", "os": "linux"}}
+                ]
+            }));
+    });
+    // Nothing may be uploaded when an architecture is missing
+    let mut try_to_add_cfg = MOCK_SERVER.mock(|w, t| {
+        when_json(w, POST, "/v1/formations/stubb/configurations");
+        t.status(404);
+    });
+
+    let res = test_main_with_ctx(
+        &seaplane_cli::test_run(
+            "seaplane --stateless --api-key abc123 formation launch stubb --check-arch".split(' '),
+        )
+        .unwrap(),
+        ctx,
+    );
+    assert_eq!(
+        res.unwrap_err().kind(),
+        &CliErrorKind::ImageMissingArchitectures(vec!["ARM64".into()])
+    );
+    manifest.assert_hits(1);
+    try_to_add_cfg.assert_hits(0);
+    printer().clear();
+    manifest.delete();
+    try_to_add_cfg.delete();
+}
+
+#[test]
+fn formation_clone() {
+    let mut clone_from = MOCK_SERVER.mock(|w, t| {
+        when(w, POST, "/v1/formations/ahab")
+            .query_param("active", "false")
+            .query_param("source", "stubb");
+        then(t, &json!([DEFAULT_CFG_UUID]));
+    });
+    let mut get_cfg = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, format!("/v1/formations/ahab/configurations/{DEFAULT_CFG_UUID}"));
+        then(t, &default_cfg_json());
+    });
+
+    let mut ctx = Ctx::default();
+    ctx.compute_url = Some(MOCK_SERVER.base_url().parse().unwrap());
+    ctx.identity_url = Some(MOCK_SERVER.base_url().parse().unwrap());
+    let res = test_main_with_ctx(&cli!("formation clone stubb ahab"), ctx);
+    assert!(res.is_ok(), "{res:?}");
+    clone_from.assert_hits(1);
+    get_cfg.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        format!(
+            "Successfully cloned remote Formation Instance 'stubb' to 'ahab' with Configuration UUIDs:
+{DEFAULT_CFG_UUID}
+(hint: the new Formation Instance is Grounded, start it with 'seaplane formation launch ahab')"
+        )
+    );
+    printer().clear();
+    clone_from.delete();
+    get_cfg.delete();
+}
+
+#[test]
+fn formation_clone_active() {
+    let mut clone_from = MOCK_SERVER.mock(|w, t| {
+        when(w, POST, "/v1/formations/ahab")
+            .query_param("active", "true")
+            .query_param("source", "stubb");
+        then(t, &json!([DEFAULT_CFG_UUID]));
+    });
+    let mut get_cfg = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, format!("/v1/formations/ahab/configurations/{DEFAULT_CFG_UUID}"));
+        then(t, &default_cfg_json());
+    });
+
+    let mut ctx = Ctx::default();
+    ctx.compute_url = Some(MOCK_SERVER.base_url().parse().unwrap());
+    ctx.identity_url = Some(MOCK_SERVER.base_url().parse().unwrap());
+    let res = test_main_with_ctx_mut(&cli!("formation clone stubb ahab --active"), &mut ctx);
+    assert!(res.is_ok(), "{res:?}");
+    clone_from.assert_hits(1);
+    get_cfg.assert_hits(1);
+
+    // The active configurations of the new Formation Instance can be rolled back to
+    let formation = &ctx.db.formations.formations[0];
+    assert_eq!(formation.history.len(), 1);
+    let set = formation.history.get(1).unwrap();
+    assert_eq!(set.configurations.len(), 1);
+    assert_eq!(set.configurations[0].uuid.to_string(), DEFAULT_CFG_UUID);
+    assert_eq!(set.configurations[0].id, Some(ctx.db.formations.configurations[0].id));
+    printer().clear();
+    clone_from.delete();
+    get_cfg.delete();
+}
+
+#[test]
+fn formation_clone_local() {
+    let res = test_main_with_ctx(
+        &cli!("formation clone stubb ahab --local"),
+        build_ctx_with_default_formation(true),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    assert!(WildMatch::new(
+        "Successfully cloned local Formation Plan 'stubb' to 'ahab' with ID '????????'"
+    )
+    .matches(printer().as_string().trim()));
+    printer().clear();
+
+    // The new name must not already exist
+    let res = test_main_with_ctx(
+        &cli!("formation clone stubb stubb --local"),
+        build_ctx_with_default_formation(true),
+    );
+    assert_eq!(res.unwrap_err().kind(), &CliErrorKind::DuplicateName("stubb".into()));
+    printer().clear();
+}
+
+#[test]
+fn formation_edit() {
+    let mut cfg = default_cfg_json();
+    cfg["flights"].as_array_mut().unwrap().pop();
+    cfg["public_endpoints"] = json!({"http:/flask":"flask:8080"});
+    cfg["flight_endpoints"] = json!({"tcp:22":"flask:2222"});
+    cfg["providers_allowed"] = json!(["AWS"]);
+    cfg["regions_denied"] = json!(["XN"]);
+
+    let mut try_to_add_cfg = MOCK_SERVER.mock(|w, t| {
+        when_json(w, POST, "/v1/formations/stubb/configurations")
+            .query_param("active", "false")
+            .json_body_obj(&cfg);
+        t.status(404);
+    });
+    // Only the edited configuration may be uploaded
+    let mut create_new_formation = MOCK_SERVER.mock(|w, t| {
+        when_json(w, POST, "/v1/formations/stubb")
+            .query_param("active", "false")
+            .json_body_obj(&cfg);
+        t.status(201).json_body_obj(&json!([DEFAULT_CFG_UUID]));
+    });
+    let mut get_subdomain = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/formations/stubb");
+        then(t, &json!({"url":"https://stubb--bar.on.cplane.cloud/"}));
+    });
+
+    let res = test_main_with_ctx(
+        &cli!(
+            "formation edit stubb \
+                --remove-flight-plan pequod \
+                --remove-public-endpoint / \
+                --public-endpoint /flask=flask:8080 \
+                --flight-endpoint tcp:22=flask:2222 \
+                --provider aws \
+                --exclude-region xn \
+                --grounded"
+        ),
+        build_ctx_with_default_formation(true),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    try_to_add_cfg.assert_hits(1);
+    create_new_formation.assert_hits(1);
+    get_subdomain.assert_hits(1);
+    let actual_out = printer().as_string().trim().to_string();
+    assert!(
+        WildMatch::new(
+            "Successfully edited Formation Plan 'stubb' with new Configuration ID '????????'\n*\
+            Successfully Launched remote Formation Instance 'stubb' with Configuration UUIDs:*"
+        )
+        .matches(&actual_out),
+        "{actual_out}"
+    );
+    printer().clear();
+    try_to_add_cfg.delete();
+    create_new_formation.delete();
+    get_subdomain.delete();
+}
+
+#[test]
+fn formation_edit_keeps_unstable_parts() {
+    // Only the flags editing these require the unstable feature
+    let mut base = default_cfg_json();
+    base["affinity"] = json!(["moby"]);
+    base["connections"] = json!(["dick"]);
+    base["formation_endpoints"] = json!({"tcp:1234":"flask:4321"});
+    let ctx = build_ctx_with_formation(base.clone(), true);
+
+    let mut cfg = base;
+    cfg["providers_allowed"] = json!(["AWS"]);
+    let mut create_new_formation = MOCK_SERVER.mock(|w, t| {
+        when_json(w, POST, "/v1/formations/stubb")
+            .query_param("active", "false")
+            .json_body_obj(&cfg);
+        t.status(201).json_body_obj(&json!([DEFAULT_CFG_UUID]));
+    });
+    let mut try_to_add_cfg = MOCK_SERVER.mock(|w, t| {
+        when_json(w, POST, "/v1/formations/stubb/configurations").query_param("active", "false");
+        t.status(404);
+    });
+    let mut get_subdomain = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/formations/stubb");
+        then(t, &json!({"url":"https://stubb--bar.on.cplane.cloud/"}));
+    });
+
+    let res = test_main_with_ctx(&cli!("formation edit stubb --provider aws --grounded"), ctx);
+    assert!(res.is_ok(), "{res:?}");
+    create_new_formation.assert_hits(1);
+    printer().clear();
+    create_new_formation.delete();
+    try_to_add_cfg.delete();
+    get_subdomain.delete();
+}
+
+#[test]
+fn formation_edit_invalid() {
+    // Nothing to do
+    let res =
+        test_main_with_ctx(&cli!("formation edit stubb"), build_ctx_with_default_formation(true));
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(printer().as_string().trim(), "Formation Plan 'stubb' is unchanged");
+    printer().clear();
+
+    let res = test_main_with_ctx(
+        &cli!("formation edit ahab --provider aws"),
+        build_ctx_with_default_formation(true),
+    );
+    assert_eq!(res.unwrap_err().kind(), &CliErrorKind::NoMatchingItem("ahab".into()));
+
+    // The public endpoint still routes to the removed Flight
+    let res = test_main_with_ctx(
+        &cli!("formation edit stubb --remove-flight-plan flask"),
+        build_ctx_with_default_formation(true),
+    );
+    assert_eq!(
+        res.unwrap_err().kind(),
+        &CliErrorKind::EndpointUnknownFlight("http:/".into(), "flask".into())
+    );
+    printer().clear();
+}
+
+#[cfg(unix)]
+#[test]
+fn formation_edit_editor() {
+    // The "editor" edits the JSON in place, just like a human would
+    std::env::set_var("VISUAL", "sed -i s/flask:80/flask:8080/");
+    let res = test_main_with_ctx(
+        &cli!("formation edit stubb --editor"),
+        build_ctx_with_default_formation(true),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    assert!(WildMatch::new(
+        "Successfully edited Formation Plan 'stubb' with new Configuration ID '????????'"
+    )
+    .matches(printer().as_string().trim()));
+    printer().clear();
+
+    // Edits are validated before saving
+    std::env::set_var("VISUAL", "sed -i s/flask:80/ahab:80/");
+    let res = test_main_with_ctx(
+        &cli!("formation edit stubb --editor"),
+        build_ctx_with_default_formation(true),
+    );
+    assert_eq!(
+        res.unwrap_err().kind(),
+        &CliErrorKind::EndpointUnknownFlight("http:/".into(), "ahab".into())
+    );
+
+    // A failing editor aborts the edit
+    std::env::set_var("VISUAL", "false");
+    let res = test_main_with_ctx(
+        &cli!("formation edit stubb --editor"),
+        build_ctx_with_default_formation(true),
+    );
+    assert_eq!(res.unwrap_err().kind(), &CliErrorKind::ChildFailed(Some(1)));
+    std::env::remove_var("VISUAL");
+    printer().clear();
+}
+
+#[test]
+fn formation_endpoint_list() {
+    let res = test_main_with_ctx(
+        &cli!("formation endpoint list stubb"),
+        build_ctx_with_default_formation(true),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(
+        printer().as_string().trim(),
+        "KIND    ENDPOINT  FLIGHT  PORT\npublic  http:/    flask   80"
+    );
+    printer().clear();
+
+    let res = test_main_with_ctx(
+        &cli!("formation endpoint list stubb --format json"),
+        build_ctx_with_default_formation(true),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(
+        printer().as_string().trim(),
+        json!([{"kind":"public","endpoint":"http:/","flight":"flask","port":80}]).to_string()
+    );
+    printer().clear();
+}
+
+#[test]
+fn formation_endpoint_add() {
+    let res = test_main_with_ctx(
+        &cli!(
+            "formation endpoint add stubb --public /pequod=pequod:8080 --flight tcp:22=flask:2222"
+        ),
+        build_ctx_with_default_formation(true),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    assert!(WildMatch::new(
+        "Added public endpoint 'http:/pequod=pequod:8080'
+Added flight endpoint 'tcp:22=flask:2222'
+Successfully updated Formation Plan 'stubb' with new Configuration ID '????????'"
+    )
+    .matches(printer().as_string().trim()));
+    printer().clear();
+
+    // The route is already used by a public endpoint
+    let res = test_main_with_ctx(
+        &cli!("formation endpoint add stubb --flight http:/=pequod:80"),
+        build_ctx_with_default_formation(true),
+    );
+    assert_eq!(res.unwrap_err().kind(), &CliErrorKind::EndpointCollision("http:/".into()));
+
+    // The Flight is not part of the Formation Plan
+    let res = test_main_with_ctx(
+        &cli!("formation endpoint add stubb --public /ahab=ahab:80"),
+        build_ctx_with_default_formation(true),
+    );
+    assert_eq!(
+        res.unwrap_err().kind(),
+        &CliErrorKind::EndpointUnknownFlight("http:/ahab".into(), "ahab".into())
+    );
+    printer().clear();
+}
+
+#[test]
+fn formation_endpoint_remove() {
+    let res = test_main_with_ctx(
+        &cli!("formation endpoint remove stubb /"),
+        build_ctx_with_default_formation(true),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    assert!(WildMatch::new(
+        "Removed public endpoint 'http:/=flask:80'
+Successfully updated Formation Plan 'stubb' with new Configuration ID '????????'"
+    )
+    .matches(printer().as_string().trim()));
+    printer().clear();
+
+    let res = test_main_with_ctx(
+        &cli!("formation endpoint remove stubb tcp:22"),
+        build_ctx_with_default_formation(true),
+    );
+    assert_eq!(res.unwrap_err().kind(), &CliErrorKind::NoMatchingItem("tcp:22".into()));
+    printer().clear();
+}
+
+#[test]
+fn formation_launch_endpoint_collision() {
+    let mut cfg = default_cfg_json();
+    cfg["flight_endpoints"] = json!({"http:/":"pequod:80"});
+    let fcm: FormationConfigurationModel = serde_json::from_str(&cfg.to_string()).unwrap();
+    let fc = FormationConfiguration::new(fcm);
+    let mut f = Formation::new("stubb");
+    f.local.insert(fc.id);
+    let mut ctx = Ctx::default();
+    ctx.compute_url = Some(MOCK_SERVER.base_url().parse().unwrap());
+    ctx.identity_url = Some(MOCK_SERVER.base_url().parse().unwrap());
+    ctx.db.formations.formations.push(f);
+    ctx.db.formations.configurations.push(fc);
+
+    let res = test_main_with_ctx(&cli!("formation launch stubb"), ctx);
+    assert_eq!(res.unwrap_err().kind(), &CliErrorKind::EndpointCollision("http:/".into()));
+    printer().clear();
+}
+
+fn container_json(flight: &str, status: &str) -> serde_json::Value {
+    json!({
+        "container_id" : "557f87c3-b26c-428c-b970-cb8acac2bd68",
+        "status" : status,
+        "flight_name": flight,
+        "configuration_id" : DEFAULT_CFG_UUID,
+        "exit_status": null,
+        "start_time": "2022-04-26 10:23:09Z",
+        "stop_time": null,
+        "public_ingress_usage": 123456_u64,
+        "public_egress_usage": 123456_u64,
+        "private_ingress_usage": 123456_u64,
+        "private_egress_usage": 123456_u64,
+        "disk_usage": 123456_u64,
+        "ram_usage": 123456_u64,
+        "cpu_usage": 123456_u64,
+        "host_latitude": 29.984142_f32,
+        "host_longitude": -95.332986_f32,
+        "host_iata": "IAH",
+        "host_country": "US",
+        "host_region": Region::XN,
+        "host_provider": Provider::AWS,
+    })
+}
+
+/// Mocks the active configuration of `stubb` and the given containers
+fn mock_wait(containers: serde_json::Value) -> Vec<httpmock::Mock<'static>> {
+    let active = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/formations/stubb/activeConfiguration");
+        then(t, &json!([{"configuration_id": DEFAULT_CFG_UUID, "traffic_weight": 1.0_f32}]));
+    });
+    let get_cfg = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, format!("/v1/formations/stubb/configurations/{DEFAULT_CFG_UUID}"));
+        then(t, &default_cfg_json());
+    });
+    let get_containers = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/formations/stubb/containers");
+        then(t, &containers);
+    });
+    vec![active, get_cfg, get_containers]
+}
+
+#[test]
+fn formation_wait() {
+    let mut mocks =
+        mock_wait(json!([container_json("flask", "running"), container_json("pequod", "running")]));
+    let res =
+        test_main_with_ctx(&cli!("formation wait stubb"), build_ctx_with_default_formation(false));
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(mocks[2].hits(), 1, "get_containers");
+    assert_eq!(
+        printer().as_string().trim(),
+        "Waiting for Formation Instance 'stubb' to be up...
+  flask 1/1, pequod 1/1
+Formation Instance 'stubb' is up"
+    );
+    printer().clear();
+    mocks.iter_mut().for_each(|m| m.delete());
+
+    // A Flight without any containers keeps the Formation from being up
+    let mut mocks = mock_wait(json!([container_json("flask", "running")]));
+    let res = test_main_with_ctx(
+        &cli!("formation wait stubb --timeout 0"),
+        build_ctx_with_default_formation(false),
+    );
+    assert_eq!(
+        res.unwrap_err().kind(),
+        &CliErrorKind::FormationWaitTimeout("stubb".into(), WaitFor::Up)
+    );
+    assert!(printer().as_string().contains("flask 1/1, pequod 0/1"));
+    printer().clear();
+    mocks.iter_mut().for_each(|m| m.delete());
+
+    let mut mocks = mock_wait(json!([container_json("flask", "stopped")]));
+    let res = test_main_with_ctx(
+        &cli!("formation wait stubb --for down"),
+        build_ctx_with_default_formation(false),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    assert!(printer()
+        .as_string()
+        .trim()
+        .ends_with("Formation Instance 'stubb' is down"));
+    printer().clear();
+    mocks.iter_mut().for_each(|m| m.delete());
+}
+
+#[test]
+fn formation_wait_unavailable() {
+    let mut mocks = mock_wait(json!([]));
+    mocks.pop().unwrap().delete();
+    let mut get_containers = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/formations/stubb/containers");
+        t.status(503).json_body(json!({
+            "status": 503,
+            "title": "Service Unavailable",
+            "detail": "try again later"
+        }));
+    });
+
+    // The check is retried until the timeout instead of failing with the API error
+    let res = test_main_with_ctx(
+        &cli!("formation wait stubb --timeout 0"),
+        build_ctx_with_default_formation(false),
+    );
+    assert_eq!(
+        res.unwrap_err().kind(),
+        &CliErrorKind::FormationWaitTimeout("stubb".into(), WaitFor::Up)
+    );
+    get_containers.assert_hits(1);
+    assert!(printer().as_string().contains(
+        "warn: failed to check the containers of Formation Instance 'stubb', trying again"
+    ));
+    printer().clear();
+    get_containers.delete();
+    mocks.iter_mut().for_each(|m| m.delete());
+}
+
+#[test]
+fn formation_launch_wait() {
+    let mut try_to_add_cfg = MOCK_SERVER.mock(|w, t| {
+        when_json(w, POST, "/v1/formations/stubb/configurations").query_param("active", "false");
+        t.status(404);
+    });
+    let mut create_new_formation = MOCK_SERVER.mock(|w, t| {
+        when_json(w, POST, "/v1/formations/stubb").query_param("active", "true");
+        t.status(201).json_body_obj(&json!([DEFAULT_CFG_UUID]));
+    });
+    let mut get_subdomain = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/formations/stubb");
+        then(t, &json!({"url":"https://stubb--bar.on.cplane.cloud/"}));
+    });
+    let mut mocks =
+        mock_wait(json!([container_json("flask", "running"), container_json("pequod", "running")]));
+
+    let res = test_main_with_ctx(
+        &cli!("formation launch stubb --wait"),
+        build_ctx_with_default_formation(true),
+    );
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(create_new_formation.hits(), 1, "create_new_formation");
+    assert_eq!(mocks[2].hits(), 1, "get_containers");
+    assert!(printer()
+        .as_string()
+        .trim()
+        .ends_with("Formation Instance 'stubb' is up"));
+    printer().clear();
+    try_to_add_cfg.delete();
+    create_new_formation.delete();
+    get_subdomain.delete();
+    mocks.iter_mut().for_each(|m| m.delete());
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_rollout() {
+    const NEW_CFG_UUID: &str = "9b8e3b1c-5d4f-4c1e-a1f3-3a2b1c0d9e8f";
+    let ctx = || {
+        let mut ctx = ctx_with_remote_id();
+        let mut cfg = default_cfg_json();
+        cfg["public_endpoints"] = json!({"http:/":"flask:8080"});
+        let fc = FormationConfiguration::new(serde_json::from_str(&cfg.to_string()).unwrap());
+        ctx.db.formations.formations[0].local.insert(fc.id);
+        ctx.db.formations.configurations.push(fc);
+        ctx
+    };
+    let container = |flight: &str, status: &str, exit_status: i32| {
+        let mut c = container_json(flight, status);
+        c["configuration_id"] = json!(NEW_CFG_UUID);
+        c["exit_status"] = json!(exit_status);
+        c
+    };
+    let mocks = |containers: serde_json::Value| {
+        let active = MOCK_SERVER.mock(|w, t| {
+            when_json(w, GET, "/v1/formations/stubb/activeConfiguration");
+            then(t, &json!([{"configuration_id": DEFAULT_CFG_UUID, "traffic_weight": 1.0_f32}]));
+        });
+        let add_cfg = MOCK_SERVER.mock(|w, t| {
+            when_json(w, POST, "/v1/formations/stubb/configurations")
+                .query_param("active", "false");
+            t.status(201).json_body_obj(&json!(NEW_CFG_UUID));
+        });
+        let half = MOCK_SERVER.mock(|w, t| {
+            when_json(w, PUT, "/v1/formations/stubb/activeConfiguration").json_body_obj(&json!([
+                {"configuration_id": DEFAULT_CFG_UUID, "traffic_weight": 50.0_f32},
+                {"configuration_id": NEW_CFG_UUID, "traffic_weight": 50.0_f32}
+            ]));
+            t.status(200);
+        });
+        let full = MOCK_SERVER.mock(|w, t| {
+            when_json(w, PUT, "/v1/formations/stubb/activeConfiguration").json_body_obj(&json!([
+                {"configuration_id": NEW_CFG_UUID, "traffic_weight": 100.0_f32}
+            ]));
+            t.status(200);
+        });
+        let restore = MOCK_SERVER.mock(|w, t| {
+            when_json(w, PUT, "/v1/formations/stubb/activeConfiguration").json_body_obj(&json!([
+                {"configuration_id": DEFAULT_CFG_UUID, "traffic_weight": 1.0_f32}
+            ]));
+            t.status(200);
+        });
+        let get_containers = MOCK_SERVER.mock(|w, t| {
+            when_json(w, GET, "/v1/formations/stubb/containers");
+            then(t, &containers);
+        });
+        vec![active, add_cfg, half, full, restore, get_containers]
+    };
+
+    let mut m = mocks(json!([container("flask", "running", 0), container("pequod", "running", 0)]));
+    let res = test_main_with_ctx(&cli!("formation rollout stubb --steps 50 --interval 0"), ctx());
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(m[1].hits(), 1, "add_cfg");
+    assert_eq!(m[2].hits(), 1, "half");
+    assert_eq!(m[3].hits(), 1, "full");
+    assert_eq!(m[4].hits(), 0, "restore");
+    assert_eq!(
+        printer().as_string().trim(),
+        format!(
+            "Uploaded new configuration {NEW_CFG_UUID} of Formation Instance 'stubb'
+Shifted 50% of traffic to the new configuration
+  flask 1/1, pequod 1/1
+Shifted 100% of traffic to the new configuration
+  flask 1/1, pequod 1/1
+Successfully rolled out configuration {NEW_CFG_UUID} to Formation Instance 'stubb'"
+        )
+    );
+    printer().clear();
+    m.iter_mut().for_each(|m| m.delete());
+
+    // An errored container rolls back to the previously active configuration
+    let mut m = mocks(json!([container("flask", "stopped", 1), container("pequod", "running", 0)]));
+    let res = test_main_with_ctx(&cli!("formation rollout stubb --steps 50 --interval 0"), ctx());
+    assert_eq!(
+        res.unwrap_err().kind(),
+        &CliErrorKind::FormationRolloutFailed(vec!["flask".into()])
+    );
+    assert_eq!(m[2].hits(), 1, "half");
+    assert_eq!(m[3].hits(), 0, "full");
+    assert_eq!(m[4].hits(), 1, "restore");
+    printer().clear();
+    m.iter_mut().for_each(|m| m.delete());
+
+    // So does failing to check the containers once traffic was shifted
+    let mut m = mocks(json!([]));
+    m.pop().unwrap().delete();
+    let mut get_containers = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/formations/stubb/containers");
+        t.status(500).json_body(json!({
+            "status": 500,
+            "title": "Internal Server Error",
+            "detail": "something went wrong"
+        }));
+    });
+    let res = test_main_with_ctx(&cli!("formation rollout stubb --steps 50 --interval 0"), ctx());
+    match res.unwrap_err().kind() {
+        CliErrorKind::Seaplane(SeaplaneError::ApiResponse(ae)) => {
+            assert_eq!(ae.kind, ApiErrorKind::InternalServerError)
+        }
+        kind => panic!("unexpected error {kind:?}"),
+    }
+    get_containers.assert_hits(1);
+    assert_eq!(m[2].hits(), 1, "half");
+    assert_eq!(m[3].hits(), 0, "full");
+    assert_eq!(m[4].hits(), 1, "restore");
+    assert!(printer()
+        .as_string()
+        .trim()
+        .ends_with("Restored the previously active configurations"));
+    printer().clear();
+    get_containers.delete();
+    m.iter_mut().for_each(|m| m.delete());
+}
+
+#[cfg_attr(feature = "unstable", test)]
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+fn formation_rollout_rollback() {
+    const NEW_CFG_UUID: &str = "3c4d5e6f-7a8b-4c9d-8e0f-1a2b3c4d5e6f";
+    let mut ctx = ctx_with_remote_id();
+    ctx.db
+        .formations
+        .record_active_configurations("stubb", &[(DEFAULT_CFG_UUID.parse().unwrap(), 1.0)]);
+    let mut cfg = default_cfg_json();
+    cfg["public_endpoints"] = json!({"http:/":"flask:8080"});
+    let fc = FormationConfiguration::new(serde_json::from_str(&cfg.to_string()).unwrap());
+    ctx.db.formations.formations[0].local.insert(fc.id);
+    ctx.db.formations.configurations.push(fc);
+
+    let container = |flight: &str| {
+        let mut c = container_json(flight, "running");
+        c["configuration_id"] = json!(NEW_CFG_UUID);
+        c
+    };
+    let set_active = |body: serde_json::Value| {
+        MOCK_SERVER.mock(|w, t| {
+            when_json(w, PUT, "/v1/formations/stubb/activeConfiguration").json_body_obj(&body);
+            t.status(200);
+        })
+    };
+    let mut mocks = [
+        MOCK_SERVER.mock(|w, t| {
+            when_json(w, GET, "/v1/formations/stubb/activeConfiguration");
+            then(t, &json!([{"configuration_id": DEFAULT_CFG_UUID, "traffic_weight": 1.0_f32}]));
+        }),
+        MOCK_SERVER.mock(|w, t| {
+            when_json(w, POST, "/v1/formations/stubb/configurations")
+                .query_param("active", "false");
+            t.status(201).json_body_obj(&json!(NEW_CFG_UUID));
+        }),
+        MOCK_SERVER.mock(|w, t| {
+            when_json(w, GET, "/v1/formations/stubb/containers");
+            then(t, &json!([container("flask"), container("pequod")]));
+        }),
+        MOCK_SERVER.mock(|w, t| {
+            when_json(w, GET, "/v1/formations/stubb/configurations");
+            then(t, &json!([DEFAULT_CFG_UUID, NEW_CFG_UUID]));
+        }),
+        set_active(json!([
+            {"configuration_id": DEFAULT_CFG_UUID, "traffic_weight": 50.0_f32},
+            {"configuration_id": NEW_CFG_UUID, "traffic_weight": 50.0_f32}
+        ])),
+        set_active(json!([{"configuration_id": NEW_CFG_UUID, "traffic_weight": 100.0_f32}])),
+        set_active(json!([{"configuration_id": DEFAULT_CFG_UUID, "traffic_weight": 1.0_f32}])),
+    ];
+
+    let res =
+        test_main_with_ctx_mut(&cli!("formation rollout stubb --steps 50 --interval 0"), &mut ctx);
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(mocks[4].hits(), 1, "half");
+    assert_eq!(mocks[5].hits(), 1, "full");
+    assert_eq!(mocks[6].hits(), 0, "restore");
+    // Only the final set of the rollout is recorded
+    let history = &ctx.db.formations.formations[0].history;
+    assert_eq!(history.len(), 2);
+    let set = history.get(2).unwrap();
+    assert_eq!(set.configurations.len(), 1);
+    assert_eq!(set.configurations[0].uuid.to_string(), NEW_CFG_UUID);
+    printer().clear();
+
+    // So rolling back returns to the configurations active before the rollout
+    let res = test_main_with_ctx_mut(&cli!("formation rollback stubb"), &mut ctx);
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(mocks[4].hits(), 1, "half");
+    assert_eq!(mocks[6].hits(), 1, "restore");
+    assert_eq!(
+        printer().as_string().trim(),
+        "Successfully rolled back Formation Instance 'stubb' to #1"
+    );
+    printer().clear();
+    mocks.iter_mut().for_each(|m| m.delete());
+}
+
+const PREV_CFG_UUID: &str = "0f1e2d3c-4b5a-4968-8776-a5b4c3d2e1f0";
+
+/// A Formation that had PREV_CFG_UUID (the local configuration) active, and then DEFAULT_CFG_UUID
+fn ctx_with_history() -> Ctx {
+    let mut ctx = ctx_with_remote_id();
+    let id = ctx.db.formations.configurations[0].id;
+    let set = |timestamp: &str, uuid: &str, id| ActiveSet {
+        timestamp: timestamp.parse().unwrap(),
+        configurations: vec![ActiveSetConfiguration {
+            uuid: uuid.parse().unwrap(),
+            traffic_weight: 1.0,
+            id,
+        }],
+    };
+    let history = &mut ctx.db.formations.formations[0].history;
+    history.push(set("2022-06-01T12:00:00Z", PREV_CFG_UUID, Some(id)));
+    history.push(ActiveSet {
+        timestamp: "2022-06-02T12:00:00Z".parse().unwrap(),
+        configurations: Vec::new(),
+    });
+    history.push(set("2022-06-03T12:00:00Z", DEFAULT_CFG_UUID, None));
+    ctx
+}
+
+#[test]
+fn formation_history() {
+    let res = test_main_with_ctx(&cli!("formation history stubb"), ctx_with_history());
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(
+        printer().as_string(),
+        format!(
+            "#   TIMESTAMP             CONFIGURATION                         WEIGHT
+1   2022-06-01T12:00:00Z  {PREV_CFG_UUID}  1
+2   2022-06-02T12:00:00Z  (landed)                              -
+3   2022-06-03T12:00:00Z  {DEFAULT_CFG_UUID}  1
+"
+        )
+    );
+    printer().clear();
+
+    let res =
+        test_main_with_ctx(&cli!("formation history stubb --format json"), ctx_with_history());
+    assert!(res.is_ok(), "{res:?}");
+    let history: serde_json::Value = serde_json::from_str(&printer().as_string()).unwrap();
+    assert_eq!(history.as_array().unwrap().len(), 3);
+    assert_eq!(history[1]["configurations"], json!([]));
+    assert_eq!(history[2]["configurations"][0]["uuid"], json!(DEFAULT_CFG_UUID));
+    printer().clear();
+}
+
+#[test]
+fn formation_rollback() {
+    const NEW_CFG_UUID: &str = "5a6b7c8d-9e0f-4a1b-8c2d-3e4f5a6b7c8d";
+    let mut list_cfgs = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/formations/stubb/configurations");
+        then(t, &json!([DEFAULT_CFG_UUID]));
+    });
+    // PREV_CFG_UUID was removed remotely, so it is uploaded again from the local configuration
+    let mut add_cfg = MOCK_SERVER.mock(|w, t| {
+        when_json(w, POST, "/v1/formations/stubb/configurations")
+            .query_param("active", "false")
+            .json_body_obj(&default_cfg_json());
+        t.status(201).json_body_obj(&json!(NEW_CFG_UUID));
+    });
+    let mut set_active = MOCK_SERVER.mock(|w, t| {
+        when_json(w, PUT, "/v1/formations/stubb/activeConfiguration");
+        t.status(200);
+    });
+
+    // By default the set before the current one is restored, here a landed Formation
+    let mut stop = MOCK_SERVER.mock(|w, t| {
+        when(w, DELETE, "/v1/formations/stubb/activeConfiguration");
+        t.status(200);
+    });
+    let res = test_main_with_ctx(&cli!("formation rollback stubb"), ctx_with_history());
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(stop.hits(), 1);
+    assert_eq!(
+        printer().as_string().trim(),
+        "Successfully rolled back Formation Instance 'stubb' to #2 (landed)"
+    );
+    printer().clear();
+    stop.delete();
+
+    let res = test_main_with_ctx(&cli!("formation rollback stubb --to 1"), ctx_with_history());
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(list_cfgs.hits(), 1);
+    assert_eq!(add_cfg.hits(), 1);
+    assert_eq!(set_active.hits(), 1);
+    assert_eq!(
+        printer().as_string().trim(),
+        format!(
+            "Re-uploaded configuration {NEW_CFG_UUID} (previously {PREV_CFG_UUID})
+Successfully rolled back Formation Instance 'stubb' to #1"
+        )
+    );
+    printer().clear();
+
+    let res = test_main_with_ctx(&cli!("formation rollback stubb --to 4"), ctx_with_history());
+    assert_eq!(
+        res.unwrap_err().kind(),
+        &CliErrorKind::OneOff("Formation 'stubb' has no history entry #4".into())
+    );
+    assert_eq!(set_active.hits(), 1);
+    printer().clear();
+
+    list_cfgs.delete();
+    add_cfg.delete();
+    set_active.delete();
+}
+
+#[test]
+fn formation_placement() {
+    let mut cfg = default_cfg_json();
+    cfg["providers_allowed"] = json!(["AWS"]);
+    cfg["regions_denied"] = json!(["XE"]);
+    let container = |id: &str, flight: &str, status: &str| {
+        let mut c = container_json(flight, status);
+        c["container_id"] = json!(id);
+        c
+    };
+    let mut frankfurt = container("8c2d5e1f-0a3b-4c6d-9e7f-1a2b3c4d5e6f", "pequod", "running");
+    frankfurt["host_provider"] = json!("GCP");
+    frankfurt["host_region"] = json!("XE");
+    frankfurt["host_country"] = json!("DE");
+    frankfurt["host_iata"] = json!("FRA");
+    // Stopped containers don't run anywhere, so are not checked
+    let mut stopped = frankfurt.clone();
+    stopped["status"] = json!("stopped");
+
+    let mut get_cfg = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, format!("/v1/formations/stubb/configurations/{DEFAULT_CFG_UUID}"));
+        then(t, &cfg);
+    });
+    let mut get_containers = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/formations/stubb/containers");
+        then(
+            t,
+            &json!([
+                container("557f87c3-b26c-428c-b970-cb8acac2bd68", "flask", "running"),
+                frankfurt,
+                stopped
+            ]),
+        );
+    });
+
+    let res = test_main_with_ctx(&cli!("formation placement stubb"), ctx_with_remote_id());
+    assert_eq!(
+        res.unwrap_err().kind(),
+        &CliErrorKind::FormationPlacementViolations("stubb".into(), 1)
+    );
+    assert_eq!(get_cfg.hits(), 1);
+    assert_eq!(
+        printer().as_string(),
+        "FLIGHT  CONTAINER                             PROVIDER  REGION  COUNTRY  IATA  VIOLATIONS
+flask   557f87c3-b26c-428c-b970-cb8acac2bd68  AWS       XN      US       IAH   -
+pequod  8c2d5e1f-0a3b-4c6d-9e7f-1a2b3c4d5e6f  GCP       XE      DE       FRA   provider GCP is not allowed, region XE is denied
+"
+    );
+    printer().clear();
+
+    let res =
+        test_main_with_ctx(&cli!("formation placement stubb --format json"), ctx_with_remote_id());
+    assert!(res.is_err());
+    let placement: serde_json::Value = serde_json::from_str(&printer().as_string()).unwrap();
+    assert_eq!(placement.as_array().unwrap().len(), 2);
+    assert_eq!(placement[0]["violations"], json!([]));
+    assert_eq!(placement[1]["provider"], json!("GCP"));
+    assert_eq!(
+        placement[1]["violations"],
+        json!(["provider GCP is not allowed", "region XE is denied"])
+    );
+    printer().clear();
+    get_cfg.delete();
+    get_containers.delete();
+}
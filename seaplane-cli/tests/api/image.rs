@@ -0,0 +1,259 @@
+use clap::ArgMatches;
+use httpmock::{prelude::*, Method};
+use seaplane::api::compute::v1::Flight as FlightModel;
+use seaplane_cli::{context::Ctx, error::CliErrorKind, ops::flight::Flight, printer::printer};
+use serde_json::json;
+
+use super::{test_main_with_ctx, MOCK_SERVER};
+
+const DIGEST: &str = "sha256:7cc4b5aefd1d0cadf8d97d4350462ba51c694ebca145b08d7d41b41acc8db5aa";
+const OCI_INDEX: &str = "application/vnd.oci.image.index.v1+json";
+
+// The mock server listens on the local machine, so it is reached as a local registry over HTTP
+fn registry() -> String { format!("{}:{}", MOCK_SERVER.host(), MOCK_SERVER.port()) }
+
+fn image_cli(argv: &str) -> ArgMatches {
+    seaplane_cli::test_run(format!("seaplane --stateless {argv}").split(' ')).unwrap()
+}
+
+#[test]
+fn image_resolve() {
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        w.method(Method::HEAD)
+            .path("/v2/seaplane/resolve/manifests/v1");
+        t.status(200).header("docker-content-digest", DIGEST);
+    });
+
+    let image = format!("{}/seaplane/resolve:v1", registry());
+    let res = test_main_with_ctx(&image_cli(&format!("image resolve {image}")), Ctx::default());
+    assert!(res.is_ok());
+    mock.assert_hits(1);
+    assert_eq!(printer().as_string().trim(), format!("{image}@{DIGEST}"));
+    printer().clear();
+
+    let res = test_main_with_ctx(
+        &image_cli(&format!("image resolve {image} --format json")),
+        Ctx::default(),
+    );
+    assert!(res.is_ok());
+    mock.assert_hits(2);
+    assert_eq!(printer().as_string().trim(), json!({"image": image, "digest": DIGEST}).to_string());
+    printer().clear();
+    mock.delete();
+
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        w.method(Method::HEAD)
+            .path("/v2/seaplane/resolve/manifests/v2");
+        t.status(404);
+    });
+    let image = format!("{}/seaplane/resolve:v2", registry());
+    let res = test_main_with_ctx(&image_cli(&format!("image resolve {image}")), Ctx::default());
+    assert!(res.is_err());
+    mock.assert_hits(1);
+    mock.delete();
+    printer().clear();
+}
+
+#[test]
+fn image_tags() {
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        w.method(GET).path("/v2/seaplane/tags/tags/list");
+        t.status(200)
+            .json_body(json!({"name": "seaplane/tags", "tags": ["latest", "v1"]}));
+    });
+
+    let res = test_main_with_ctx(
+        &image_cli(&format!("image tags {}/seaplane/tags", registry())),
+        Ctx::default(),
+    );
+    assert!(res.is_ok());
+    mock.assert_hits(1);
+    assert_eq!(printer().as_string().trim(), "TAG\nlatest\nv1");
+    printer().clear();
+
+    let res = test_main_with_ctx(
+        &image_cli(&format!("image tags {}/seaplane/tags --no-header", registry())),
+        Ctx::default(),
+    );
+    assert!(res.is_ok());
+    mock.assert_hits(2);
+    assert_eq!(printer().as_string().trim(), "latest\nv1");
+    printer().clear();
+    mock.delete();
+}
+
+#[test]
+fn image_manifest() {
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        w.method(GET).path("/v2/seaplane/manifest/manifests/latest");
+        t.status(200)
+            .header("content-type", OCI_INDEX)
+            .header("docker-content-digest", DIGEST)
+            .json_body(json!({
+                "schemaVersion": 2,
+                "mediaType": OCI_INDEX,
+                "manifests": [
+                    {"digest": "sha256:aaa", "platform": {"architecture": "amd64", "os": "linux"}},
+                    {"digest": "sha256:bbb", "platform": {"architecture": "arm64", "os": "linux", "variant": "v8"}},
+                    {"digest": "sha256:ccc", "platform": {"architecture": "unknown", "os": "unknown"}}
+                ]
+            }));
+    });
+
+    let image = format!("{}/seaplane/manifest", registry());
+    let res = test_main_with_ctx(&image_cli(&format!("image manifest {image}")), Ctx::default());
+    assert!(res.is_ok());
+    mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        format!(
+            "Image: {image}
+Digest: {DIGEST}
+Media Type: {OCI_INDEX}
+
+PLATFORM        DIGEST
+linux/amd64     sha256:aaa
+linux/arm64/v8  sha256:bbb"
+        )
+    );
+    printer().clear();
+    mock.delete();
+}
+
+#[test]
+fn image_pin() {
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        w.method(Method::HEAD).path("/v2/seaplane/pin/manifests/v1");
+        t.status(200).header("docker-content-digest", DIGEST);
+    });
+
+    let image = format!("{}/seaplane/pin:v1", registry());
+    let ctx = || {
+        let mut ctx = Ctx::default();
+        ctx.db.flights.add_flight(Flight::new(
+            FlightModel::builder()
+                .name("pinme")
+                .image(&image)
+                .build()
+                .unwrap(),
+        ));
+        ctx.db.flights.add_flight(Flight::new(
+            FlightModel::builder()
+                .name("pinned")
+                .image(format!("{image}@{DIGEST}"))
+                .build()
+                .unwrap(),
+        ));
+        ctx
+    };
+
+    let res = test_main_with_ctx(&image_cli("image pin pinme"), ctx());
+    assert!(res.is_ok());
+    mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        format!("Pinned Flight Plan pinme to {image}@{DIGEST}\n\nSuccessfully pinned 1 item")
+    );
+    printer().clear();
+
+    let res = test_main_with_ctx(&image_cli("image pin pin --all"), ctx());
+    assert!(res.is_ok());
+    mock.assert_hits(2);
+    assert_eq!(
+        printer().as_string().trim(),
+        format!(
+            "Pinned Flight Plan pinme to {image}@{DIGEST}
+Flight Plan pinned is already pinned to {image}@{DIGEST}
+
+Successfully pinned 1 item"
+        )
+    );
+    printer().clear();
+
+    let res = test_main_with_ctx(&image_cli("image pin pinned --update"), ctx());
+    assert!(res.is_ok());
+    mock.assert_hits(3);
+    assert_eq!(
+        printer().as_string().trim(),
+        format!("Pinned Flight Plan pinned to {image}@{DIGEST}\n\nSuccessfully pinned 1 item")
+    );
+    printer().clear();
+    mock.delete();
+}
+
+#[test]
+fn flight_plan_pin_digest() {
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        w.method(Method::HEAD)
+            .path("/v2/seaplane/plan/manifests/v1");
+        t.status(200).header("docker-content-digest", DIGEST);
+    });
+
+    let image = format!("{}/seaplane/plan:v1", registry());
+    // Only internal runs of `flight plan` may be stateless
+    let mut ctx = Ctx::default();
+    ctx.internal_run = true;
+    let res = test_main_with_ctx(
+        &image_cli(&format!("flight plan --name planned --image {image} --pin-digest")),
+        ctx,
+    );
+    assert!(res.is_ok());
+    mock.assert_hits(1);
+    let out = printer().as_string().to_string();
+    assert!(
+        out.starts_with(&format!(
+            "Pinned Flight Plan planned to {image}@{DIGEST}\nSuccessfully created Flight Plan 'planned'"
+        )),
+        "{out}"
+    );
+    printer().clear();
+    mock.delete();
+}
+
+#[test]
+fn flight_plan_check_arch() {
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        w.method(GET).path("/v2/seaplane/arch/manifests/v1");
+        t.status(200)
+            .header("content-type", OCI_INDEX)
+            .header("docker-content-digest", DIGEST)
+            .json_body(json!({
+                "schemaVersion": 2,
+                "mediaType": OCI_INDEX,
+                "manifests": [
+                    {"digest": "sha256:aaa", "platform": {"architecture": "amd64", "os": "linux"}},
+                    {"digest": "sha256:ccc", "platform": {"architecture": "unknown", "os": "unknown"}}
+                ]
+            }));
+    });
+
+    let image = format!("{}/seaplane/arch:v1", registry());
+    let plan = |args: &str| {
+        // Only internal runs of `flight plan` may be stateless
+        let mut ctx = Ctx::default();
+        ctx.internal_run = true;
+        test_main_with_ctx(
+            &image_cli(&format!("flight plan --name arch --image {image} {args}")),
+            ctx,
+        )
+    };
+
+    assert!(plan("--architecture amd64 --check-arch").is_ok());
+    mock.assert_hits(1);
+    printer().clear();
+
+    let res = plan("--architecture amd64 --architecture arm64 --check-arch");
+    assert_eq!(
+        res.unwrap_err().kind(),
+        &CliErrorKind::ImageMissingArchitectures(vec!["ARM64".into()])
+    );
+    mock.assert_hits(2);
+    printer().clear();
+
+    assert!(plan("--infer-arch").is_ok());
+    mock.assert_hits(3);
+    let out = printer().as_string().to_string();
+    assert!(out.starts_with("Inferred architectures AMD64 for Flight Plan arch\n"), "{out}");
+    printer().clear();
+    mock.delete();
+}
@@ -1,5 +1,16 @@
+use std::time::{Duration, Instant};
+
 use httpmock::{prelude::*, Method};
-use seaplane_cli::printer::printer;
+use seaplane::{
+    api::{locks::v1::LockName, ApiErrorKind},
+    error::SeaplaneError,
+};
+use seaplane_cli::{
+    api::LocksReq,
+    context::Ctx,
+    error::{CliError, CliErrorKind},
+    printer::printer,
+};
 use serde_json::json;
 
 use super::{test_main, then, when_json, MOCK_SERVER};
@@ -36,6 +47,45 @@ fn locks_acquire() {
     mock.delete();
 }
 
+#[test]
+fn locks_acquire_wait_timeout() {
+    let mut acquire_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, POST, "/v1/locks/base64:YnVzeQ");
+        t.status(409).json_body(json!({
+            "title": "Conflict",
+            "status": 409,
+            "detail": "lock is held"
+        }));
+    });
+    let mut info_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/locks/base64:YnVzeQ");
+        then(
+            t,
+            &json!({
+                "name": "YnVzeQ",
+                "id": "D4lbVpdBE_U",
+                "info": {"ttl": 30, "client-id": "other", "ip": "10.0.0.1"}
+            }),
+        );
+    });
+
+    let res = test_main(
+        &cli!("locks acquire busy --client-id bar --ttl 30 --wait --timeout 1"),
+        MOCK_SERVER.base_url(),
+    );
+    assert_eq!(
+        res.unwrap_err().kind(),
+        &CliErrorKind::Seaplane(seaplane::error::SeaplaneError::LockWaitTimeout)
+    );
+    // Retried until the timeout elapsed
+    assert!(acquire_mock.hits() > 1);
+    assert!(info_mock.hits() > 1);
+    assert_eq!(printer().as_string(), "");
+
+    acquire_mock.delete();
+    info_mock.delete();
+}
+
 #[test]
 fn locks_list_output_pages() {
     let server_page = json!({
@@ -445,6 +495,59 @@ fn locks_renew() {
     printer().clear();
 }
 
+#[test]
+fn locks_renew_refreshes_token() {
+    let identity = |token: &'static str| {
+        MOCK_SERVER.mock(move |w, t| {
+            w.method(POST)
+                .path("/identity/token")
+                .header("authorization", "Bearer rotating");
+            t.status(201).json_body(json!({
+                "token": token,
+                "tenant": "tnt-abcdef1234567890",
+                "subdomain": "pequod"
+            }));
+        })
+    };
+    let renew = |token: &str, status: u16, body: serde_json::Value| {
+        MOCK_SERVER.mock(|w, t| {
+            w.method(Method::PATCH)
+                .path("/v1/locks/base64:Zm9v")
+                .header("authorization", format!("Bearer {token}"));
+            t.status(status).json_body(body);
+        })
+    };
+    let mut expired_mock = renew(
+        "expired.token",
+        401,
+        json!({"status": 401, "title": "Unauthorized", "detail": "token expired"}),
+    );
+    let mut renew_mock = renew("fresh.token", 200, json!({"status": 200, "title": "Ok"}));
+
+    let mut ctx = Ctx::default();
+    ctx.args.api_key = Some("rotating".into());
+    ctx.identity_url = Some(MOCK_SERVER.base_url().parse().unwrap());
+    ctx.locks_url = ctx.identity_url.clone();
+
+    let mut identity_mock = identity("expired.token");
+    let mut req = LocksReq::new(&ctx).unwrap();
+    req.set_identifiers(Some(LockName::from_encoded("Zm9v")), Some("ATlcuG7mmF4"))
+        .unwrap();
+    identity_mock.assert_hits(1);
+    identity_mock.delete();
+
+    // The token expires while the lock is held, the retry must use the refreshed one
+    let mut identity_mock = identity("fresh.token");
+    assert!(req.renew(20).is_ok());
+    identity_mock.assert_hits(1);
+    expired_mock.assert_hits(1);
+    renew_mock.assert_hits(1);
+
+    identity_mock.delete();
+    expired_mock.delete();
+    renew_mock.delete();
+}
+
 #[test]
 fn locks_release() {
     let resp_json = json!({"status": 200u32, "title": "Ok"});
@@ -461,3 +564,404 @@ fn locks_release() {
     assert_eq!(printer().as_string().trim(), "Successfully released the lock");
     printer().clear();
 }
+
+fn hold(name: &str, command: &[&str]) -> Result<(), CliError> {
+    let mut argv = vec![
+        "seaplane",
+        "--stateless",
+        "--api-key",
+        "abc123",
+        "locks",
+        "hold",
+        name,
+        "--client-id",
+        "cron",
+        "--ttl",
+        "3",
+        "--",
+    ];
+    argv.extend_from_slice(command);
+    test_main(&seaplane_cli::test_run(argv).unwrap(), MOCK_SERVER.base_url())
+}
+
+#[test]
+fn locks_hold() {
+    let resp_json = json!({"status": 200u32, "title": "Ok"});
+
+    let mut acquire_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, POST, "/v1/locks/base64:aG9sZA");
+        then(t, &json!({"id": "D4lbVpdBE_U", "sequencer": 3}));
+    });
+    let mut renew_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, Method::PATCH, "/v1/locks/base64:aG9sZA").query_param("id", "D4lbVpdBE_U");
+        then(t, &resp_json);
+    });
+    let mut release_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, DELETE, "/v1/locks/base64:aG9sZA").query_param("id", "D4lbVpdBE_U");
+        then(t, &resp_json);
+    });
+
+    // The lock is renewed every second while the command runs, and the lock's details are
+    // exported to it
+    let res = hold(
+        "hold",
+        &[
+            "sh",
+            "-c",
+            r#"[ "$SEAPLANE_LOCK_ID" = D4lbVpdBE_U ] && [ "$SEAPLANE_LOCK_SEQUENCER" = 3 ] && sleep 1.5"#,
+        ],
+    );
+    assert!(res.is_ok());
+    acquire_mock.assert_hits(1);
+    renew_mock.assert_hits(1);
+    release_mock.assert_hits(1);
+
+    // The lock is released even when the command fails
+    let res = hold("hold", &["sh", "-c", "exit 3"]);
+    assert_eq!(res.unwrap_err().kind(), &CliErrorKind::ChildFailed(Some(3)));
+    acquire_mock.assert_hits(2);
+    release_mock.assert_hits(2);
+
+    acquire_mock.delete();
+    renew_mock.delete();
+    release_mock.delete();
+    printer().clear();
+}
+
+#[test]
+fn locks_hold_renew_fails() {
+    let mut acquire_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, POST, "/v1/locks/base64:bG9zdA");
+        then(t, &json!({"id": "D4lbVpdBE_U", "sequencer": 3}));
+    });
+    let mut renew_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, Method::PATCH, "/v1/locks/base64:bG9zdA");
+        t.status(409)
+            .json_body(json!({"status": 409u32, "title": "Conflict"}));
+    });
+    let mut release_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, DELETE, "/v1/locks/base64:bG9zdA");
+        then(t, &json!({"status": 200u32, "title": "Ok"}));
+    });
+
+    // The command is killed long before it would finish on its own
+    let start = Instant::now();
+    let res = hold("lost", &["sleep", "30"]);
+    assert!(start.elapsed() < Duration::from_secs(10));
+    assert!(matches!(res.unwrap_err().kind(), CliErrorKind::Seaplane(_)));
+    renew_mock.assert_hits(1);
+    release_mock.assert_hits(1);
+
+    acquire_mock.delete();
+    renew_mock.delete();
+    release_mock.delete();
+    printer().clear();
+}
+
+#[test]
+fn locks_elect_exit_on_loss() {
+    let mut acquire_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, POST, "/v1/locks/base64:ZWxlY3Q");
+        then(t, &json!({"id": "D4lbVpdBE_U", "sequencer": 3}));
+    });
+    let mut renew_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, Method::PATCH, "/v1/locks/base64:ZWxlY3Q");
+        t.status(409).json_body(json!({
+            "status": 409u32,
+            "title": "Conflict",
+            "detail": "lock is held"
+        }));
+    });
+    let mut release_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, DELETE, "/v1/locks/base64:ZWxlY3Q");
+        then(t, &json!({"status": 200u32, "title": "Ok"}));
+    });
+
+    let res = test_main(
+        &cli!("locks elect elect --client-id bar --ttl 1 --exit-on-loss"),
+        MOCK_SERVER.base_url(),
+    );
+    assert!(matches!(res.unwrap_err().kind(), CliErrorKind::Seaplane(_)));
+    acquire_mock.assert_hits(1);
+    renew_mock.assert_hits(1);
+    // The lock was lost, so there is nothing to release
+    release_mock.assert_hits(0);
+
+    let output = printer().as_string().to_string();
+    let mut lines = output.lines();
+    assert_eq!(lines.next(), Some("Elected leader (LOCK-ID: D4lbVpdBE_U, SEQUENCER: 3)"));
+    assert!(lines.next().unwrap().starts_with("Lost leadership: "));
+    assert_eq!(lines.next(), None);
+
+    acquire_mock.delete();
+    renew_mock.delete();
+    release_mock.delete();
+    printer().clear();
+}
+
+#[test]
+fn locks_elect_fails() {
+    let mut acquire_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, POST, "/v1/locks/base64:ZGVuaWVk");
+        t.status(403).json_body(json!({
+            "status": 403u32,
+            "title": "Forbidden",
+            "detail": "access denied"
+        }));
+    });
+
+    // Only another client holding the lock keeps the campaign going
+    let res =
+        test_main(&cli!("locks elect denied --client-id bar --ttl 30"), MOCK_SERVER.base_url());
+    match res.unwrap_err().kind() {
+        CliErrorKind::Seaplane(SeaplaneError::ApiResponse(ae)) => {
+            assert_eq!(ae.kind, ApiErrorKind::Forbidden)
+        }
+        kind => panic!("unexpected error {kind:?}"),
+    }
+    acquire_mock.assert_hits(1);
+    assert_eq!(printer().as_string(), "");
+
+    acquire_mock.delete();
+    printer().clear();
+}
+
+#[test]
+fn locks_semaphore_acquire() {
+    let mut list_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/locks/base64:ZGVwbG95cw/");
+        then(
+            t,
+            &json!({"next": null, "infos": [{
+                "name": "ZGVwbG95cy8w",
+                "id": "D4lbVpdBE_U",
+                "info": {"ttl": 30, "client-id": "other", "ip": "10.0.0.1"}
+            }]}),
+        );
+    });
+    let mut acquire_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, POST, "/v1/locks/base64:ZGVwbG95cy8x")
+            .query_param("ttl", "30")
+            .query_param("client-id", "bar");
+        then(t, &json!({"id": "D4lbVpdBE_V", "sequencer": 2}));
+    });
+
+    let res = test_main(
+        &cli!("locks semaphore acquire deploys --slots 2 --client-id bar --ttl 30"),
+        MOCK_SERVER.base_url(),
+    );
+    assert!(res.is_ok());
+    list_mock.assert_hits(1);
+    acquire_mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        "LOCK-NAME: deploys/1\nLOCK-ID: D4lbVpdBE_V\nSEQUENCER: 2"
+    );
+    printer().clear();
+
+    // The slot name is printed encoded if the semaphore name was given encoded
+    let res = test_main(
+        &cli!("locks semaphore acquire ZGVwbG95cw --base64 --slots 2 --client-id bar --ttl 30"),
+        MOCK_SERVER.base_url(),
+    );
+    assert!(res.is_ok());
+    assert_eq!(
+        printer().as_string().trim(),
+        "LOCK-NAME: ZGVwbG95cy8x\nLOCK-ID: D4lbVpdBE_V\nSEQUENCER: 2"
+    );
+    printer().clear();
+
+    // All slots are held
+    let res = test_main(
+        &cli!("locks semaphore acquire deploys --slots 1 --client-id bar --ttl 30"),
+        MOCK_SERVER.base_url(),
+    );
+    assert!(matches!(res.unwrap_err().kind(), CliErrorKind::Seaplane(_)));
+    acquire_mock.assert_hits(2);
+
+    list_mock.delete();
+    acquire_mock.delete();
+    printer().clear();
+}
+
+#[test]
+fn locks_semaphore_hold() {
+    let resp_json = json!({"status": 200u32, "title": "Ok"});
+
+    let mut list_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/locks/base64:am9icw/");
+        then(t, &json!({"next": null, "infos": []}));
+    });
+    let mut acquire_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, POST, "/v1/locks/base64:am9icy8w");
+        then(t, &json!({"id": "D4lbVpdBE_U", "sequencer": 3}));
+    });
+    let mut release_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, DELETE, "/v1/locks/base64:am9icy8w").query_param("id", "D4lbVpdBE_U");
+        then(t, &resp_json);
+    });
+
+    // The slot is released once the command exits rather than being left until its TTL runs out
+    let res = test_main(
+        &seaplane_cli::test_run([
+            "seaplane",
+            "--stateless",
+            "--api-key",
+            "abc123",
+            "locks",
+            "semaphore",
+            "hold",
+            "jobs",
+            "--slots",
+            "2",
+            "--client-id",
+            "cron",
+            "--ttl",
+            "30",
+            "--",
+            "sh",
+            "-c",
+            r#"[ "$SEAPLANE_LOCK_ID" = D4lbVpdBE_U ]"#,
+        ])
+        .unwrap(),
+        MOCK_SERVER.base_url(),
+    );
+    assert!(res.is_ok());
+    list_mock.assert_hits(1);
+    acquire_mock.assert_hits(1);
+    release_mock.assert_hits(1);
+
+    list_mock.delete();
+    acquire_mock.delete();
+    release_mock.delete();
+    printer().clear();
+}
+
+fn lock_info(name: &str, id: &str, client_id: &str, ip: &str, ttl: u32) -> serde_json::Value {
+    json!({"name": name, "id": id, "info": {"ttl": ttl, "client-id": client_id, "ip": ip}})
+}
+
+#[test]
+fn locks_list_filters() {
+    let page = json!({
+        "next": null,
+        "infos": [
+            lock_info("a", "MQo", "worker-1", "192.0.2.1", 2),
+            lock_info("b", "Mgo", "worker-1", "192.0.2.1", 30),
+            lock_info("c", "Mwo", "worker-2", "192.0.2.2", 2),
+            lock_info("d", "NAo", "worker-2", "192.0.2.2", 30),
+        ]
+    });
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/locks/base64:c3RhbGU/");
+        then(t, &page);
+    });
+
+    let res = test_main(&cli!("locks list stale/ --client-id worker-1"), MOCK_SERVER.base_url());
+    assert!(res.is_ok());
+    assert_eq!(
+        printer().as_string().trim(),
+        "LOCK-NAME  LOCK-ID  CLIENT-ID  CLIENT-IP  TTL\n\
+         a          MQo      worker-1   192.0.2.1  2\n\
+         b          Mgo      worker-1   192.0.2.1  30"
+    );
+    printer().clear();
+
+    let res = test_main(
+        &cli!("locks list stale/ --ip 192.0.2.2 --max-ttl 10 --no-header"),
+        MOCK_SERVER.base_url(),
+    );
+    assert!(res.is_ok());
+    assert_eq!(printer().as_string().trim(), "c   Mwo  worker-2  192.0.2.2  2");
+    printer().clear();
+
+    let res =
+        test_main(&cli!("locks list stale/ --min-ttl 10 --format json"), MOCK_SERVER.base_url());
+    assert!(res.is_ok());
+    assert_eq!(
+        printer().as_string().trim(),
+        "{\"name\":\"b\",\"id\":\"Mgo\",\"info\":{\"ttl\":30,\"client-id\":\"worker-1\",\"ip\":\"192.0.2.1\"}}\n\
+         {\"name\":\"d\",\"id\":\"NAo\",\"info\":{\"ttl\":30,\"client-id\":\"worker-2\",\"ip\":\"192.0.2.2\"}}"
+    );
+    printer().clear();
+
+    mock.assert_hits(3);
+    mock.delete();
+}
+
+#[test]
+fn locks_inspect() {
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/locks/base64:aW5zcA");
+        then(t, &lock_info("aW5zcA", "D4lbVpdBE_U", "worker-1", "192.0.2.1", 12));
+    });
+
+    let res = test_main(&cli!("locks inspect insp"), MOCK_SERVER.base_url());
+    assert!(res.is_ok());
+    assert_eq!(
+        printer().as_string().trim(),
+        "LOCK-NAME: aW5zcA\n\
+         LOCK-ID: D4lbVpdBE_U\n\
+         CLIENT-ID: worker-1\n\
+         CLIENT-IP: 192.0.2.1\n\
+         TTL: 12"
+    );
+    printer().clear();
+
+    let res = test_main(&cli!("locks inspect insp --decode"), MOCK_SERVER.base_url());
+    assert!(res.is_ok());
+    assert!(printer().as_string().starts_with("LOCK-NAME: insp\n"));
+    printer().clear();
+
+    mock.assert_hits(2);
+    mock.delete();
+}
+
+#[test]
+fn locks_force_release() {
+    let mut info_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/locks/base64:b3JwaGFu");
+        then(t, &lock_info("b3JwaGFu", "D4lbVpdBE_U", "worker-1", "192.0.2.1", 12));
+    });
+    let mut release_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, DELETE, "/v1/locks/base64:b3JwaGFu").query_param("id", "D4lbVpdBE_U");
+        then(t, &json!({"status": 200u32, "title": "Ok"}));
+    });
+
+    let res = test_main(&cli!("locks force-release orphan"), MOCK_SERVER.base_url());
+    assert!(res.is_ok());
+    info_mock.assert_hits(1);
+    release_mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        "Successfully released the lock held by worker-1 (192.0.2.1) with 12 seconds remaining"
+    );
+    printer().clear();
+
+    info_mock.delete();
+    release_mock.delete();
+}
+
+#[test]
+fn locks_force_release_denied() {
+    let mut info_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/locks/base64:Z3VhcmRlZA");
+        then(t, &lock_info("Z3VhcmRlZA", "D4lbVpdBE_U", "worker-1", "192.0.2.1", 12));
+    });
+    let mut release_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, DELETE, "/v1/locks/base64:Z3VhcmRlZA");
+        t.status(403).json_body(json!({
+            "status": 403u32,
+            "title": "Forbidden",
+            "detail": "not allowed"
+        }));
+    });
+
+    let res = test_main(&cli!("locks force-release guarded"), MOCK_SERVER.base_url());
+    assert!(matches!(res.unwrap_err().kind(), CliErrorKind::Seaplane(_)));
+    release_mock.assert_hits(1);
+    assert_eq!(printer().as_string(), "");
+
+    info_mock.delete();
+    release_mock.delete();
+}
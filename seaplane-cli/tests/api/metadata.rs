@@ -1,4 +1,7 @@
+use std::io::Write;
+
 use httpmock::prelude::*;
+use seaplane::api::metadata::v1::Keyring;
 use seaplane_cli::printer::printer;
 use serde_json::json;
 
@@ -44,6 +47,44 @@ fn metadata_get() {
     mock.delete();
 }
 
+#[test]
+fn metadata_get_encrypted() {
+    let keys = "1:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+    let sealed = keys
+        .parse::<Keyring>()
+        .unwrap()
+        .encrypt_value("bar")
+        .unwrap();
+    let resp = json!({"key":"c2VjcmV0", "value": sealed});
+
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/config/base64:c2VjcmV0");
+        then(t, &resp);
+    });
+
+    let mut key_file = tempfile::NamedTempFile::new().unwrap();
+    write!(key_file, "{keys}").unwrap();
+    let argv = format!(
+        "seaplane --stateless --api-key abc123 metadata get secret --decode --key-file {}",
+        key_file.path().display()
+    );
+
+    let res = test_main(&seaplane_cli::test_run(argv.split(' ')).unwrap(), MOCK_SERVER.base_url());
+    assert!(res.is_ok());
+    mock.assert_hits(1);
+    assert_eq!(printer().as_string().trim(), "bar");
+    printer().clear();
+
+    // Without --decode the value is left encrypted
+    let res = test_main(&cli!("metadata get secret"), MOCK_SERVER.base_url());
+    assert!(res.is_ok());
+    mock.assert_hits(2);
+    assert_eq!(printer().as_string().trim(), sealed.to_string());
+    printer().clear();
+
+    mock.delete();
+}
+
 #[test]
 fn metadata_put() {
     let resp_json = json!({"status": 200_i32, "title": "Ok"});
@@ -69,6 +110,42 @@ fn metadata_put() {
     mock.delete();
 }
 
+#[test]
+fn metadata_put_invalid() {
+    let mock = MOCK_SERVER.mock(|w, t| {
+        when(w, PUT, "/v1/config/base64:Zm9vLw");
+        then(t, &json!({"status": 200_i32, "title": "Ok"}));
+    });
+
+    // Directories can't be set directly
+    let res = test_main(&cli!("metadata set foo/ bar"), MOCK_SERVER.base_url());
+    assert!(res.is_err());
+    mock.assert_hits(0);
+    printer().clear();
+}
+
+#[test]
+fn metadata_put_large() {
+    let mut mock = MOCK_SERVER.mock(|w, t| {
+        when(w, PUT, "/v1/config/base64:Zm9v");
+        then(t, &json!({"status": 200_i32, "title": "Ok"}));
+    });
+
+    // Unusually large values are only warned about, it's up to the API to reject them
+    let value = "a".repeat(70 * 1024);
+    let argv = format!("seaplane --stateless --api-key abc123 metadata set foo {value}");
+    let res = test_main(&seaplane_cli::test_run(argv.split(' ')).unwrap(), MOCK_SERVER.base_url());
+    assert!(res.is_ok());
+    mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        "warn: key 'foo' (3 bytes) or its value (71680 bytes) is unusually large and may be \
+        rejected by the Metadata API\nSuccess"
+    );
+    printer().clear();
+    mock.delete();
+}
+
 #[test]
 fn metadata_list_root() {
     let mut mock = MOCK_SERVER.mock(|w, t| {
@@ -195,3 +272,81 @@ fn metadata_delete() {
     assert_eq!(printer().as_string().trim(), "Removed Zm9v\n\nSuccessfully removed 1 item");
     printer().clear();
 }
+
+#[test]
+fn metadata_sync() {
+    let list_resp = json!({
+        "next_key": None::<String>,
+        "kvs": [
+            {"key": "Y2ZnL2EudG9tbA", "value": "b25l"},
+            {"key": "Y2ZnL29sZA", "value": "eA"}
+        ]
+    });
+    let resp_json = json!({"status": 200_i32, "title": "Ok"});
+
+    let mut list_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/config/base64:Y2Zn/");
+        then(t, &list_resp);
+    });
+    let mut put_mock = MOCK_SERVER.mock(|w, t| {
+        when(w, PUT, "/v1/config/base64:Y2ZnL3N1Yi9iLnRvbWw")
+            .header("content-type", "application/octet-stream")
+            .body("dHdv");
+        then(t, &resp_json);
+    });
+    let mut delete_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, DELETE, "/v1/config/base64:Y2ZnL29sZA");
+        then(t, &resp_json);
+    });
+
+    let local = tempfile::tempdir().unwrap();
+    std::fs::write(local.path().join("a.toml"), "one").unwrap();
+    std::fs::create_dir(local.path().join("sub")).unwrap();
+    std::fs::write(local.path().join("sub").join("b.toml"), "two").unwrap();
+    std::fs::write(local.path().join(".hidden"), "skipped").unwrap();
+    let sync = |args: &str| {
+        let argv = format!(
+            "seaplane --stateless --api-key abc123 metadata sync {} cfg {args}",
+            local.path().display()
+        );
+        test_main(
+            &seaplane_cli::test_run(argv.split(' ').filter(|s| !s.is_empty())).unwrap(),
+            MOCK_SERVER.base_url(),
+        )
+    };
+
+    // A dry run doesn't change anything
+    let res = sync("--delete --dry-run");
+    assert!(res.is_ok());
+    list_mock.assert_hits(1);
+    put_mock.assert_hits(0);
+    delete_mock.assert_hits(0);
+    assert_eq!(
+        printer().as_string().trim(),
+        "+ cfg/sub/b.toml\n- cfg/old\n\nDry run: 1 to create, 0 to update, 1 to delete, 1 unchanged"
+    );
+    printer().clear();
+
+    // Without --delete the old key is left alone
+    let res = sync("");
+    assert!(res.is_ok());
+    list_mock.assert_hits(2);
+    put_mock.assert_hits(1);
+    delete_mock.assert_hits(0);
+    assert_eq!(
+        printer().as_string().trim(),
+        "+ cfg/sub/b.toml\n\nSuccessfully synced: 1 created, 0 updated, 0 deleted, 1 unchanged"
+    );
+    printer().clear();
+
+    let res = sync("--delete");
+    assert!(res.is_ok());
+    list_mock.assert_hits(3);
+    put_mock.assert_hits(2);
+    delete_mock.assert_hits(1);
+    printer().clear();
+
+    list_mock.delete();
+    put_mock.delete();
+    delete_mock.delete();
+}
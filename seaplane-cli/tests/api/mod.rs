@@ -23,6 +23,7 @@ macro_rules! cli {
 
 mod account;
 mod formation;
+mod image;
 mod locks;
 mod metadata;
 mod restrict;
@@ -38,8 +39,13 @@ fn test_main(matches: &ArgMatches, url: String) -> Result<(), CliError> {
 }
 
 fn test_main_with_ctx(matches: &ArgMatches, mut ctx: Ctx) -> Result<(), CliError> {
+    test_main_with_ctx_mut(matches, &mut ctx)
+}
+
+/// Same as `test_main_with_ctx` but leaves the context to be inspected afterwards
+fn test_main_with_ctx_mut(matches: &ArgMatches, ctx: &mut Ctx) -> Result<(), CliError> {
     let s: Box<dyn CliCommand> = Box::new(Seaplane);
-    s.traverse_exec(&matches, &mut ctx)?;
+    s.traverse_exec(&matches, ctx)?;
     Ok(())
 }
 
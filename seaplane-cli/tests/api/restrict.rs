@@ -1,5 +1,8 @@
+use std::io::Write;
+
 use httpmock::prelude::*;
-use seaplane_cli::printer::printer;
+use seaplane::error::SeaplaneError;
+use seaplane_cli::{error::CliErrorKind, printer::printer};
 use serde_json::json;
 
 use super::{test_main, then, when_json, MOCK_SERVER};
@@ -250,3 +253,261 @@ fn restrict_delete() {
 
     mock.delete();
 }
+
+#[test]
+fn restrict_plan_apply() {
+    let details = |regions: &[&str]| {
+        json!({
+            "regions_allowed": regions,
+            "regions_denied": [],
+            "providers_allowed": [],
+            "providers_denied": []
+        })
+    };
+    let list_resp = json!({
+        "restrictions": [
+            {"api": "Config", "directory": "Zm9vL2Jhcg", "details": details(&["XE", "XN"]), "state": "Enforced"},
+            {"api": "Config", "directory": "Zm9vL2Jheg", "details": details(&["XN"]), "state": "Enforced"},
+            {"api": "Locks", "directory": "am9icw", "details": details(&["XU"]), "state": "Pending"},
+        ]
+    });
+    let resp_json = json!({"status": 200_i32, "title": "Ok"});
+
+    let mut list_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/restrict/");
+        then(t, &list_resp);
+    });
+    let mut update_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, PUT, "/v1/restrict/config/base64:Zm9vL2Jheg/")
+            .json_body_obj(&details(&["XE"]));
+        then(t, &resp_json);
+    });
+    let mut create_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, PUT, "/v1/restrict/config/base64:bmV3/").json_body_obj(&json!({
+            "regions_allowed": [],
+            "regions_denied": [],
+            "providers_allowed": [],
+            "providers_denied": ["AWS"]
+        }));
+        then(t, &resp_json);
+    });
+    let mut delete_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, DELETE, "/v1/restrict/locks/base64:am9icw/");
+        then(t, &resp_json);
+    });
+
+    let mut policy = tempfile::NamedTempFile::new().unwrap();
+    write!(
+        policy,
+        r#"
+[[restriction]]
+api = "config"
+directory = "foo/bar"
+regions-allowed = ["xn", "xe"]
+
+[[restriction]]
+api = "config"
+directory = "foo/baz"
+regions-allowed = ["xe"]
+
+[[restriction]]
+api = "config"
+directory = "new"
+providers-denied = ["aws"]
+"#
+    )
+    .unwrap();
+    let run = |cmd: &str| {
+        let argv = format!(
+            "seaplane --stateless --api-key abc123 restrict {cmd} {}",
+            policy.path().display()
+        );
+        test_main(&seaplane_cli::test_run(argv.split(' ')).unwrap(), MOCK_SERVER.base_url())
+    };
+
+    // Planning doesn't change anything
+    let res = run("plan");
+    assert!(res.is_ok());
+    list_mock.assert_hits(1);
+    update_mock.assert_hits(0);
+    create_mock.assert_hits(0);
+    delete_mock.assert_hits(0);
+    assert_eq!(
+        printer().as_string().trim(),
+        "~ Config foo/baz\n+ Config new\n- Locks jobs\n\n\
+         Plan: 1 to create, 1 to update, 1 to delete, 1 unchanged"
+    );
+    printer().clear();
+
+    let res = run("apply");
+    assert!(res.is_ok());
+    list_mock.assert_hits(2);
+    update_mock.assert_hits(1);
+    create_mock.assert_hits(1);
+    delete_mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        "~ Config foo/baz\n+ Config new\n- Locks jobs\n\n\
+         Successfully applied: 1 created, 1 updated, 1 deleted, 1 unchanged"
+    );
+    printer().clear();
+
+    list_mock.delete();
+    update_mock.delete();
+    create_mock.delete();
+    delete_mock.delete();
+}
+
+#[test]
+fn restrict_wait() {
+    let restriction = |state: &str| {
+        json!({
+            "api": "Config",
+            "directory": "d2FpdA",
+            "details": {
+                "regions_allowed": ["XE"],
+                "regions_denied": [],
+                "providers_allowed": [],
+                "providers_denied": []
+            },
+            "state": state
+        })
+    };
+    let resp_json = json!({"status": 200_i32, "title": "Ok"});
+
+    let mut set_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, PUT, "/v1/restrict/config/base64:d2FpdA/");
+        then(t, &resp_json);
+    });
+    let mut pending_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/restrict/config/base64:d2FpdA/");
+        then(t, &restriction("Pending"));
+    });
+
+    // Gives up once the timeout elapses
+    let res = test_main(&cli!("restrict wait config wait --timeout 0"), MOCK_SERVER.base_url());
+    assert_eq!(
+        res.unwrap_err().kind(),
+        &CliErrorKind::Seaplane(SeaplaneError::RestrictionWaitTimeout)
+    );
+    pending_mock.assert_hits(1);
+    assert!(printer().as_string().is_empty());
+
+    let res = test_main(
+        &cli!("restrict set config wait --region xe --wait --timeout 0"),
+        MOCK_SERVER.base_url(),
+    );
+    assert_eq!(
+        res.unwrap_err().kind(),
+        &CliErrorKind::Seaplane(SeaplaneError::RestrictionWaitTimeout)
+    );
+    set_mock.assert_hits(1);
+    pending_mock.assert_hits(2);
+    pending_mock.delete();
+
+    let mut enforced_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/restrict/config/base64:d2FpdA/");
+        then(t, &restriction("Enforced"));
+    });
+
+    let res = test_main(
+        &cli!("restrict set config wait --region xe --wait --decode"),
+        MOCK_SERVER.base_url(),
+    );
+    assert!(res.is_ok());
+    set_mock.assert_hits(2);
+    enforced_mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        "Set a restriction on directory wait in config API\nThe restriction is enforced"
+    );
+    printer().clear();
+
+    let res = test_main(&cli!("restrict wait config wait --format json"), MOCK_SERVER.base_url());
+    assert!(res.is_ok());
+    enforced_mock.assert_hits(2);
+    assert_eq!(printer().as_string().trim(), restriction("Enforced").to_string());
+    printer().clear();
+
+    set_mock.delete();
+    enforced_mock.delete();
+}
+
+#[test]
+fn restrict_explain_audit() {
+    let restriction = |directory: &str, providers_denied: &[&str]| {
+        json!({
+            "api": "Config",
+            "directory": directory,
+            "details": {
+                "regions_allowed": ["XE"],
+                "regions_denied": [],
+                "providers_allowed": [],
+                "providers_denied": providers_denied
+            },
+            "state": "Enforced"
+        })
+    };
+    let list_resp = json!({
+        "restrictions": [restriction("ZXU", &[]), restriction("ZXUvdXNlcnM", &["AWS"])]
+    });
+    let kvs_resp = json!({
+        "next_key": None::<String>,
+        "kvs": [
+            {"key": "ZXUvdXNlcnMvYWxpY2U", "value": "eA"},
+            {"key": "dG9w", "value": "eA"},
+            {"key": "dXMvbG9ncy94", "value": "eA"}
+        ]
+    });
+
+    let mut list_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/restrict/config/");
+        then(t, &list_resp);
+    });
+    let mut kvs_mock = MOCK_SERVER.mock(|w, t| {
+        when_json(w, GET, "/v1/config/");
+        then(t, &kvs_resp);
+    });
+
+    // The most specific restriction governs the key
+    let res =
+        test_main(&cli!("restrict explain config eu/users/alice --decode"), MOCK_SERVER.base_url());
+    assert!(res.is_ok());
+    list_mock.assert_hits(1);
+    assert_eq!(
+        printer().as_string().trim(),
+        "\
+eu/users/alice in config API is governed by the restriction on directory eu/users
+
+API     DIRECTORY  STATE     REGIONS ALLOWED  REGIONS DENIED  PROVIDERS ALLOWED  PROVIDERS DENIED
+Config  eu/users   Enforced  [XE]             []              []                 [AWS]"
+    );
+    printer().clear();
+
+    let res = test_main(
+        &cli!("restrict explain config dXMvbG9ncy94 --base64 --format json"),
+        MOCK_SERVER.base_url(),
+    );
+    assert!(res.is_ok());
+    list_mock.assert_hits(2);
+    assert_eq!(
+        printer().as_string().trim(),
+        json!({"api": "config", "key": "dXMvbG9ncy94", "restriction": null}).to_string()
+    );
+    printer().clear();
+
+    let res = test_main(&cli!("restrict audit --decode"), MOCK_SERVER.base_url());
+    assert!(res.is_ok());
+    list_mock.assert_hits(3);
+    kvs_mock.assert_hits(1);
+    assert_eq!(printer().as_string().trim(), "DIRECTORY\nus\nus/logs");
+    printer().clear();
+
+    let res = test_main(&cli!("restrict audit --format json"), MOCK_SERVER.base_url());
+    assert!(res.is_ok());
+    assert_eq!(printer().as_string().trim(), json!(["dXM", "dXMvbG9ncw"]).to_string());
+    printer().clear();
+
+    list_mock.delete();
+    kvs_mock.delete();
+}
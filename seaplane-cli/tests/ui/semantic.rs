@@ -563,6 +563,26 @@ fn seaplane_formation_land() {
     assert!(cli!("formation stop foo").is_ok());
 }
 
+#[test]
+fn seaplane_formation_wait() {
+    // requires a NAME|ID
+    assert!(cli!("formation wait").is_err());
+    assert!(cli!("formation wait foo").is_ok());
+    assert!(cli!("formation wait foo --interval 1").is_ok());
+    // checking without pause would flood the API
+    assert!(cli!("formation wait foo --interval 0").is_err());
+}
+
+#[test]
+fn seaplane_formation_status() {
+    assert!(cli!("formation status").is_ok());
+    assert!(cli!("formation status foo --watch --interval 1").is_ok());
+    // --interval requires --watch
+    assert!(cli!("formation status foo --interval 1").is_err());
+    // checking without pause would flood the API
+    assert!(cli!("formation status foo --watch --interval 0").is_err());
+}
+
 #[test]
 fn seaplane_md() {
     // requires a subcmd
@@ -630,6 +650,11 @@ fn seaplane_md_set() {
 
     // aliases
     assert!(cli!("metadata put foo bar").is_ok());
+
+    // encryption
+    assert!(cli!("metadata set foo bar --encrypt").is_ok());
+    assert!(cli!("metadata set foo bar -E --key-file keys.txt").is_ok());
+    assert!(cli!("metadata set foo bar --key-file").is_err());
 }
 
 #[test]
@@ -653,6 +678,21 @@ fn seaplane_md_list() {
     assert!(cli!("metadata list --only-keys --only-values").is_err());
 }
 
+#[test]
+fn seaplane_md_sync() {
+    // requires a LOCAL_DIR and REMOTE_DIR
+    assert!(cli!("metadata sync").is_err());
+    assert!(cli!("metadata sync ./config").is_err());
+    // provide LOCAL_DIR and REMOTE_DIR
+    assert!(cli!("metadata sync ./config config").is_ok());
+    // multiples are not allowed
+    assert!(cli!("metadata sync ./config config foo").is_err());
+
+    assert!(cli!("metadata sync ./config config --delete --dry-run").is_ok());
+    assert!(cli!("metadata sync ./config config --diff").is_ok());
+    assert!(cli!("metadata sync ./config config -E --key-file keys.txt").is_ok());
+}
+
 #[test]
 fn seaplane_locks() {
     // requires a subcmd
@@ -691,10 +731,35 @@ fn seaplane_locks_list() {
     assert!(cli!("locks list foo bar,baz").is_err());
     assert!(cli!("locks list foo,bar baz").is_err());
 
+    // filters
+    assert!(cli!("locks list --client-id foo --ip 192.0.2.1").is_ok());
+    assert!(cli!("locks list foo/ --min-ttl 5 --max-ttl 10").is_ok());
+    assert!(cli!("locks list --min-ttl soon").is_err());
+
     // aliases
     assert!(cli!("locks ls foo").is_ok());
 }
 
+#[test]
+fn seaplane_locks_inspect() {
+    // requires a LOCK_NAME
+    assert!(cli!("locks inspect").is_err());
+    assert!(cli!("locks inspect foo").is_ok());
+    assert!(cli!("locks inspect foo --decode").is_ok());
+    // can not have multiples
+    assert!(cli!("locks inspect foo bar").is_err());
+}
+
+#[test]
+fn seaplane_locks_force_release() {
+    // requires a LOCK_NAME
+    assert!(cli!("locks force-release").is_err());
+    assert!(cli!("locks force-release foo").is_ok());
+    assert!(cli!("locks force-release Zm9v --base64").is_ok());
+    // can not have multiples
+    assert!(cli!("locks force-release foo bar").is_err());
+}
+
 #[test]
 fn seaplane_locks_renew() {
     // requires a LOCK_NAME and LOCK_ID and TTL
@@ -737,11 +802,64 @@ fn seaplane_locks_acquire() {
     assert!(cli!("locks acquire foo --client-id bar, baz --ttl 60").is_err());
     assert!(cli!("locks acquire foo --client-id bar --ttl 60 30").is_err());
     assert!(cli!("locks acquire foo --client-id bar --ttl 60, 30").is_err());
+    // wait, optionally with a timeout
+    assert!(cli!("locks acquire foo --client-id bar --ttl 60 --wait").is_ok());
+    assert!(cli!("locks acquire foo --client-id bar --ttl 60 --wait --timeout 30").is_ok());
+    assert!(cli!("locks acquire foo --client-id bar --ttl 60 --wait --timeout soon").is_err());
+    // timeout requires wait
+    assert!(cli!("locks acquire foo --client-id bar --ttl 60 --timeout 30").is_err());
 
     // aliases
     assert!(cli!("locks acq foo --client-id bar --ttl 60").is_ok());
 }
 
+#[test]
+fn seaplane_locks_hold() {
+    // requires a LOCK_NAME, CLIENT_ID, TTL and COMMAND
+    assert!(cli!("locks hold").is_err());
+    assert!(cli!("locks hold foo --client-id bar --ttl 60").is_err());
+    assert!(cli!("locks hold foo --ttl 60 -- true").is_err());
+    assert!(cli!("locks hold foo --client-id bar -- true").is_err());
+    // provide LOCK_NAME, CLIENT_ID, TTL and COMMAND
+    assert!(cli!("locks hold foo --client-id bar --ttl 60 -- true").is_ok());
+    // the COMMAND may have arguments, including flags
+    assert!(cli!("locks hold foo --client-id bar --ttl 60 -- ls -la /tmp").is_ok());
+    assert!(cli!("locks hold foo -L bar -T 60 -- echo a,b").is_ok());
+    // the COMMAND must come after --
+    assert!(cli!("locks hold foo --client-id bar --ttl 60 true").is_err());
+}
+
+#[test]
+fn seaplane_locks_elect() {
+    // requires a LOCK_NAME, CLIENT_ID and TTL
+    assert!(cli!("locks elect").is_err());
+    assert!(cli!("locks elect foo --client-id bar").is_err());
+    assert!(cli!("locks elect foo --ttl 60").is_err());
+    // provide LOCK_NAME, CLIENT_ID and TTL
+    assert!(cli!("locks elect foo --client-id bar --ttl 60").is_ok());
+    assert!(cli!("locks elect foo --client-id bar --ttl 60 --exit-on-loss").is_ok());
+    // can not have multiples
+    assert!(cli!("locks elect foo baz --client-id bar --ttl 60").is_err());
+}
+
+#[test]
+fn seaplane_locks_semaphore() {
+    // requires a subcmd
+    assert!(cli!("locks semaphore").is_err());
+    // requires a LOCK_NAME, SLOTS, CLIENT_ID and TTL
+    assert!(cli!("locks semaphore acquire foo --client-id bar --ttl 60").is_err());
+    assert!(cli!("locks semaphore acquire foo --slots 3 --ttl 60").is_err());
+    assert!(cli!("locks semaphore acquire --slots 3 --client-id bar --ttl 60").is_err());
+    // provide LOCK_NAME, SLOTS, CLIENT_ID and TTL
+    assert!(cli!("locks semaphore acquire foo --slots 3 --client-id bar --ttl 60").is_ok());
+    // must have at least one slot
+    assert!(cli!("locks semaphore acquire foo --slots 0 --client-id bar --ttl 60").is_err());
+    assert!(cli!("locks semaphore acquire foo --slots x --client-id bar --ttl 60").is_err());
+
+    // aliases
+    assert!(cli!("locks sem acq foo --slots 3 --client-id bar --ttl 60").is_ok());
+}
+
 #[test]
 fn seaplane_restrict() {
     // requires a subcmd
@@ -759,6 +877,9 @@ fn seaplane_restrict_get() {
 
     // provide API and directory
     assert!(cli!("restrict get config foo").is_ok());
+    assert!(cli!("restrict get LOCKS foo").is_ok());
+    // unknown APIs are rejected
+    assert!(cli!("restrict get queues foo").is_err());
 
     // three is a crowd
     assert!(cli!("restrict get foo bar baz").is_err());
@@ -769,6 +890,7 @@ fn seaplane_restrict_list() {
     // requires no args or just API
     assert!(cli!("restrict list").is_ok());
     assert!(cli!("restrict list config").is_ok());
+    assert!(cli!("restrict list queues").is_err());
     assert!(cli!("restrict list config foo/bar").is_err());
 
     assert!(cli!("restrict list config -D").is_ok());
@@ -822,4 +944,73 @@ fn seaplane_restrict_set() {
 
     // default is all providers and regions allowed
     assert!(cli!("restrict set config foo").is_ok());
+
+    // waiting for enforcement, the timeout requires --wait
+    assert!(cli!("restrict set config foo --region xe --wait").is_ok());
+    assert!(cli!("restrict set config foo --region xe --wait --timeout 60").is_ok());
+    assert!(cli!("restrict set config foo --region xe --timeout 60").is_err());
+}
+
+#[test]
+fn seaplane_restrict_plan_apply() {
+    // requires a policy file
+    assert!(cli!("restrict plan").is_err());
+    assert!(cli!("restrict apply").is_err());
+    assert!(cli!("restrict plan policy.toml").is_ok());
+    assert!(cli!("restrict diff policy.toml").is_ok());
+    assert!(cli!("restrict apply policy.toml --format json").is_ok());
+
+    // only one file
+    assert!(cli!("restrict apply policy.toml other.toml").is_err());
+}
+
+#[test]
+fn seaplane_restrict_wait() {
+    // requires API and directory
+    assert!(cli!("restrict wait").is_err());
+    assert!(cli!("restrict wait config").is_err());
+
+    assert!(cli!("restrict wait config foo").is_ok());
+    assert!(cli!("restrict wait config foo --timeout 60 -D").is_ok());
+    assert!(cli!("restrict wait config foo --timeout soon").is_err());
+}
+
+#[test]
+fn seaplane_restrict_explain() {
+    // requires API and key
+    assert!(cli!("restrict explain").is_err());
+    assert!(cli!("restrict explain config").is_err());
+
+    assert!(cli!("restrict explain config foo/bar").is_ok());
+    assert!(cli!("restrict explain locks Zm9v --base64 -D").is_ok());
+    assert!(cli!("restrict explain config foo bar").is_err());
+}
+
+#[test]
+fn seaplane_restrict_audit() {
+    assert!(cli!("restrict audit").is_ok());
+    assert!(cli!("restrict audit --decode --no-header").is_ok());
+    assert!(cli!("restrict audit config").is_err());
+}
+
+#[test]
+fn seaplane_image() {
+    // requires a subcommand
+    assert!(cli!("image").is_err());
+    assert!(cli!("img resolve seaplane/nginx").is_ok());
+
+    // requires an image
+    assert!(cli!("image resolve").is_err());
+    assert!(cli!("image tags").is_err());
+    assert!(cli!("image manifest").is_err());
+    assert!(cli!("image resolve localhost:5000/nginx:latest --format json").is_ok());
+    assert!(cli!("image digest seaplane/nginx").is_ok());
+    assert!(cli!("image tags seaplane/nginx --no-header").is_ok());
+    assert!(cli!("image inspect seaplane/nginx@sha256:7cc4b5aefd1d0cadf8d97d4350462ba51c694ebca145b08d7d41b41acc8db5aa").is_ok());
+
+    // requires a flight
+    assert!(cli!("image pin").is_err());
+    assert!(cli!("image pin foo").is_ok());
+    assert!(cli!("image pin foo --all --update").is_ok());
+    assert!(cli!("image pin foo bar").is_err());
 }
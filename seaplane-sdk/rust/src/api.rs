@@ -4,6 +4,7 @@ pub mod compute;
 pub mod identity;
 pub mod locks;
 pub mod metadata;
+pub mod registry;
 pub mod restrict;
 pub mod shared;
 
@@ -7,25 +7,25 @@ pub use containers::*;
 pub use endpoint::*;
 pub use formation::*;
 pub use image_ref::*;
-use serde::{
-    de::{self, Deserialize, Deserializer},
-    Serialize,
-};
-use strum::{Display, EnumString, EnumVariantNames};
+use serde::de::{self, Deserialize, Deserializer};
+use strum::{AsRefStr, EnumString, EnumVariantNames};
 
-use crate::api::shared::v1::impl_deser_from_str;
+use crate::api::shared::v1::{impl_deser_from_str, impl_unknown_variant, UnknownValue};
 
 /// The processor architecture a [`Flight`] wants to run on
-#[derive(
-    Debug, Serialize, Hash, Eq, PartialEq, Copy, Clone, Display, EnumString, EnumVariantNames,
-)]
+#[derive(Debug, Hash, Eq, PartialEq, Clone, AsRefStr, EnumString, EnumVariantNames)]
 #[strum(ascii_case_insensitive)]
+#[non_exhaustive]
 pub enum Architecture {
     AMD64,
     ARM64,
+    /// A value not known to this version of the SDK, kept as it was received
+    #[strum(default)]
+    Unknown(UnknownValue),
 }
 
 impl_deser_from_str!(Architecture);
+impl_unknown_variant!(Architecture);
 
 #[cfg(test)]
 mod test_arch {
@@ -40,4 +40,11 @@ mod test_arch {
         let arch: Architecture = serde_json::from_str("\"AMD64\"").unwrap();
         assert_eq!(arch, Architecture::AMD64);
     }
+
+    #[test]
+    fn architecture_unknown_round_trips() {
+        let arch: Architecture = serde_json::from_str("\"riscv64\"").unwrap();
+        assert_eq!(arch, Architecture::Unknown("riscv64".into()));
+        assert_eq!(serde_json::to_string(&arch).unwrap(), "\"riscv64\"");
+    }
 }
@@ -1,17 +1,30 @@
 use chrono::{offset::Utc, DateTime};
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{self, Deserializer},
+    Deserialize, Serialize,
+};
+use strum::{AsRefStr, EnumString};
 use uuid::Uuid;
 
-use crate::api::shared::v1::{Provider, Region};
+use crate::api::shared::v1::{
+    impl_deser_from_str, impl_unknown_variant, Provider, Region, UnknownValue,
+};
 
-#[derive(Debug, Serialize, Deserialize, Copy, Clone, Eq, PartialEq)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, Eq, PartialEq, AsRefStr, EnumString)]
+#[strum(serialize_all = "lowercase")]
+#[non_exhaustive]
 pub enum ContainerStatus {
     Started,
     Stopped,
     Running,
+    /// A value not known to this version of the SDK, kept as it was received
+    #[strum(default)]
+    Unknown(UnknownValue),
 }
 
+impl_deser_from_str!(ContainerStatus);
+impl_unknown_variant!(ContainerStatus);
+
 /// Information about a particular Container Host
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ContainerHostInfo {
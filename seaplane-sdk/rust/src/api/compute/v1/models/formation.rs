@@ -43,9 +43,7 @@ impl FormationConfigurationBuilder {
     /// "close" to each other (for some version of close including but not limited to latency).
     ///
     /// **NOTE:** This method can be called multiple times. All values will be utilized.
-    #[cfg_attr(feature = "unstable", must_use)]
-    #[cfg(feature = "unstable")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    #[must_use]
     pub fn add_affinity<S: Into<String>>(mut self, name: S) -> Self {
         self.affinity.push(name.into());
         self
@@ -61,9 +59,7 @@ impl FormationConfigurationBuilder {
     /// both formations opt in to that connection by adding each other to their connection mapping.
     ///
     /// **NOTE:** This method can be called multiple times. All values will be utilized.
-    #[cfg_attr(feature = "unstable", must_use)]
-    #[cfg(feature = "unstable")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    #[must_use]
     pub fn add_connection<S: Into<String>>(mut self, name: S) -> Self {
         self.connections.push(name.into());
         self
@@ -124,9 +120,7 @@ impl FormationConfigurationBuilder {
     /// rejected.
     ///
     /// **NOTE:** This method can be called multiple times. All values will be utilized.
-    #[cfg_attr(feature = "unstable", must_use)]
-    #[cfg(feature = "unstable")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+    #[must_use]
     pub fn add_formation_endpoint(mut self, key: EndpointKey, value: EndpointValue) -> Self {
         self.formation_endpoints.insert(key, value);
         self
@@ -286,8 +280,6 @@ impl FormationConfiguration {
     ///
     /// This is a hint to the scheduler to place containers that run in each of these formations
     /// "close" to each other (for some version of close including but not limited to latency).
-    #[cfg(feature = "unstable")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
     pub fn affinities(&self) -> &[String] { &self.affinity }
 
     /// The names of another Formation that this Formation has an affinity for.
@@ -308,8 +300,6 @@ impl FormationConfiguration {
     /// The names of another Formation that this Formation is connected to. Two Formations can
     /// communicate over their `FormationConfigurationBuilder::formation_endpoints` if and only if
     /// both formations opt in to that connection by adding each other to their connection mapping.
-    #[cfg(feature = "unstable")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
     pub fn connections(&self) -> &[String] { &self.connections }
 
     /// Add a [`Flight`] to the makeup of this Formation Configuration.
@@ -347,6 +337,11 @@ impl FormationConfiguration {
         self.public_endpoints.iter()
     }
 
+    /// Removes the publicly exposed endpoint with the given key, returning its value if it existed.
+    pub fn remove_public_endpoint(&mut self, key: &EndpointKey) -> Option<EndpointValue> {
+        self.public_endpoints.remove(key)
+    }
+
     /// Adds an entry to the `formation_endpoints` map, which describes the privately exposed
     /// endpoints of this formation. These private endpoints are those that this formation exposes
     /// to other formations listed in is's `connection` mapping
@@ -365,14 +360,15 @@ impl FormationConfiguration {
     /// endpoints of this formation. These private endpoints are those that this formation exposes
     /// to other formations listed in is's `connection` mapping
     /// ([`FormationConfigurationBuilder::add_connection`]).
+    pub fn formation_endpoints(&self) -> impl Iterator<Item = (&EndpointKey, &EndpointValue)> {
+        self.formation_endpoints.iter()
+    }
+
+    /// Removes the Formation Endpoint with the given key, returning its value if it existed.
     #[cfg(feature = "unstable")]
     #[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
-    pub fn formation_endpoints(
-        &self,
-        _key: EndpointKey,
-        _value: EndpointValue,
-    ) -> impl Iterator<Item = (&EndpointKey, &EndpointValue)> {
-        self.formation_endpoints.iter()
+    pub fn remove_formation_endpoint(&mut self, key: &EndpointKey) -> Option<EndpointValue> {
+        self.formation_endpoints.remove(key)
     }
 
     /// Adds an entry to the `flight_endpoints` map, which describes the endpoints which *this
@@ -397,6 +393,11 @@ impl FormationConfiguration {
         self.flight_endpoints.iter()
     }
 
+    /// Removes the Flight Endpoint with the given key, returning its value if it existed.
+    pub fn remove_flight_endpoint(&mut self, key: &EndpointKey) -> Option<EndpointValue> {
+        self.flight_endpoints.remove(key)
+    }
+
     /// Add a [`Provider`] which the scheduler is allowed to schedule [`Flight`]s of this formation
     /// to run on. By default all [`Provider`]s are allowed. Adding an entry here effectively
     /// restricts the [`Flight`]s of this Formation to only the listed [`Provider`]s.
@@ -650,6 +651,9 @@ impl Flight {
     #[inline]
     pub fn image(&self) -> &ImageReference { &self.image }
 
+    /// Set the container image reference this [`Flight`] uses
+    pub fn set_image(&mut self, image_ref: ImageReference) { self.image = image_ref; }
+
     /// Returns the [`Architecture`]s this [`Flight`] can be run on.
     #[inline]
     pub fn architecture(&self) -> impl Iterator<Item = &Architecture> { self.architecture.iter() }
@@ -657,6 +661,11 @@ impl Flight {
     /// Add an [`Architecture`]s this [`Flight`] can be run on.
     pub fn add_architecture(&mut self, arch: Architecture) { self.architecture.insert(arch); }
 
+    /// Replaces the [`Architecture`]s this [`Flight`] can be run on.
+    pub fn set_architecture(&mut self, archs: Vec<Architecture>) {
+        self.architecture = archs.into_iter().collect();
+    }
+
     /// Returns the minimum number of instances this [`Flight`] should ever have running.
     pub fn minimum(&self) -> u64 { self.minimum }
 
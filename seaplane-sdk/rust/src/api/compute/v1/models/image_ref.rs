@@ -4,7 +4,7 @@ use nom::{
     bytes::complete::{tag, take_till, take_till1, take_while1},
     character::complete::{alphanumeric1, anychar},
     combinator::{opt, rest},
-    error::{context, Error, VerboseError},
+    error::{context, Error, ErrorKind, ParseError, VerboseError},
     sequence::{preceded, separated_pair},
     AsChar, IResult,
 };
@@ -184,8 +184,18 @@ pub fn parse_reference(input: &str) -> Res<&str, ImageReference> {
     ))
 }
 
+// The name ends at the first `@`, or at the last `:` when no `/` follows it. A `:` which is
+// followed by a `/` separates the domain from a port number instead (i.e. `localhost:5000/foo`)
 fn parse_name(input: &str) -> Res<&str, &str> {
-    context("parse_name", take_till1(|c| (c == ':' || c == '@')))(input)
+    let end = input.find('@').unwrap_or(input.len());
+    let end = match input[..end].rfind(':') {
+        Some(i) if !input[i..end].contains('/') => i,
+        _ => end,
+    };
+    if end == 0 {
+        return Err(nom::Err::Error(VerboseError::from_error_kind(input, ErrorKind::TakeTill1)));
+    }
+    Ok((&input[end..], &input[..end]))
 }
 
 // Split name into domain and path. Domain is the first component delimited by a
@@ -320,6 +330,29 @@ mod tests {
             parse_name("registry.hub.docker.com/seaplane/busybox@sha256:XXX"),
             Ok(("@sha256:XXX", "registry.hub.docker.com/seaplane/busybox"))
         );
+        assert_eq!(
+            parse_name("localhost:5000/busybox:latest"),
+            Ok((":latest", "localhost:5000/busybox"))
+        );
+        assert_eq!(parse_name("localhost:5000/busybox"), Ok(("", "localhost:5000/busybox")));
+        assert!(parse_name(":latest").is_err());
+    }
+
+    #[test]
+    fn domain_with_port() {
+        assert_eq!(
+            parse("localhost:5000/seaplane/busybox:latest"),
+            Ok(ImageReference {
+                domain: "localhost:5000".to_string(),
+                path: "seaplane/busybox".to_string(),
+                tag: Some("latest".to_string()),
+                digest: None
+            })
+        );
+        assert_eq!(
+            parse("127.0.0.1:5000/busybox").map(|r| r.to_string()),
+            Ok("127.0.0.1:5000/busybox".to_string())
+        );
     }
 
     #[test]
@@ -1,13 +1,24 @@
 //! The `/locks` endpoint APIs which allows working with [`HeldLock`]s
+mod election;
+mod guard;
 mod models;
+mod semaphore;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+pub use election::*;
+pub use guard::*;
 pub use models::*;
 use reqwest::Url;
+pub use semaphore::*;
 use serde::Deserialize;
 
 use crate::{
     api::{
-        locks::LOCKS_API_URL, map_api_error, shared::v1::RangeQueryContext, ApiRequest,
-        RequestBuilder,
+        locks::LOCKS_API_URL, map_api_error, shared::v1::RangeQueryContext, with_fresh_token,
+        ApiErrorKind, ApiRequest, RequestBuilder,
     },
     base64::add_base64_path_segment,
     error::{Result, SeaplaneError},
@@ -15,6 +26,11 @@ use crate::{
 
 static LOCKS_API_BASE_PATH: &str = "v1/locks/";
 
+/// The delay before the first retry of [`LocksRequest::acquire_blocking`]
+const ACQUIRE_RETRY_INITIAL_DELAY: Duration = Duration::from_millis(100);
+/// The maximum delay between retries of [`LocksRequest::acquire_blocking`]
+const ACQUIRE_RETRY_MAX_DELAY: Duration = Duration::from_secs(5);
+
 /// A builder struct for creating a [`LocksRequest`] which will then be used for making a
 /// request against the `/locks` APIs
 #[derive(Debug)]
@@ -110,6 +126,18 @@ impl LocksRequest {
     /// Create a new request builder
     pub fn builder() -> LocksRequestBuilder { LocksRequestBuilder::new() }
 
+    // Internal method creating a new request with the same token and endpoint, but a different
+    // target
+    fn retarget(&self, target: RequestTarget) -> LocksRequest {
+        ApiRequest {
+            target: Some(target),
+            token: self.request.token.clone(),
+            client: self.request.client.clone(),
+            endpoint_url: self.request.endpoint_url.clone(),
+        }
+        .into()
+    }
+
     // Internal method creating the URL for all single lock endpoints
     fn single_lock_url(&self) -> Result<Url> {
         match &self.request.target {
@@ -213,6 +241,92 @@ impl LocksRequest {
             .map_err(Into::into)
     }
 
+    /// Acquires the lock targeted by the requests built with `new_request` (which must be
+    /// `SingleLock` requests) with the given TTL, waiting until it is free if it's currently held
+    /// by someone else.
+    ///
+    /// Retries are made with an exponential backoff (plus some random jitter so that multiple
+    /// waiting clients don't retry in lock step) for as long as the lock is held. If a `timeout`
+    /// is given and the lock could not be acquired before it elapses,
+    /// [`SeaplaneError::LockWaitTimeout`] is returned.
+    ///
+    /// Waiting can outlast any single access token, so `new_request` is called once up front, and
+    /// again whenever the API rejects the access token of the current request. It should build
+    /// each request with a fresh token.
+    ///
+    /// `on_held` is called with information about the current holder of the lock each time a new
+    /// holder is seen while waiting.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use seaplane::api::locks::v1::{LocksRequest, LocksRequestBuilder};
+    ///
+    /// let resp = LocksRequest::acquire_blocking(
+    ///     || {
+    ///         LocksRequestBuilder::new()
+    ///             .token("abc123_token")
+    ///             .encoded_lock_name("bW9ieQo")
+    ///             .build()
+    ///     },
+    ///     15,
+    ///     "test-client",
+    ///     Some(Duration::from_secs(60)),
+    ///     |info| println!("waiting on {}", info.info.client_id),
+    /// )
+    /// .unwrap();
+    /// dbg!(resp);
+    /// ```
+    pub fn acquire_blocking<R, F>(
+        mut new_request: R,
+        ttl: u32,
+        client_id: &str,
+        timeout: Option<Duration>,
+        mut on_held: F,
+    ) -> Result<HeldLock>
+    where
+        R: FnMut() -> Result<LocksRequest>,
+        F: FnMut(&LockInfo),
+    {
+        let deadline = timeout.map(|t| Instant::now() + t);
+        let mut delay = ACQUIRE_RETRY_INITIAL_DELAY;
+        let mut holder = None;
+        let mut req = new_request()?;
+
+        loop {
+            match with_fresh_token(&mut req, &mut new_request, |req| req.acquire(ttl, client_id)) {
+                Err(SeaplaneError::ApiResponse(ae)) if ae.kind == ApiErrorKind::Conflict => (),
+                res => return res,
+            }
+
+            match with_fresh_token(&mut req, &mut new_request, LocksRequest::get_lock_info) {
+                Ok(info) => {
+                    if holder.as_ref() != Some(&info.id) {
+                        on_held(&info);
+                        holder = Some(info.id);
+                    }
+                }
+                // The lock was released in the meantime
+                Err(SeaplaneError::ApiResponse(ae)) if ae.kind == ApiErrorKind::NotFound => (),
+                Err(e) => return Err(e),
+            }
+
+            // Sleep somewhere between half and all of the current delay
+            let half = delay.as_millis() as u64 / 2;
+            let mut sleep = Duration::from_millis(half + fastrand::u64(0..=half));
+            if let Some(deadline) = deadline {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    return Err(SeaplaneError::LockWaitTimeout);
+                }
+                sleep = sleep.min(remaining);
+            }
+            thread::sleep(sleep);
+            delay = (delay * 2).min(ACQUIRE_RETRY_MAX_DELAY);
+        }
+    }
+
     /// Attempts to release the given lock.
     ///
     /// **NOTE:** This endpoints requires the `RequestTarget` be a `HeldLock`
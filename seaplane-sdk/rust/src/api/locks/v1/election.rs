@@ -0,0 +1,192 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::{
+    api::{
+        locks::v1::{HeldLock, LocksRequest, RequestTarget},
+        with_fresh_token, ApiErrorKind,
+    },
+    error::{Result, SeaplaneError},
+};
+
+/// A change in leadership observed by a [`LeaderElection`]
+#[derive(Debug, PartialEq)]
+pub enum LeadershipEvent {
+    /// This client became the leader by acquiring the given lock
+    Elected(HeldLock),
+    /// This client was the leader, but the lock could not be renewed. The client goes back to
+    /// campaigning for leadership.
+    Lost(SeaplaneError),
+    /// Trying to acquire the lock failed for a reason other than another client holding it, such
+    /// as a network failure or the request being rejected. The client keeps campaigning.
+    Failed(SeaplaneError),
+}
+
+/// Campaigns for leadership among any number of clients by acquiring a named lock.
+///
+/// Whichever client holds the lock is the leader. While this client is the leader the lock is
+/// renewed in the background every third of its TTL, and while it is not it tries to acquire the
+/// lock on the same interval. If the leader fails to renew the lock it loses leadership and goes
+/// back to campaigning after the interval.
+///
+/// Changes in leadership can be polled with [`LeaderElection::is_leader`], or received as
+/// [`LeadershipEvent`]s from [`LeaderElection::events`]. Stepping down, either by calling
+/// [`LeaderElection::step_down`] or dropping the `LeaderElection`, releases the lock if held so
+/// another client can take over right away.
+///
+/// # Examples
+/// ```no_run
+/// use seaplane::api::locks::v1::{LeaderElection, LeadershipEvent, LocksRequestBuilder};
+///
+/// let election = LeaderElection::campaign(
+///     || {
+///         LocksRequestBuilder::new()
+///             .token("abc123_token")
+///             .encoded_lock_name("bW9ieQo")
+///             .build()
+///     },
+///     15,
+///     "replica-1",
+/// )
+/// .unwrap();
+/// for event in election.events() {
+///     match event {
+///         LeadershipEvent::Elected(lock) => {
+///             println!("leader with sequencer {}", lock.sequencer())
+///         }
+///         LeadershipEvent::Lost(e) => println!("no longer the leader: {e}"),
+///         LeadershipEvent::Failed(e) => println!("failed to campaign: {e}"),
+///     }
+/// }
+/// ```
+pub struct LeaderElection {
+    leader: Arc<AtomicBool>,
+    events: mpsc::Receiver<LeadershipEvent>,
+    stop_tx: Option<mpsc::Sender<()>>,
+    campaigner: Option<JoinHandle<Result<()>>>,
+}
+
+impl fmt::Debug for LeaderElection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LeaderElection")
+            .field("leader", &self.is_leader())
+            .finish()
+    }
+}
+
+impl LeaderElection {
+    /// Starts campaigning for leadership by acquiring the lock targeted by the requests built with
+    /// `new_request` (which must be `SingleLock` requests) with the given `ttl` in seconds.
+    ///
+    /// `new_request` is called once up front, and again whenever the API rejects the access token
+    /// of the current request, so it should build each request with a fresh token.
+    ///
+    /// Campaigning happens in the background, so this returns right away.
+    pub fn campaign<F>(mut new_request: F, ttl: u32, client_id: &str) -> Result<Self>
+    where
+        F: FnMut() -> Result<LocksRequest> + Send + 'static,
+    {
+        // Every request is retargeted at the lock itself
+        let mut new_request = move || single_lock(new_request()?);
+        let mut req = new_request()?;
+        let client_id = client_id.to_owned();
+        let interval = Duration::from_millis(u64::from(ttl) * 1000 / 3);
+        let leader = Arc::new(AtomicBool::new(false));
+        let (events_tx, events) = mpsc::channel();
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+
+        let campaigner = {
+            let leader = Arc::clone(&leader);
+            thread::spawn(move || {
+                let mut held: Option<HeldLock> = None;
+                loop {
+                    match held.clone() {
+                        None => {
+                            match with_fresh_token(&mut req, &mut new_request, |req| {
+                                req.acquire(ttl, &client_id)
+                            }) {
+                                Ok(lock) => {
+                                    leader.store(true, Ordering::SeqCst);
+                                    let _ = events_tx.send(LeadershipEvent::Elected(lock.clone()));
+                                    held = Some(lock);
+                                }
+                                // Someone else is the leader
+                                Err(SeaplaneError::ApiResponse(ae))
+                                    if ae.kind == ApiErrorKind::Conflict => {}
+                                Err(e) => {
+                                    let _ = events_tx.send(LeadershipEvent::Failed(e));
+                                }
+                            }
+                        }
+                        Some(lock) => {
+                            if let Err(e) = with_fresh_token(&mut req, &mut new_request, |req| {
+                                req.retarget(RequestTarget::HeldLock(lock.clone()))
+                                    .renew(ttl)
+                            }) {
+                                leader.store(false, Ordering::SeqCst);
+                                held = None;
+                                let _ = events_tx.send(LeadershipEvent::Lost(e));
+                            }
+                        }
+                    }
+
+                    // Any message, or the sender being dropped means we should step down
+                    if let Err(mpsc::RecvTimeoutError::Timeout) = stop_rx.recv_timeout(interval) {
+                        continue;
+                    }
+                    leader.store(false, Ordering::SeqCst);
+                    return match held {
+                        Some(lock) => with_fresh_token(&mut req, &mut new_request, |req| {
+                            req.retarget(RequestTarget::HeldLock(lock.clone()))
+                                .release()
+                        }),
+                        None => Ok(()),
+                    };
+                }
+            })
+        };
+
+        Ok(Self { leader, events, stop_tx: Some(stop_tx), campaigner: Some(campaigner) })
+    }
+
+    /// Returns `true` if this client is currently the leader
+    pub fn is_leader(&self) -> bool { self.leader.load(Ordering::SeqCst) }
+
+    /// The receiving end of a channel of leadership changes
+    pub fn events(&self) -> &mpsc::Receiver<LeadershipEvent> { &self.events }
+
+    /// Stops campaigning, releasing the lock if this client is the leader. Returns any error from
+    /// releasing the lock.
+    ///
+    /// Dropping the `LeaderElection` does the same, but ignores any errors.
+    pub fn step_down(mut self) -> Result<()> { self.stop() }
+
+    // Stops the campaign thread, returning the result of releasing the lock. Does nothing if
+    // already stopped.
+    fn stop(&mut self) -> Result<()> {
+        let campaigner = match self.campaigner.take() {
+            Some(campaigner) => campaigner,
+            None => return Ok(()),
+        };
+        if let Some(stop_tx) = self.stop_tx.take() {
+            let _ = stop_tx.send(());
+        }
+        campaigner.join().unwrap_or(Ok(()))
+    }
+}
+
+impl Drop for LeaderElection {
+    fn drop(&mut self) { let _ = self.stop(); }
+}
+
+// Retargets `req` at the single lock it names
+fn single_lock(req: LocksRequest) -> Result<LocksRequest> {
+    Ok(req.retarget(RequestTarget::SingleLock(req.lock_name()?)))
+}
@@ -0,0 +1,211 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::{
+    api::{
+        locks::v1::{HeldLock, LocksRequest, RequestTarget},
+        with_fresh_token,
+    },
+    error::{Result, SeaplaneError},
+};
+
+/// A held lock which is renewed in the background for as long as the guard is alive, and released
+/// when it is dropped.
+///
+/// The lock is renewed every third of its TTL. Should a renewal fail the lock must be considered
+/// lost (another client may now hold it), which is signaled through [`LockGuard::is_lost`],
+/// [`LockGuard::take_loss_receiver`] or the callback given to
+/// [`LockGuard::acquire_with_callback`]. Any work protected by the lock should also pass the
+/// [`LockGuard::sequencer`] along as a fencing token, so that other services can reject requests
+/// made by a client which has since lost the lock.
+///
+/// # Examples
+/// ```no_run
+/// use seaplane::api::locks::v1::{LockGuard, LocksRequestBuilder};
+///
+/// let guard = LockGuard::acquire(
+///     || {
+///         LocksRequestBuilder::new()
+///             .token("abc123_token")
+///             .encoded_lock_name("bW9ieQo")
+///             .build()
+///     },
+///     30,
+///     "test-client",
+/// )
+/// .unwrap();
+/// // ... do some work, passing along guard.sequencer() as a fencing token
+/// assert!(!guard.is_lost());
+///
+/// // The lock is released when the guard is dropped, or explicitly with
+/// guard.release().unwrap();
+/// ```
+pub struct LockGuard {
+    lock: HeldLock,
+    lost: Arc<AtomicBool>,
+    loss_rx: Option<mpsc::Receiver<SeaplaneError>>,
+    stop_tx: Option<mpsc::Sender<()>>,
+    renewer: Option<JoinHandle<Result<()>>>,
+}
+
+impl fmt::Debug for LockGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LockGuard")
+            .field("lock", &self.lock)
+            .field("lost", &self.is_lost())
+            .finish()
+    }
+}
+
+impl LockGuard {
+    /// Acquires the lock targeted by the requests built with `new_request` (which must be
+    /// `SingleLock` requests) for `ttl` seconds, and starts renewing it in the background.
+    ///
+    /// `new_request` is called once up front, and again whenever the API rejects the access token
+    /// of the current request, so it should build each request with a fresh token.
+    ///
+    /// If the lock is lost the error which caused it can be retrieved from the receiver returned by
+    /// [`LockGuard::take_loss_receiver`].
+    pub fn acquire<R>(mut new_request: R, ttl: u32, client_id: &str) -> Result<Self>
+    where
+        R: FnMut() -> Result<LocksRequest> + Send + 'static,
+    {
+        let req = new_request()?;
+        let lock = req.acquire(ttl, client_id)?;
+        Ok(Self::with_loss_receiver(req, new_request, lock, ttl))
+    }
+
+    /// The same as [`LockGuard::acquire`] except `on_lost` is called (from the background renewal
+    /// thread) with the error which caused the lock to be lost.
+    pub fn acquire_with_callback<R, F>(
+        mut new_request: R,
+        ttl: u32,
+        client_id: &str,
+        on_lost: F,
+    ) -> Result<Self>
+    where
+        R: FnMut() -> Result<LocksRequest> + Send + 'static,
+        F: FnOnce(SeaplaneError) + Send + 'static,
+    {
+        let req = new_request()?;
+        let lock = req.acquire(ttl, client_id)?;
+        Ok(Self::start(req, new_request, lock, ttl, on_lost))
+    }
+
+    /// Starts renewing a lock which was already acquired with the given `ttl`, releasing it when
+    /// the guard is dropped. The renewals and release are made with requests built by
+    /// `new_request`, as in [`LockGuard::acquire`].
+    ///
+    /// If the lock is lost the error which caused it can be retrieved from the receiver returned by
+    /// [`LockGuard::take_loss_receiver`].
+    pub fn from_held_lock<R>(mut new_request: R, lock: HeldLock, ttl: u32) -> Result<Self>
+    where
+        R: FnMut() -> Result<LocksRequest> + Send + 'static,
+    {
+        let req = new_request()?;
+        Ok(Self::with_loss_receiver(req, new_request, lock, ttl))
+    }
+
+    // Starts the renewal thread, sending the error which caused the lock to be lost (if ever) to
+    // the guard's loss receiver
+    fn with_loss_receiver<R>(req: LocksRequest, new_request: R, lock: HeldLock, ttl: u32) -> Self
+    where
+        R: FnMut() -> Result<LocksRequest> + Send + 'static,
+    {
+        let (loss_tx, loss_rx) = mpsc::channel();
+        let mut guard = Self::start(req, new_request, lock, ttl, move |e| {
+            let _ = loss_tx.send(e);
+        });
+        guard.loss_rx = Some(loss_rx);
+        guard
+    }
+
+    // Starts the renewal thread for an acquired lock, which also releases it once stopped
+    fn start<R, F>(
+        req: LocksRequest,
+        mut new_request: R,
+        lock: HeldLock,
+        ttl: u32,
+        on_lost: F,
+    ) -> Self
+    where
+        R: FnMut() -> Result<LocksRequest> + Send + 'static,
+        F: FnOnce(SeaplaneError) + Send + 'static,
+    {
+        // Every request is retargeted at the held lock
+        let held = lock.clone();
+        let mut req = req.retarget(RequestTarget::HeldLock(held.clone()));
+        let mut new_request =
+            move || Ok(new_request()?.retarget(RequestTarget::HeldLock(held.clone())));
+        let lost = Arc::new(AtomicBool::new(false));
+
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let interval = Duration::from_millis(u64::from(ttl) * 1000 / 3);
+        let renewer = {
+            let lost = Arc::clone(&lost);
+            thread::spawn(move || {
+                // Any message, or the sender being dropped means we should stop
+                while let Err(mpsc::RecvTimeoutError::Timeout) = stop_rx.recv_timeout(interval) {
+                    if let Err(e) =
+                        with_fresh_token(&mut req, &mut new_request, |req| req.renew(ttl))
+                    {
+                        lost.store(true, Ordering::SeqCst);
+                        on_lost(e);
+                        return Ok(());
+                    }
+                }
+                with_fresh_token(&mut req, &mut new_request, LocksRequest::release)
+            })
+        };
+
+        Self { lock, lost, loss_rx: None, stop_tx: Some(stop_tx), renewer: Some(renewer) }
+    }
+
+    /// The held lock
+    pub fn held_lock(&self) -> &HeldLock { &self.lock }
+
+    /// The lock's sequencer, which increases each time the lock is acquired and can be used as a
+    /// fencing token
+    pub fn sequencer(&self) -> u32 { self.lock.sequencer() }
+
+    /// Returns `true` if renewing the lock failed, meaning it may now be held by someone else
+    pub fn is_lost(&self) -> bool { self.lost.load(Ordering::SeqCst) }
+
+    /// Takes the receiving end of a channel which will receive the error that caused the lock to
+    /// be lost, if it ever is.
+    ///
+    /// Returns `None` if the receiver was already taken, or the guard was created with
+    /// [`LockGuard::acquire_with_callback`].
+    pub fn take_loss_receiver(&mut self) -> Option<mpsc::Receiver<SeaplaneError>> {
+        self.loss_rx.take()
+    }
+
+    /// Stops renewing the lock and releases it, returning any error from the release request.
+    ///
+    /// Dropping the guard does the same, but ignores any errors.
+    pub fn release(mut self) -> Result<()> { self.stop() }
+
+    // Stops the renewal thread, returning the result of releasing the lock if it's still held.
+    // Does nothing if already stopped.
+    fn stop(&mut self) -> Result<()> {
+        let renewer = match self.renewer.take() {
+            Some(renewer) => renewer,
+            None => return Ok(()),
+        };
+        if let Some(stop_tx) = self.stop_tx.take() {
+            let _ = stop_tx.send(());
+        }
+        renewer.join().unwrap_or(Ok(()))
+    }
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) { let _ = self.stop(); }
+}
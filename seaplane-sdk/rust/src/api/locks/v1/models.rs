@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use crate::{api::shared::v1::RangeQueryContext, base64::Base64Encoded, impl_base64};
 
 /// A single lock name, encoded in url-safe base64, may not contain `\0` bytes
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(transparent)]
 pub struct LockName {
     inner: Base64Encoded,
@@ -21,7 +21,7 @@ impl_base64!(LockId);
 
 /// A lock that at some point was held by this client.
 /// At any point this may have lapsed.
-#[derive(Debug, Serialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 pub struct HeldLock {
     pub(crate) name: LockName,
     pub(crate) id: LockId,
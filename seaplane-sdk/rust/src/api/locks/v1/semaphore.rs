@@ -0,0 +1,136 @@
+use std::collections::HashSet;
+
+use crate::{
+    api::{
+        locks::v1::{HeldLock, LockGuard, LockInfo, LockName, LocksRequest, RequestTarget},
+        shared::v1::{Directory, RangeQueryContext},
+        ApiErrorKind,
+    },
+    error::{Result, SeaplaneError},
+};
+
+/// A counted semaphore which allows up to a fixed number of clients to hold it at once.
+///
+/// The semaphore is made up of one ordinary lock per slot, named `<name>/<slot>` for each slot
+/// from `0` up to (but not including) the number of slots. Acquiring the semaphore means acquiring
+/// any one of the slots, which then behaves exactly like a single lock: it must be renewed before
+/// its TTL expires, and released when no longer needed.
+///
+/// All clients using the semaphore must agree on the number of slots.
+///
+/// # Examples
+/// ```no_run
+/// use seaplane::api::locks::v1::{LocksRequestBuilder, Semaphore};
+///
+/// // At most 3 deploys at once
+/// let req = LocksRequestBuilder::new()
+///     .token("abc123_token")
+///     .encoded_lock_name("ZGVwbG95cw")
+///     .build()
+///     .unwrap();
+///
+/// let semaphore = Semaphore::new(&req, 3).unwrap();
+/// let guard = semaphore
+///     .acquire(
+///         || {
+///             LocksRequestBuilder::new()
+///                 .token("abc123_token")
+///                 .encoded_lock_name("ZGVwbG95cw")
+///                 .build()
+///         },
+///         30,
+///         "deployer",
+///     )
+///     .unwrap();
+/// println!("holding {}", String::from_utf8_lossy(&guard.held_lock().name().decode()));
+/// // ... the slot is renewed in the background and released when the guard is dropped
+/// ```
+#[derive(Debug)]
+pub struct Semaphore {
+    req: LocksRequest,
+    name: Vec<u8>,
+    slots: u32,
+}
+
+impl Semaphore {
+    /// Creates a semaphore named by the lock targeted by `req` (which must be a `SingleLock`
+    /// request) with the given number of slots.
+    pub fn new(req: &LocksRequest, slots: u32) -> Result<Self> {
+        if slots == 0 {
+            return Err(SeaplaneError::EmptySemaphore);
+        }
+        let name = base64::decode_config(req.lock_name()?.encoded(), base64::URL_SAFE_NO_PAD)
+            .map_err(|e| SeaplaneError::Decode(e.to_string()))?;
+        let req = req.retarget(RequestTarget::Range(RangeQueryContext::new()));
+
+        Ok(Self { req, name, slots })
+    }
+
+    /// The number of slots, i.e. how many clients may hold the semaphore at once
+    pub fn slots(&self) -> u32 { self.slots }
+
+    /// The name of the lock used for the given slot
+    pub fn slot_name(&self, slot: u32) -> LockName {
+        let mut name = self.name.clone();
+        name.push(b'/');
+        name.extend_from_slice(slot.to_string().as_bytes());
+        LockName::from_unencoded(name)
+    }
+
+    /// Returns information about the locks of all slots which are currently held
+    pub fn held_slots(&self) -> Result<Vec<LockInfo>> {
+        let mut range = RangeQueryContext::new();
+        range.set_directory(Directory::from_unencoded(&self.name));
+        let mut req = self.req.retarget(RequestTarget::Range(range));
+
+        let slot_names: HashSet<_> = (0..self.slots).map(|s| self.slot_name(s)).collect();
+        Ok(req
+            .get_all_pages()?
+            .into_iter()
+            .filter(|info| slot_names.contains(&info.name))
+            .collect())
+    }
+
+    /// Attempts to acquire any free slot for `ttl` seconds, returning the lock of the slot
+    /// which was acquired.
+    ///
+    /// The lock is not renewed or released automatically, see [`Semaphore::acquire`] for that.
+    /// Returns [`SeaplaneError::SemaphoreFull`] if all slots are held.
+    pub fn try_acquire(&self, ttl: u32, client_id: &str) -> Result<HeldLock> {
+        let held: HashSet<_> = self
+            .held_slots()?
+            .into_iter()
+            .map(|info| info.name)
+            .collect();
+
+        for name in (0..self.slots).map(|s| self.slot_name(s)) {
+            if held.contains(&name) {
+                continue;
+            }
+            // The slot may have been taken since listing the held slots
+            match self
+                .req
+                .retarget(RequestTarget::SingleLock(name))
+                .acquire(ttl, client_id)
+            {
+                Err(SeaplaneError::ApiResponse(ae)) if ae.kind == ApiErrorKind::Conflict => (),
+                res => return res,
+            }
+        }
+
+        Err(SeaplaneError::SemaphoreFull(self.slots))
+    }
+
+    /// Acquires any free slot for `ttl` seconds, renewing it in the background until the returned
+    /// guard is dropped just like [`LockGuard::acquire`]. The slot is renewed and released with
+    /// requests built by `new_request`, which should build each request with a fresh token.
+    ///
+    /// Returns [`SeaplaneError::SemaphoreFull`] if all slots are held.
+    pub fn acquire<R>(&self, new_request: R, ttl: u32, client_id: &str) -> Result<LockGuard>
+    where
+        R: FnMut() -> Result<LocksRequest> + Send + 'static,
+    {
+        let lock = self.try_acquire(ttl, client_id)?;
+        LockGuard::from_held_lock(new_request, lock, ttl)
+    }
+}
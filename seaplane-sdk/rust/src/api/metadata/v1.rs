@@ -1,10 +1,14 @@
 //! The `/config` endpoint APIs which allows working with [`KeyValue`]s
+mod encryption;
 mod models;
+mod validation;
+pub use encryption::*;
 pub use models::*;
 use reqwest::{
     header::{self, CONTENT_TYPE},
     Url,
 };
+pub use validation::*;
 
 use crate::{
     api::{
@@ -203,6 +207,10 @@ impl MetadataRequest {
     /// ```
     pub fn put_value(&self, value: Value) -> Result<()> {
         let url = self.single_key_url()?;
+        if let Some(RequestTarget::Key(key)) = &self.request.target {
+            validate_encoded_key(key.encoded())?;
+        }
+        validate_encoded_value(value.encoded())?;
         let resp = self
             .request
             .client
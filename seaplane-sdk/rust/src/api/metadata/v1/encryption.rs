@@ -0,0 +1,309 @@
+//! Optional client side envelope encryption of metadata values.
+//!
+//! Each value is encrypted with a freshly generated data key, and that data key is in turn
+//! encrypted ("wrapped") with one of the long lived keys from a [`Keyring`]. The version of the
+//! key used to wrap the data key is stored in the value header, which allows rotating keys while
+//! still being able to decrypt values written with older keys.
+//!
+//! The layout of an encrypted value (prior to base64 encoding) is:
+//!
+//! ```text
+//! | magic (4) | key version (4, BE) | wrap nonce (24) | wrapped data key (48) | nonce (24) | ciphertext |
+//! ```
+//!
+//! The magic and key version are authenticated as associated data of both the wrapped data key and
+//! the ciphertext.
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use base64::{decode_config, STANDARD_NO_PAD, URL_SAFE_NO_PAD};
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+
+use crate::{
+    api::metadata::v1::Value,
+    error::{Result, SeaplaneError},
+};
+
+/// Identifies a value as encrypted, the last byte is the version of the layout
+const MAGIC: &[u8; 4] = b"SPE\x01";
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+const TAG_LEN: usize = 16;
+const HEADER_LEN: usize = MAGIC.len() + 4;
+const WRAPPED_KEY_LEN: usize = KEY_LEN + TAG_LEN;
+const PREFIX_LEN: usize = HEADER_LEN + NONCE_LEN + WRAPPED_KEY_LEN + NONCE_LEN;
+
+/// Returns `true` if the (decoded) bytes look like a value encrypted by a [`Keyring`]
+pub fn is_encrypted<B: AsRef<[u8]>>(bytes: B) -> bool {
+    let bytes = bytes.as_ref();
+    bytes.len() >= PREFIX_LEN + TAG_LEN && bytes.starts_with(MAGIC)
+}
+
+/// A single 256-bit key used to wrap the per-value data keys
+// Not Copy to avoid scattering copies of the key material around
+#[allow(missing_copy_implementations)]
+#[derive(Clone, PartialEq, Eq)]
+pub struct EncryptionKey {
+    version: u32,
+    key: [u8; KEY_LEN],
+}
+
+// Manually implemented so that key material never ends up in logs
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptionKey")
+            .field("version", &self.version)
+            .finish_non_exhaustive()
+    }
+}
+
+impl EncryptionKey {
+    /// Creates a key from raw bytes
+    pub fn new(version: u32, key: [u8; KEY_LEN]) -> Self { Self { version, key } }
+
+    /// Creates a key from base64 encoded bytes (either URL safe or standard, with or without
+    /// padding)
+    pub fn from_base64<S: AsRef<str>>(version: u32, encoded: S) -> Result<Self> {
+        let encoded = encoded.as_ref().trim().trim_end_matches('=');
+        let raw = decode_config(encoded, URL_SAFE_NO_PAD)
+            .or_else(|_| decode_config(encoded, STANDARD_NO_PAD))
+            .map_err(|_| {
+                SeaplaneError::MetadataEncryption(format!(
+                    "key version {version} is not valid base64"
+                ))
+            })?;
+        let key = raw.try_into().map_err(|_| {
+            SeaplaneError::MetadataEncryption(format!(
+                "key version {version} must be exactly {KEY_LEN} bytes"
+            ))
+        })?;
+
+        Ok(Self::new(version, key))
+    }
+
+    /// The version of this key which is stored in the header of each value it encrypts
+    pub fn version(&self) -> u32 { self.version }
+
+    fn cipher(&self) -> XChaCha20Poly1305 { XChaCha20Poly1305::new(&self.key.into()) }
+}
+
+/// A set of versioned keys. New values are always encrypted with the highest key version, while
+/// any key in the ring can be used to decrypt.
+///
+/// The textual format (used by [`Keyring::from_str`]) is one `VERSION:BASE64-KEY` pair per line or
+/// separated by commas. Empty lines and lines beginning with `#` are ignored.
+///
+/// ```text
+/// # rotated 2022-11-01
+/// 1:lXwTlIx9v5Q1V0nOQ5iKx1wsK3fgvIZPpPSJvkDgPhs
+/// 2:Xk3Mx1FQ9mW2aHG0Zr3Qm3qD2p8qfXKJ2x6qTGzj3YQ
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Keyring {
+    keys: BTreeMap<u32, EncryptionKey>,
+}
+
+impl FromStr for Keyring {
+    type Err = SeaplaneError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut ring = Keyring::default();
+        for entry in s
+            .split(['\n', ','])
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        {
+            let (version, key) = entry.split_once(':').ok_or_else(|| {
+                SeaplaneError::MetadataEncryption(
+                    "keys must be in the form VERSION:BASE64-KEY".into(),
+                )
+            })?;
+            let version = version.trim().parse::<u32>().map_err(|_| {
+                SeaplaneError::MetadataEncryption(format!("invalid key version '{version}'"))
+            })?;
+            ring.insert(EncryptionKey::from_base64(version, key)?)?;
+        }
+
+        if ring.is_empty() {
+            return Err(SeaplaneError::MetadataEncryption("no keys were provided".into()));
+        }
+
+        Ok(ring)
+    }
+}
+
+impl Keyring {
+    /// Create an empty keyring
+    pub fn new() -> Self { Self::default() }
+
+    /// Adds a key to the ring, failing if a key with the same version already exists
+    pub fn insert(&mut self, key: EncryptionKey) -> Result<()> {
+        if self.keys.contains_key(&key.version) {
+            return Err(SeaplaneError::MetadataEncryption(format!(
+                "duplicate key version {}",
+                key.version
+            )));
+        }
+        self.keys.insert(key.version, key);
+        Ok(())
+    }
+
+    /// Returns `true` if there are no keys in the ring
+    pub fn is_empty(&self) -> bool { self.keys.is_empty() }
+
+    /// The key that will be used to encrypt new values, i.e. the highest version
+    pub fn active_key(&self) -> Option<&EncryptionKey> { self.keys.values().next_back() }
+
+    /// Returns the key with the given version, if it exists in the ring
+    pub fn get(&self, version: u32) -> Option<&EncryptionKey> { self.keys.get(&version) }
+
+    /// Encrypts the given bytes with a new data key wrapped by the active key
+    pub fn encrypt<B: AsRef<[u8]>>(&self, plaintext: B) -> Result<Vec<u8>> {
+        let kek = self
+            .active_key()
+            .ok_or_else(|| SeaplaneError::MetadataEncryption("no keys were provided".into()))?;
+
+        let mut out = Vec::with_capacity(PREFIX_LEN + plaintext.as_ref().len() + TAG_LEN);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&kek.version.to_be_bytes());
+        let aad = out.clone();
+
+        let data_key = XChaCha20Poly1305::generate_key(&mut OsRng);
+        let wrap_nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let wrapped = kek
+            .cipher()
+            .encrypt(&wrap_nonce, Payload { msg: &data_key, aad: &aad })
+            .map_err(|_| SeaplaneError::MetadataEncryption("failed to wrap data key".into()))?;
+
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = XChaCha20Poly1305::new(&data_key)
+            .encrypt(&nonce, Payload { msg: plaintext.as_ref(), aad: &aad })
+            .map_err(|_| SeaplaneError::MetadataEncryption("failed to encrypt value".into()))?;
+
+        out.extend_from_slice(&wrap_nonce);
+        out.extend_from_slice(&wrapped);
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    /// Decrypts bytes previously encrypted by a keyring containing the same key version
+    pub fn decrypt<B: AsRef<[u8]>>(&self, bytes: B) -> Result<Vec<u8>> {
+        let bytes = bytes.as_ref();
+        if !is_encrypted(bytes) {
+            return Err(SeaplaneError::MetadataEncryption("value is not encrypted".into()));
+        }
+
+        let (aad, rest) = bytes.split_at(HEADER_LEN);
+        let version = u32::from_be_bytes(aad[MAGIC.len()..].try_into().unwrap());
+        let kek = self
+            .get(version)
+            .ok_or(SeaplaneError::MissingEncryptionKey(version))?;
+
+        let (wrap_nonce, rest) = rest.split_at(NONCE_LEN);
+        let (wrapped, rest) = rest.split_at(WRAPPED_KEY_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+        let data_key = kek
+            .cipher()
+            .decrypt(XNonce::from_slice(wrap_nonce), Payload { msg: wrapped, aad })
+            .map_err(|_| {
+                SeaplaneError::MetadataEncryption(format!(
+                    "failed to unwrap data key with key version {version}"
+                ))
+            })?;
+
+        XChaCha20Poly1305::new_from_slice(&data_key)
+            .map_err(|_| SeaplaneError::MetadataEncryption("invalid data key length".into()))?
+            .decrypt(XNonce::from_slice(nonce), Payload { msg: ciphertext, aad })
+            .map_err(|_| SeaplaneError::MetadataEncryption("value failed authentication".into()))
+    }
+
+    /// Encrypts the given bytes and base64 encodes them into a [`Value`] ready to be stored
+    pub fn encrypt_value<B: AsRef<[u8]>>(&self, plaintext: B) -> Result<Value> {
+        Ok(Value::from_unencoded(self.encrypt(plaintext)?))
+    }
+
+    /// Decodes and decrypts a [`Value`]. Values which are not encrypted are returned decoded but
+    /// otherwise unmodified.
+    pub fn decrypt_value(&self, value: &Value) -> Result<Vec<u8>> {
+        let bytes = value.decode();
+        if is_encrypted(&bytes) {
+            self.decrypt(bytes)
+        } else {
+            Ok(bytes)
+        }
+    }
+}
+
+#[cfg(test)]
+mod encryption_test {
+    use super::*;
+
+    fn ring() -> Keyring {
+        "1:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA,\
+         2:AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let ring = ring();
+        let sealed = ring.encrypt(b"hunter2").unwrap();
+        assert!(is_encrypted(&sealed));
+        assert_eq!(&sealed[4..8], &2u32.to_be_bytes());
+        assert_eq!(ring.decrypt(&sealed).unwrap(), b"hunter2");
+    }
+
+    #[test]
+    fn decrypt_with_rotated_key() {
+        let old: Keyring = "1:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"
+            .parse()
+            .unwrap();
+        let sealed = old.encrypt(b"hunter2").unwrap();
+        assert_eq!(ring().decrypt(sealed).unwrap(), b"hunter2");
+    }
+
+    #[test]
+    fn missing_key_version() {
+        let sealed = ring().encrypt(b"hunter2").unwrap();
+        let old: Keyring = "1:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"
+            .parse()
+            .unwrap();
+        assert_eq!(old.decrypt(sealed), Err(SeaplaneError::MissingEncryptionKey(2)));
+    }
+
+    #[test]
+    fn tampered_value() {
+        let ring = ring();
+        let mut sealed = ring.encrypt(b"hunter2").unwrap();
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+        assert!(ring.decrypt(sealed).is_err());
+    }
+
+    #[test]
+    fn plain_values_pass_through() {
+        let value = Value::from_unencoded("not a secret");
+        assert_eq!(ring().decrypt_value(&value).unwrap(), b"not a secret");
+    }
+
+    #[test]
+    fn parse_keyring() {
+        let ring: Keyring = "# comment\n\n 3:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA= \n"
+            .parse()
+            .unwrap();
+        assert_eq!(ring.active_key().unwrap().version(), 3);
+        assert!("".parse::<Keyring>().is_err());
+        assert!("1:AAAA".parse::<Keyring>().is_err());
+        assert!("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"
+            .parse::<Keyring>()
+            .is_err());
+        assert!("1:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA,1:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"
+            .parse::<Keyring>()
+            .is_err());
+    }
+}
@@ -0,0 +1,154 @@
+//! Local validation of metadata keys and values, so malformed keys and values can be reported
+//! before making a request.
+//!
+//! Only the structure of keys and the encoding of keys and values are checked by default. The
+//! `/config` API has the final say on anything else, such as how large keys and values may be,
+//! but callers which know their limits can opt in to checking sizes with [`SizeLimits`].
+
+use thiserror::Error;
+
+/// The character separating directories within a key
+pub const DIRECTORY_SEPARATOR: u8 = b'/';
+
+#[derive(Error, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MetadataValidationError {
+    #[error("key cannot be empty")]
+    EmptyKey,
+
+    #[error("key cannot end with a '/' which is reserved for directories")]
+    TrailingSeparator,
+
+    #[error("key cannot contain an empty directory name (i.e. a leading '/' or '//')")]
+    EmptyDirectory,
+
+    #[error("{0} is not valid URL safe base64 without padding")]
+    InvalidEncoding(&'static str),
+
+    #[error("key is {0} bytes which is more than the maximum of {1} bytes")]
+    KeyTooLong(usize, usize),
+
+    #[error("value is {0} bytes which is more than the maximum of {1} bytes")]
+    ValueTooLong(usize, usize),
+}
+
+/// The maximum sizes of un-encoded keys and values, for callers which want to check them before
+/// making a request. The `/config` API doesn't document its limits so they are not checked unless
+/// asked for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SizeLimits {
+    pub max_key_len: usize,
+    pub max_value_len: usize,
+}
+
+impl SizeLimits {
+    /// Checks the sizes of an un-encoded key and value
+    pub fn check<K: AsRef<[u8]>, V: AsRef<[u8]>>(
+        &self,
+        key: K,
+        value: V,
+    ) -> Result<(), MetadataValidationError> {
+        let (key_len, value_len) = (key.as_ref().len(), value.as_ref().len());
+        if key_len > self.max_key_len {
+            return Err(MetadataValidationError::KeyTooLong(key_len, self.max_key_len));
+        }
+        if value_len > self.max_value_len {
+            return Err(MetadataValidationError::ValueTooLong(value_len, self.max_value_len));
+        }
+
+        Ok(())
+    }
+}
+
+/// Validates an un-encoded key.
+///
+/// Rules:
+///
+/// - may not be empty
+/// - may not end with the directory separator `/`
+/// - may not contain empty directory names, i.e. begin with `/` or contain `//`
+pub fn validate_key<B: AsRef<[u8]>>(key: B) -> Result<(), MetadataValidationError> {
+    let key = key.as_ref();
+    if key.is_empty() {
+        return Err(MetadataValidationError::EmptyKey);
+    }
+    if key.last() == Some(&DIRECTORY_SEPARATOR) {
+        return Err(MetadataValidationError::TrailingSeparator);
+    }
+    if key
+        .split(|b| *b == DIRECTORY_SEPARATOR)
+        .any(<[u8]>::is_empty)
+    {
+        return Err(MetadataValidationError::EmptyDirectory);
+    }
+
+    Ok(())
+}
+
+/// Validates a key encoded as URL safe base64 without padding, checking both the encoding and
+/// the decoded key per [`validate_key`]
+pub fn validate_encoded_key<S: AsRef<str>>(key: S) -> Result<(), MetadataValidationError> {
+    validate_key(
+        base64::decode_config(key.as_ref(), base64::URL_SAFE_NO_PAD)
+            .map_err(|_| MetadataValidationError::InvalidEncoding("key"))?,
+    )
+}
+
+/// Validates a value is encoded as URL safe base64 without padding. Any bytes are a valid value.
+pub fn validate_encoded_value<S: AsRef<str>>(value: S) -> Result<(), MetadataValidationError> {
+    base64::decode_config(value.as_ref(), base64::URL_SAFE_NO_PAD)
+        .map(|_| ())
+        .map_err(|_| MetadataValidationError::InvalidEncoding("value"))
+}
+
+#[cfg(test)]
+mod validation_test {
+    use super::*;
+
+    #[test]
+    fn valid_keys() {
+        assert!(validate_key("foo").is_ok());
+        assert!(validate_key("foo/bar/baz").is_ok());
+        assert!(validate_key(b"\x00\xff").is_ok());
+        assert!(validate_key(vec![b'a'; 4096]).is_ok());
+    }
+
+    #[test]
+    fn invalid_keys() {
+        assert_eq!(validate_key(""), Err(MetadataValidationError::EmptyKey));
+        assert_eq!(validate_key("foo/"), Err(MetadataValidationError::TrailingSeparator));
+        assert_eq!(validate_key("/"), Err(MetadataValidationError::TrailingSeparator));
+        assert_eq!(validate_key("/foo"), Err(MetadataValidationError::EmptyDirectory));
+        assert_eq!(validate_key("foo//bar"), Err(MetadataValidationError::EmptyDirectory));
+    }
+
+    #[test]
+    fn size_limits() {
+        let limits = SizeLimits { max_key_len: 3, max_value_len: 4 };
+        assert!(limits.check("foo", "").is_ok());
+        assert!(limits.check("foo", "barz").is_ok());
+        assert_eq!(limits.check("fooo", "bar"), Err(MetadataValidationError::KeyTooLong(4, 3)));
+        assert_eq!(
+            limits.check("foo", vec![0; 5]),
+            Err(MetadataValidationError::ValueTooLong(5, 4))
+        );
+    }
+
+    #[test]
+    fn encoded() {
+        assert!(validate_encoded_key("Zm9vL2Jhcg").is_ok());
+        assert_eq!(
+            validate_encoded_key("Zm9v/2Jhcg"),
+            Err(MetadataValidationError::InvalidEncoding("key"))
+        );
+        assert_eq!(
+            validate_encoded_key("Zm9vLw"), // foo/
+            Err(MetadataValidationError::TrailingSeparator)
+        );
+        assert!(validate_encoded_value("YmFy").is_ok());
+        assert!(validate_encoded_value("").is_ok());
+        assert_eq!(
+            validate_encoded_value("Ym+y"),
+            Err(MetadataValidationError::InvalidEncoding("value"))
+        );
+    }
+}
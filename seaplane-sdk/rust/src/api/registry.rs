@@ -0,0 +1,6 @@
+//! Talking to the OCI container image registries which hold the images [`Flight`]s run
+//!
+//! [`Flight`]: crate::api::compute::v1::Flight
+
+#[cfg(feature = "registry_api_v2")]
+pub mod v2;
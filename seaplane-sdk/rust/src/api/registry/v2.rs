@@ -0,0 +1,485 @@
+//! The OCI distribution (`/v2`) APIs of a container image registry, which allows resolving
+//! [`ImageReference`]s to digests, listing tags and reading image manifests
+//!
+//! Registries which require authentication are supported through the token flow described by the
+//! registry's `WWW-Authenticate` challenge, or through HTTP basic authentication.
+
+mod error;
+mod models;
+pub use models::*;
+use reqwest::{
+    blocking::{self, RequestBuilder, Response},
+    header::{self, HeaderMap},
+    Method, StatusCode, Url,
+};
+use serde::Deserialize;
+
+use crate::{
+    api::{compute::v1::ImageReference, registry::v2::error::map_api_error},
+    error::{Result, SeaplaneError},
+};
+
+/// The header registries use to return the digest of a manifest
+const DIGEST_HEADER: &str = "docker-content-digest";
+
+/// The tag used when an image reference has neither a tag nor a digest
+const DEFAULT_TAG: &str = "latest";
+
+/// A builder struct for creating a [`RegistryRequest`] which will then be used for making a
+/// request against the `/v2` APIs of a container image registry
+#[derive(Debug, Default)]
+pub struct RegistryRequestBuilder {
+    reference: Option<ImageReference>,
+    credentials: Option<(String, String)>,
+    #[cfg(any(feature = "allow_insecure_urls", feature = "danger_zone"))]
+    allow_http: bool,
+    #[cfg(any(feature = "allow_invalid_certs", feature = "danger_zone"))]
+    allow_invalid_certs: bool,
+    // Used for testing
+    base_url: Option<Url>,
+}
+
+impl RegistryRequestBuilder {
+    /// Create a new RegistryRequestBuilder
+    pub fn new() -> Self { Self::default() }
+
+    /// Build a RegistryRequest from the given parameters
+    pub fn build(self) -> Result<RegistryRequest> {
+        let reference = self.reference.ok_or(SeaplaneError::MissingImageReference)?;
+
+        #[cfg_attr(
+            not(any(
+                feature = "api_tests",
+                feature = "allow_insecure_urls",
+                feature = "danger_zone"
+            )),
+            allow(unused_mut)
+        )]
+        let mut builder = blocking::Client::builder().https_only(true);
+        #[cfg_attr(
+            not(any(feature = "allow_insecure_urls", feature = "danger_zone")),
+            allow(unused_mut)
+        )]
+        let mut scheme = "https";
+
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "api_tests")] {
+                builder = builder.https_only(false);
+            } else if #[cfg(any(feature = "allow_insecure_urls", feature = "danger_zone"))] {
+                builder = builder.https_only(!self.allow_http);
+            }
+        }
+        #[cfg(any(feature = "allow_insecure_urls", feature = "danger_zone"))]
+        if self.allow_http {
+            scheme = "http";
+        }
+        #[cfg(any(feature = "allow_invalid_certs", feature = "danger_zone"))]
+        {
+            builder = builder.danger_accept_invalid_certs(self.allow_invalid_certs);
+        }
+
+        let url = if let Some(url) = &self.base_url {
+            url.clone()
+        } else {
+            format!("{scheme}://{}/", reference.domain()).parse()?
+        };
+
+        Ok(RegistryRequest {
+            endpoint_url: url.join(&format!("v2/{}/", reference.path()))?,
+            reference,
+            credentials: self.credentials,
+            token: None,
+            client: builder.build()?,
+        })
+    }
+
+    /// The image to query, the registry is taken from the domain of the reference.
+    ///
+    /// **NOTE:** This is required for all endpoints
+    #[must_use]
+    pub fn reference(mut self, reference: ImageReference) -> Self {
+        self.reference = Some(reference);
+        self
+    }
+
+    /// The username and password (or access token) to authenticate with, if the registry requires
+    /// them. Without credentials only anonymous access is attempted.
+    #[must_use]
+    pub fn credentials<S: Into<String>>(mut self, username: S, password: S) -> Self {
+        self.credentials = Some((username.into(), password.into()));
+        self
+    }
+
+    /// Allow non-HTTPS registries for this request (default: `false`)
+    #[cfg(any(feature = "allow_insecure_urls", feature = "danger_zone"))]
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "allow_insecure_urls", feature = "danger_zone"))))]
+    pub fn allow_http(mut self, yes: bool) -> Self {
+        self.allow_http = yes;
+        self
+    }
+
+    /// Allow invalid TLS certificates (default: `false`)
+    #[cfg(any(feature = "allow_invalid_certs", feature = "danger_zone"))]
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "allow_invalid_certs", feature = "danger_zone"))))]
+    pub fn allow_invalid_certs(mut self, yes: bool) -> Self {
+        self.allow_invalid_certs = yes;
+        self
+    }
+
+    // Used in testing and development to manually set the URL
+    #[doc(hidden)]
+    pub fn base_url<U: AsRef<str>>(mut self, url: U) -> Self {
+        self.base_url = Some(url.as_ref().parse().unwrap());
+        self
+    }
+}
+
+/// For making requests against the `/v2` APIs of a container image registry.
+#[derive(Debug)]
+pub struct RegistryRequest {
+    reference: ImageReference,
+    credentials: Option<(String, String)>,
+    // A bearer token handed out by the registry's token service, reused across requests
+    token: Option<String>,
+    client: blocking::Client,
+    endpoint_url: Url,
+}
+
+/// The response of a registry token service
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    #[serde(default)]
+    token: Option<String>,
+    #[serde(default)]
+    access_token: Option<String>,
+}
+
+/// The image configuration blob, of which only the platform is of interest
+#[derive(Debug, Deserialize)]
+struct ImageConfig {
+    architecture: String,
+    os: String,
+    #[serde(default)]
+    variant: Option<String>,
+}
+
+impl RegistryRequest {
+    /// Create a new request builder
+    pub fn builder() -> RegistryRequestBuilder { RegistryRequestBuilder::new() }
+
+    /// The digest, or tag, to use when requesting the manifest of the reference
+    fn manifest_ref(&self) -> &str {
+        self.reference
+            .digest
+            .as_deref()
+            .or(self.reference.tag.as_deref())
+            .unwrap_or(DEFAULT_TAG)
+    }
+
+    fn manifest_url(&self, reference: &str) -> Result<Url> {
+        Ok(self.endpoint_url.join(&format!("manifests/{reference}"))?)
+    }
+
+    fn request(&self, method: Method, url: Url, accept: &str) -> RequestBuilder {
+        let req = self
+            .client
+            .request(method, url)
+            .header(header::ACCEPT, accept);
+        match (&self.token, &self.credentials) {
+            (Some(token), _) => req.bearer_auth(token),
+            (None, Some((user, pass))) => req.basic_auth(user, Some(pass)),
+            (None, None) => req,
+        }
+    }
+
+    // Sends the request, answering a bearer token challenge from the registry once if required
+    fn send(&mut self, method: Method, url: Url, accept: &str) -> Result<Response> {
+        let resp = self.request(method.clone(), url.clone(), accept).send()?;
+        if resp.status() != StatusCode::UNAUTHORIZED || self.token.is_some() {
+            return map_api_error(resp);
+        }
+
+        match bearer_challenge(resp.headers()) {
+            Some(challenge) => {
+                self.token = Some(self.request_token(&challenge)?);
+                let resp = self.request(method, url, accept).send()?;
+                map_api_error(resp)
+            }
+            None => map_api_error(resp),
+        }
+    }
+
+    fn request_token(&self, challenge: &[(String, String)]) -> Result<String> {
+        let mut realm = None;
+        let mut params = Vec::new();
+        for (k, v) in challenge {
+            if k == "realm" {
+                realm = Some(v);
+            } else {
+                params.push((k, v));
+            }
+        }
+        let mut url: Url = realm.ok_or(SeaplaneError::MissingRegistryToken)?.parse()?;
+        url.query_pairs_mut().extend_pairs(params);
+
+        let mut req = self.client.get(url);
+        if let Some((user, pass)) = &self.credentials {
+            req = req.basic_auth(user, Some(pass));
+        }
+        let resp = map_api_error(req.send()?)?.json::<TokenResponse>()?;
+        resp.token
+            .or(resp.access_token)
+            .ok_or(SeaplaneError::MissingRegistryToken)
+    }
+
+    /// Returns the digest of the manifest the reference currently points to. When the reference
+    /// has neither a tag nor a digest, the `latest` tag is used.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use seaplane::api::registry::v2::RegistryRequest;
+    ///
+    /// let mut req = RegistryRequest::builder()
+    ///     .reference(
+    ///         "registry.cplane.cloud/seaplane/nginx:latest"
+    ///             .parse()
+    ///             .unwrap(),
+    ///     )
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let digest = req.resolve_digest().unwrap();
+    /// dbg!(digest);
+    /// ```
+    pub fn resolve_digest(&mut self) -> Result<String> {
+        let url = self.manifest_url(self.manifest_ref())?;
+        let resp = self.send(Method::HEAD, url.clone(), &manifest_accept())?;
+        if let Some(digest) = digest_header(resp.headers()) {
+            return Ok(digest);
+        }
+
+        // Not all registries include the digest when answering a HEAD request
+        let resp = self.send(Method::GET, url, &manifest_accept())?;
+        digest_header(resp.headers())
+            .or_else(|| self.reference.digest.clone())
+            .ok_or(SeaplaneError::MissingImageDigest)
+    }
+
+    /// Returns all tags of the repository of the reference, following the pagination of the
+    /// registry if there is any.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use seaplane::api::registry::v2::RegistryRequest;
+    ///
+    /// let mut req = RegistryRequest::builder()
+    ///     .reference("registry.cplane.cloud/seaplane/nginx".parse().unwrap())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let tags = req.get_tags().unwrap();
+    /// dbg!(tags);
+    /// ```
+    pub fn get_tags(&mut self) -> Result<Vec<String>> {
+        let mut tags = Vec::new();
+        let mut url = self.endpoint_url.join("tags/list")?;
+        loop {
+            let resp = self.send(Method::GET, url.clone(), "application/json")?;
+            let next = next_link(resp.headers())
+                .map(|link| url.join(&link))
+                .transpose()?;
+            tags.extend(resp.json::<TagList>()?.tags.unwrap_or_default());
+            match next {
+                Some(next) => url = next,
+                None => break,
+            }
+        }
+        Ok(tags)
+    }
+
+    /// Returns the manifest the reference points to along with the platforms the image is
+    /// available for. For a manifest of a single image, the platform is read from the image
+    /// configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use seaplane::api::registry::v2::RegistryRequest;
+    ///
+    /// let mut req = RegistryRequest::builder()
+    ///     .reference(
+    ///         "registry.cplane.cloud/seaplane/nginx:latest"
+    ///             .parse()
+    ///             .unwrap(),
+    ///     )
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let manifest = req.get_manifest().unwrap();
+    /// dbg!(manifest.architectures());
+    /// ```
+    pub fn get_manifest(&mut self) -> Result<ImageManifest> {
+        let url = self.manifest_url(self.manifest_ref())?;
+        let resp = self.send(Method::GET, url, &manifest_accept())?;
+        let digest = digest_header(resp.headers()).or_else(|| self.reference.digest.clone());
+        let content_type = resp
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.split(';').next().unwrap_or_default().trim().to_owned());
+        let raw: RawManifest = resp.json()?;
+        let media_type = raw
+            .media_type
+            .or(content_type)
+            .unwrap_or_else(|| MEDIA_TYPE_OCI_MANIFEST.to_owned());
+        let digest = digest.ok_or(SeaplaneError::MissingImageDigest)?;
+
+        let manifests = match media_type.as_str() {
+            MEDIA_TYPE_OCI_INDEX | MEDIA_TYPE_DOCKER_MANIFEST_LIST => raw
+                .manifests
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|d| {
+                    d.platform
+                        .map(|platform| PlatformManifest { digest: d.digest, platform })
+                })
+                .collect(),
+            MEDIA_TYPE_OCI_MANIFEST | MEDIA_TYPE_DOCKER_MANIFEST => {
+                let config = raw
+                    .config
+                    .ok_or_else(|| SeaplaneError::UnsupportedImageManifest(media_type.clone()))?;
+                let url = self
+                    .endpoint_url
+                    .join(&format!("blobs/{}", config.digest))?;
+                let config = self
+                    .send(Method::GET, url, "application/json")?
+                    .json::<ImageConfig>()?;
+                vec![PlatformManifest {
+                    digest: digest.clone(),
+                    platform: Platform {
+                        architecture: config.architecture,
+                        os: config.os,
+                        variant: config.variant,
+                    },
+                }]
+            }
+            _ => return Err(SeaplaneError::UnsupportedImageManifest(media_type)),
+        };
+
+        Ok(ImageManifest { digest, media_type, manifests })
+    }
+}
+
+/// All manifest media types we understand, most preferred first
+fn manifest_accept() -> String {
+    [
+        MEDIA_TYPE_OCI_INDEX,
+        MEDIA_TYPE_DOCKER_MANIFEST_LIST,
+        MEDIA_TYPE_OCI_MANIFEST,
+        MEDIA_TYPE_DOCKER_MANIFEST,
+    ]
+    .join(", ")
+}
+
+fn digest_header(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(DIGEST_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(ToOwned::to_owned)
+}
+
+/// Returns the target of a `Link: <...>; rel="next"` header used by registries for pagination
+fn next_link(headers: &HeaderMap) -> Option<String> {
+    let link = headers.get(header::LINK)?.to_str().ok()?;
+    link.split(',').find_map(|l| {
+        let (target, params) = l.split_once(';')?;
+        if params.replace(' ', "") != "rel=\"next\"" {
+            return None;
+        }
+        Some(
+            target
+                .trim()
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_owned(),
+        )
+    })
+}
+
+/// Returns the parameters of a `WWW-Authenticate: Bearer realm="..",service=".."` challenge
+fn bearer_challenge(headers: &HeaderMap) -> Option<Vec<(String, String)>> {
+    let value = headers.get(header::WWW_AUTHENTICATE)?.to_str().ok()?;
+    let (scheme, params) = value.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+    Some(parse_auth_params(params))
+}
+
+// Parses `key="value",key=value` pairs, where quoted values may contain commas
+fn parse_auth_params(s: &str) -> Vec<(String, String)> {
+    let mut params = Vec::new();
+    let mut rest = s.trim();
+    while let Some((key, after)) = rest.split_once('=') {
+        let key = key.trim().trim_start_matches(',').trim().to_owned();
+        let (value, after) = if let Some(quoted) = after.strip_prefix('"') {
+            match quoted.split_once('"') {
+                Some((value, after)) => (value, after),
+                None => (quoted, ""),
+            }
+        } else {
+            after.split_once(',').unwrap_or((after, ""))
+        };
+        params.push((key, value.trim().to_owned()));
+        rest = after.trim_start().trim_start_matches(',');
+    }
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn auth_params() {
+        assert_eq!(
+            parse_auth_params(
+                r#"realm="https://auth.example.com/token",service="registry.example.com",scope="repository:foo/bar:pull,push""#
+            ),
+            vec![
+                ("realm".to_owned(), "https://auth.example.com/token".to_owned()),
+                ("service".to_owned(), "registry.example.com".to_owned()),
+                ("scope".to_owned(), "repository:foo/bar:pull,push".to_owned()),
+            ]
+        );
+        assert_eq!(
+            parse_auth_params("realm=https://auth.example.com/token, service=registry"),
+            vec![
+                ("realm".to_owned(), "https://auth.example.com/token".to_owned()),
+                ("service".to_owned(), "registry".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn basic_challenge_is_not_bearer() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::WWW_AUTHENTICATE, HeaderValue::from_static(r#"Basic realm="x""#));
+        assert_eq!(bearer_challenge(&headers), None);
+    }
+
+    #[test]
+    fn link_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::LINK,
+            HeaderValue::from_static(r#"</v2/foo/tags/list?n=2&last=b>; rel="next""#),
+        );
+        assert_eq!(next_link(&headers), Some("/v2/foo/tags/list?n=2&last=b".to_owned()));
+        assert_eq!(next_link(&HeaderMap::new()), None);
+    }
+}
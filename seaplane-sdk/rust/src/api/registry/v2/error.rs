@@ -0,0 +1,30 @@
+use reqwest::blocking::Response;
+use serde::Deserialize;
+
+use crate::{api::error::ApiError, error::Result};
+
+#[derive(Debug, Deserialize)]
+struct RegistryErrors {
+    errors: Vec<RegistryError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RegistryError {
+    message: String,
+}
+
+/// Maps a response error from a registry, which lists its errors as `{"errors": [{"code": "..",
+/// "message": ".."}]}`. Some responses (such as for `HEAD` requests) have no body at all.
+pub fn map_api_error(resp: Response) -> Result<Response> {
+    if let Err(source) = resp.error_for_status_ref() {
+        let kind = source.status().into();
+        let message = resp
+            .json::<RegistryErrors>()
+            .ok()
+            .and_then(|e| e.errors.into_iter().next())
+            .map(|e| e.message)
+            .unwrap_or_default();
+        return Err(ApiError { message, source, kind }.into());
+    }
+    Ok(resp)
+}
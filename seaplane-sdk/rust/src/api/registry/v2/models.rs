@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+
+/// An OCI image index, which lists one image manifest per platform
+pub const MEDIA_TYPE_OCI_INDEX: &str = "application/vnd.oci.image.index.v1+json";
+/// An OCI image manifest for a single platform
+pub const MEDIA_TYPE_OCI_MANIFEST: &str = "application/vnd.oci.image.manifest.v1+json";
+/// The Docker equivalent of an OCI image index
+pub const MEDIA_TYPE_DOCKER_MANIFEST_LIST: &str =
+    "application/vnd.docker.distribution.manifest.list.v2+json";
+/// The Docker equivalent of an OCI image manifest
+pub const MEDIA_TYPE_DOCKER_MANIFEST: &str = "application/vnd.docker.distribution.manifest.v2+json";
+
+/// The platform architecture and OS registries use for non-image manifests (such as build
+/// attestations) inside of an image index
+const UNKNOWN_PLATFORM: &str = "unknown";
+
+/// The only OS Seaplane runs containers on
+const LINUX: &str = "linux";
+
+/// The tags of a single repository
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagList {
+    pub name: String,
+    // Registries return `null` rather than an empty list for repositories without tags
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+}
+
+/// The platform an image was built for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Platform {
+    pub architecture: String,
+    pub os: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+}
+
+impl Platform {
+    /// Whether this describes an actual platform, and not a non-image manifest such as a build
+    /// attestation
+    pub fn is_known(&self) -> bool {
+        self.architecture != UNKNOWN_PLATFORM && self.os != UNKNOWN_PLATFORM
+    }
+}
+
+/// The image manifest for one platform
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlatformManifest {
+    pub digest: String,
+    pub platform: Platform,
+}
+
+/// An image manifest as returned by a registry, which is either the manifest of a single image, or
+/// an index pointing to the image manifests of several platforms.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageManifest {
+    /// The digest of the manifest (or index) itself
+    pub digest: String,
+    pub media_type: String,
+    /// The platforms the image is available for. Images without an index have exactly one.
+    pub manifests: Vec<PlatformManifest>,
+}
+
+impl ImageManifest {
+    /// Whether the manifest is an index of per-platform manifests
+    pub fn is_index(&self) -> bool {
+        self.media_type == MEDIA_TYPE_OCI_INDEX
+            || self.media_type == MEDIA_TYPE_DOCKER_MANIFEST_LIST
+    }
+
+    /// The architectures the image is available for on Linux, in the order listed by the registry.
+    /// Images for any other OS can't be run by Seaplane, so their architectures are left out.
+    pub fn architectures(&self) -> Vec<&str> {
+        let mut archs: Vec<&str> = Vec::new();
+        for m in self
+            .manifests
+            .iter()
+            .filter(|m| m.platform.is_known() && m.platform.os == LINUX)
+        {
+            if !archs.contains(&&*m.platform.architecture) {
+                archs.push(&m.platform.architecture);
+            }
+        }
+        archs
+    }
+}
+
+/// A manifest or index as it comes over the wire, the fields of both are optional since either
+/// may be returned
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RawManifest {
+    #[serde(default)]
+    pub media_type: Option<String>,
+    #[serde(default)]
+    pub manifests: Option<Vec<RawDescriptor>>,
+    #[serde(default)]
+    pub config: Option<RawDescriptor>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RawDescriptor {
+    pub digest: String,
+    #[serde(default)]
+    pub platform: Option<Platform>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn platform_manifest(arch: &str, os: &str) -> PlatformManifest {
+        PlatformManifest {
+            digest: format!("sha256:{arch}"),
+            platform: Platform { architecture: arch.into(), os: os.into(), variant: None },
+        }
+    }
+
+    #[test]
+    fn architectures_skip_unknown_duplicates_and_other_os() {
+        let manifest = ImageManifest {
+            digest: "sha256:abc".into(),
+            media_type: MEDIA_TYPE_OCI_INDEX.into(),
+            manifests: vec![
+                platform_manifest("amd64", "linux"),
+                platform_manifest("arm64", "linux"),
+                platform_manifest("amd64", "windows"),
+                platform_manifest("s390x", "windows"),
+                platform_manifest("unknown", "unknown"),
+            ],
+        };
+        assert!(manifest.is_index());
+        assert_eq!(manifest.architectures(), vec!["amd64", "arm64"]);
+    }
+
+    #[test]
+    fn tag_list_null_tags() {
+        let tags: TagList = serde_json::from_str(r#"{"name":"foo","tags":null}"#).unwrap();
+        assert_eq!(tags, TagList { name: "foo".into(), tags: None });
+    }
+}
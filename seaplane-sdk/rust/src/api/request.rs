@@ -7,6 +7,8 @@ use reqwest::{
     Url,
 };
 
+#[cfg(any(feature = "locks_api_v1", feature = "restrict_api_v1"))]
+use crate::api::ApiErrorKind;
 use crate::error::{Result, SeaplaneError};
 
 /// A builder struct for creating a ApiRequest which will then be used for
@@ -147,3 +149,26 @@ pub(crate) struct ApiRequest<T> {
     #[doc(hidden)]
     pub(crate) endpoint_url: Url,
 }
+
+/// Runs `f` with `req`, and if the API rejected its access token runs it once more with a new
+/// request from `new_request`, which replaces `req` from then on.
+///
+/// Access tokens are short lived, so anything which keeps making requests for longer than that
+/// (i.e. waiting on or renewing a lock) is handed a way to build requests with fresh tokens.
+#[cfg(any(feature = "locks_api_v1", feature = "restrict_api_v1"))]
+pub(crate) fn with_fresh_token<R, T, F>(
+    req: &mut R,
+    new_request: &mut F,
+    f: impl Fn(&R) -> Result<T>,
+) -> Result<T>
+where
+    F: FnMut() -> Result<R>,
+{
+    match f(req) {
+        Err(SeaplaneError::ApiResponse(ae)) if ae.kind == ApiErrorKind::Unauthorized => {
+            *req = new_request()?;
+            f(req)
+        }
+        res => res,
+    }
+}
@@ -1,6 +1,9 @@
 //! The `/restrict` endpoint APIs which allows working with [`Restriction`]s
 pub mod models;
-use std::str::FromStr;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
 
 pub use models::*;
 use reqwest::{
@@ -10,14 +13,19 @@ use reqwest::{
 
 use crate::{
     api::{
-        map_api_error, restrict::RESTRICT_API_URL, shared::v1::RangeQueryContext, ApiRequest,
-        RequestBuilder,
+        map_api_error, restrict::RESTRICT_API_URL, shared::v1::RangeQueryContext, with_fresh_token,
+        ApiRequest, RequestBuilder,
     },
     error::{Result, SeaplaneError},
 };
 
 static RESTRICT_API_BASE_PATH: &str = "v1/restrict/";
 
+/// The delay before the first poll of [`RestrictRequest::wait_until_enforced`]
+const WAIT_POLL_INITIAL_DELAY: Duration = Duration::from_millis(100);
+/// The maximum delay between polls of [`RestrictRequest::wait_until_enforced`]
+const WAIT_POLL_MAX_DELAY: Duration = Duration::from_secs(5);
+
 /// A builder struct for creating a [`RestrictRequest`] which will then be used for making a
 /// request against the `/restrict` APIs
 #[derive(Debug)]
@@ -148,9 +156,6 @@ impl RestrictRequest {
             }
 
             Some(RequestTarget::ApiRange { api, context }) => {
-                let api = Api::from_str(api)
-                    .map_err(|_| SeaplaneError::IncorrectRestrictRequestTarget)?;
-
                 let mut url = self.request.endpoint_url.join(&format!("{}/", api))?;
 
                 match context.from() {
@@ -196,6 +201,68 @@ impl RestrictRequest {
             .map_err(Into::into)
     }
 
+    /// Polls the restriction targeted by the requests built with `new_request` (which must be
+    /// `Single` requests) until its state is [`RestrictionState::Enforced`], returning the
+    /// enforced restriction. The delay between polls starts small and backs off up to five
+    /// seconds.
+    ///
+    /// If `timeout` is given and the restriction is still pending once it elapses,
+    /// [`SeaplaneError::RestrictionWaitTimeout`] is returned.
+    ///
+    /// Enforcement can take longer than any single access token is valid, so `new_request` is
+    /// called once up front, and again whenever the API rejects the access token of the current
+    /// request. It should build each request with a fresh token.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use seaplane::api::restrict::v1::{RestrictRequest, RestrictRequestBuilder};
+    ///
+    /// let resp = RestrictRequest::wait_until_enforced(
+    ///     || {
+    ///         RestrictRequestBuilder::new()
+    ///             .token("abc123_token")
+    ///             .single_restriction("config", "bW9ieQo")
+    ///             .build()
+    ///     },
+    ///     Some(Duration::from_secs(60)),
+    /// )
+    /// .unwrap();
+    /// dbg!(resp);
+    /// ```
+    pub fn wait_until_enforced<R>(
+        mut new_request: R,
+        timeout: Option<Duration>,
+    ) -> Result<Restriction>
+    where
+        R: FnMut() -> Result<RestrictRequest>,
+    {
+        let deadline = timeout.map(|t| Instant::now() + t);
+        let mut delay = WAIT_POLL_INITIAL_DELAY;
+        let mut req = new_request()?;
+
+        loop {
+            let restriction =
+                with_fresh_token(&mut req, &mut new_request, RestrictRequest::get_restriction)?;
+            if restriction.state == RestrictionState::Enforced {
+                return Ok(restriction);
+            }
+
+            let mut sleep = delay;
+            if let Some(deadline) = deadline {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    return Err(SeaplaneError::RestrictionWaitTimeout);
+                }
+                sleep = sleep.min(remaining);
+            }
+            thread::sleep(sleep);
+            delay = (delay * 2).min(WAIT_POLL_MAX_DELAY);
+        }
+    }
+
     /// Returns a single page of restrictions, starting from `from_api` and
     /// `from_key` combination.
     ///
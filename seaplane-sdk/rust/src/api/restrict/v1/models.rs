@@ -7,7 +7,10 @@ use serde::{
 use strum::{AsRefStr, EnumString, EnumVariantNames};
 
 use crate::{
-    api::shared::v1::{impl_deser_from_str, Provider, RangeQueryContext, Region},
+    api::shared::v1::{
+        impl_deser_from_str, impl_unknown_variant, Provider, RangeQueryContext, Region,
+        UnknownValue,
+    },
     base64::Base64Encoded,
     error::SeaplaneError,
     impl_base64,
@@ -43,26 +46,19 @@ pub struct Restriction {
     pub state: RestrictionState,
 }
 
-#[derive(
-    Clone,
-    Copy,
-    Debug,
-    PartialEq,
-    Eq,
-    Serialize,
-    AsRefStr,
-    strum::Display,
-    EnumString,
-    EnumVariantNames,
-)]
+#[derive(Clone, Debug, PartialEq, Eq, AsRefStr, EnumString, EnumVariantNames)]
 #[strum(ascii_case_insensitive)]
 #[non_exhaustive]
 pub enum Api {
     Config,
     Locks,
+    /// A value not known to this version of the SDK, kept as it was received
+    #[strum(default)]
+    Unknown(UnknownValue),
 }
 
 impl_deser_from_str!(Api);
+impl_unknown_variant!(Api);
 
 #[cfg(test)]
 mod test_api {
@@ -77,6 +73,14 @@ mod test_api {
         let api: Api = serde_json::from_str("\"CONFIG\"").unwrap();
         assert_eq!(api, Api::Config);
     }
+
+    #[test]
+    fn api_unknown_round_trips() {
+        let api: Api = serde_json::from_str("\"Queues\"").unwrap();
+        assert_eq!(api, Api::Unknown("Queues".into()));
+        assert_eq!(serde_json::to_string(&api).unwrap(), "\"Queues\"");
+        assert_eq!(serde_json::to_string(&Api::Locks).unwrap(), "\"Locks\"");
+    }
 }
 /// A key pointing to a directory, encoded in url-safe base64.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -94,16 +98,19 @@ impl fmt::Display for RestrictedDirectory {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.inner) }
 }
 
-#[derive(
-    Clone, Copy, Debug, PartialEq, Eq, Serialize, strum::Display, EnumString, EnumVariantNames,
-)]
+#[derive(Clone, Debug, PartialEq, Eq, AsRefStr, EnumString, EnumVariantNames)]
 #[strum(ascii_case_insensitive)]
+#[non_exhaustive]
 pub enum RestrictionState {
     Pending,
     Enforced,
+    /// A value not known to this version of the SDK, kept as it was received
+    #[strum(default)]
+    Unknown(UnknownValue),
 }
 
 impl_deser_from_str!(RestrictionState);
+impl_unknown_variant!(RestrictionState);
 
 /// A builder for creating a [`RestrictionDetails`] struct
 #[derive(Debug, Default)]
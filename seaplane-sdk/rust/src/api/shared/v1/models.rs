@@ -1,11 +1,17 @@
 //! This file contains models that are used across multiple APIs, like `Region`
 //! and `Provider`
 
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
 use serde::{
     de::{self, Deserializer},
-    Deserialize, Serialize,
+    Deserialize,
 };
-use strum::{EnumString, EnumVariantNames};
+use strum::{AsRefStr, EnumString, EnumVariantNames};
 
 /// Implements Deserialize using FromStr
 macro_rules! impl_deser_from_str {
@@ -23,20 +29,90 @@ macro_rules! impl_deser_from_str {
 }
 pub(crate) use impl_deser_from_str;
 
+/// A value not known to this version of the SDK, kept exactly as it was received.
+///
+/// Known values parse ignoring ASCII case, so unknown values are also compared, ordered and hashed
+/// ignoring ASCII case.
+#[derive(Debug, Clone)]
+pub struct UnknownValue(String);
+
+impl UnknownValue {
+    /// The value as it was received
+    pub fn as_str(&self) -> &str { &self.0 }
+
+    // The value's bytes with ASCII case ignored
+    fn folded(&self) -> impl Iterator<Item = u8> + '_ {
+        self.0.bytes().map(|b| b.to_ascii_lowercase())
+    }
+}
+
+impl From<&str> for UnknownValue {
+    fn from(s: &str) -> Self { Self(s.to_owned()) }
+}
+
+impl From<String> for UnknownValue {
+    fn from(s: String) -> Self { Self(s) }
+}
+
+impl AsRef<str> for UnknownValue {
+    fn as_ref(&self) -> &str { &self.0 }
+}
+
+impl fmt::Display for UnknownValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.pad(&self.0) }
+}
+
+impl PartialEq for UnknownValue {
+    fn eq(&self, other: &Self) -> bool { self.0.eq_ignore_ascii_case(&other.0) }
+}
+
+impl Eq for UnknownValue {}
+
+impl PartialOrd for UnknownValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for UnknownValue {
+    fn cmp(&self, other: &Self) -> Ordering { self.folded().cmp(other.folded()) }
+}
+
+impl Hash for UnknownValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.folded().for_each(|b| state.write_u8(b));
+        state.write_u8(0xFF);
+    }
+}
+
+/// Implements Display and Serialize for an enum with an `Unknown(UnknownValue)` variant, which
+/// should also be its `#[strum(default)]` so that values from a newer API still parse. Unknown
+/// values are displayed and serialized exactly as they were received so they round-trip, and known
+/// variants use their `AsRef<str>` name.
+macro_rules! impl_unknown_variant {
+    ($t:ty) => {
+        impl ::std::fmt::Display for $t {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                match self {
+                    Self::Unknown(s) => f.pad(s.as_str()),
+                    known => f.pad(known.as_ref()),
+                }
+            }
+        }
+
+        impl ::serde::Serialize for $t {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                serializer.collect_str(self)
+            }
+        }
+    };
+}
+pub(crate) use impl_unknown_variant;
+
 /// A backing cloud provider used to restrict data placement
 #[derive(
-    Clone,
-    Copy,
-    Debug,
-    Hash,
-    Serialize,
-    Eq,
-    PartialEq,
-    PartialOrd,
-    Ord,
-    strum::Display,
-    EnumString,
-    EnumVariantNames,
+    Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord, AsRefStr, EnumString, EnumVariantNames,
 )]
 #[allow(clippy::upper_case_acronyms)]
 #[strum(ascii_case_insensitive)]
@@ -47,9 +123,13 @@ pub enum Provider {
     DigitalOcean,
     Equinix,
     GCP,
+    /// A value not known to this version of the SDK, kept as it was received
+    #[strum(default)]
+    Unknown(UnknownValue),
 }
 
 impl_deser_from_str!(Provider);
+impl_unknown_variant!(Provider);
 
 #[cfg(test)]
 mod test_provider {
@@ -64,11 +144,32 @@ mod test_provider {
         let provider: Provider = serde_json::from_str("\"AWS\"").unwrap();
         assert_eq!(provider, Provider::AWS);
     }
+
+    #[test]
+    fn provider_unknown_round_trips() {
+        let provider: Provider = serde_json::from_str("\"Hetzner\"").unwrap();
+        assert_eq!(provider, Provider::Unknown("Hetzner".into()));
+        assert_eq!(provider.to_string(), "Hetzner");
+        assert_eq!(serde_json::to_string(&provider).unwrap(), "\"Hetzner\"");
+        assert_eq!(serde_json::to_string(&Provider::GCP).unwrap(), "\"GCP\"");
+    }
+
+    #[test]
+    fn provider_unknown_ignores_case() {
+        let provider: Provider = serde_json::from_str("\"Hetzner\"").unwrap();
+        assert_eq!(provider, Provider::Unknown("HETZNER".into()));
+        assert_eq!(provider.to_string(), "Hetzner");
+
+        let providers: std::collections::HashSet<_> = ["hetzner".parse::<Provider>().unwrap()]
+            .into_iter()
+            .collect();
+        assert!(providers.contains(&provider));
+    }
 }
 
 /// A regulatory region used to restrict data placement
 #[derive(
-    strum::Display, EnumString, Debug, Serialize, Hash, Eq, PartialEq, PartialOrd, Ord, Copy, Clone,
+    AsRefStr, EnumString, EnumVariantNames, Debug, Hash, Eq, PartialEq, PartialOrd, Ord, Clone,
 )]
 #[allow(clippy::upper_case_acronyms)]
 #[strum(ascii_case_insensitive)]
@@ -92,9 +193,13 @@ pub enum Region {
     XS,
     /// The UK
     XU,
+    /// A value not known to this version of the SDK, kept as it was received
+    #[strum(default)]
+    Unknown(UnknownValue),
 }
 
 impl_deser_from_str!(Region);
+impl_unknown_variant!(Region);
 
 #[cfg(test)]
 mod test_region {
@@ -109,4 +214,12 @@ mod test_region {
         let region: Region = serde_json::from_str("\"XN\"").unwrap();
         assert_eq!(region, Region::XN);
     }
+
+    #[test]
+    fn region_unknown_round_trips() {
+        let region: Region = serde_json::from_str("\"XM\"").unwrap();
+        assert_eq!(region, Region::Unknown("XM".into()));
+        assert_eq!(serde_json::to_string(&region).unwrap(), "\"XM\"");
+        assert_eq!(serde_json::to_string(&Region::XE).unwrap(), "\"XE\"");
+    }
 }
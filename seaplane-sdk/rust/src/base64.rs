@@ -34,7 +34,7 @@ macro_rules! impl_base64 {
 }
 
 /// Holds a [URL-safe base64 encoded](https://datatracker.ietf.org/doc/html/rfc4648#section-5) string
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
 pub(crate) struct Base64Encoded(String);
 
 impl fmt::Display for Base64Encoded {
@@ -2,6 +2,8 @@
 
 use thiserror::Error;
 
+#[cfg(feature = "metadata_api_v1")]
+use crate::api::metadata::v1::MetadataValidationError;
 use crate::api::ApiError;
 
 pub type Result<T> = std::result::Result<T, SeaplaneError>;
@@ -44,6 +46,29 @@ pub enum SeaplaneError {
     IncorrectLocksRequestTarget,
     #[error("restrict requests must target all restrictions, an api, or an api and a key")]
     IncorrectRestrictRequestTarget,
+    #[error("metadata encryption: {0}")]
+    MetadataEncryption(String),
+    #[error("no encryption key with version {0} is available to decrypt the value")]
+    MissingEncryptionKey(u32),
+    #[cfg(feature = "metadata_api_v1")]
+    #[error("invalid metadata: {0}")]
+    InvalidMetadata(#[from] MetadataValidationError),
+    #[error("timed out waiting to acquire the lock")]
+    LockWaitTimeout,
+    #[error("timed out waiting for the restriction to be enforced")]
+    RestrictionWaitTimeout,
+    #[error("a semaphore must have at least one slot")]
+    EmptySemaphore,
+    #[error("all {0} slots of the semaphore are held")]
+    SemaphoreFull(u32),
+    #[error("request did not include the required image reference")]
+    MissingImageReference,
+    #[error("the registry did not return a digest for the image")]
+    MissingImageDigest,
+    #[error("the registry did not hand out a token to access the image")]
+    MissingRegistryToken,
+    #[error("unsupported image manifest media type '{0}'")]
+    UnsupportedImageManifest(String),
     #[error("the API returned an error status")]
     ApiResponse(#[from] ApiError),
 }
@@ -81,6 +106,18 @@ impl PartialEq for SeaplaneError {
             IncorrectMetadataRequestTarget => matches!(rhs, IncorrectMetadataRequestTarget),
             IncorrectLocksRequestTarget => matches!(rhs, IncorrectLocksRequestTarget),
             IncorrectRestrictRequestTarget => matches!(rhs, IncorrectRestrictRequestTarget),
+            MetadataEncryption(_) => matches!(rhs, MetadataEncryption(_)),
+            MissingEncryptionKey(v) => matches!(rhs, MissingEncryptionKey(ov) if v == ov),
+            #[cfg(feature = "metadata_api_v1")]
+            InvalidMetadata(e) => matches!(rhs, InvalidMetadata(oe) if e == oe),
+            LockWaitTimeout => matches!(rhs, LockWaitTimeout),
+            RestrictionWaitTimeout => matches!(rhs, RestrictionWaitTimeout),
+            EmptySemaphore => matches!(rhs, EmptySemaphore),
+            SemaphoreFull(s) => matches!(rhs, SemaphoreFull(os) if s == os),
+            MissingImageReference => matches!(rhs, MissingImageReference),
+            MissingImageDigest => matches!(rhs, MissingImageDigest),
+            MissingRegistryToken => matches!(rhs, MissingRegistryToken),
+            UnsupportedImageManifest(m) => matches!(rhs, UnsupportedImageManifest(om) if m == om),
             ApiResponse(ae) => match rhs {
                 ApiResponse(oae) => ae == oae,
                 _ => false,
@@ -1,10 +1,24 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
 use httpmock::{prelude::*, Method, Then, When};
 use once_cell::sync::Lazy;
-use seaplane::api::{
-    locks::v1::{
-        HeldLock, LockId, LockInfo, LockInfoInner, LockInfoRange, LockName, LocksRequestBuilder,
+use seaplane::{
+    api::{
+        locks::v1::{
+            HeldLock, LeaderElection, LeadershipEvent, LockGuard, LockId, LockInfo, LockInfoInner,
+            LockInfoRange, LockName, LocksRequest, LocksRequestBuilder, Semaphore,
+        },
+        shared::v1::{Directory, RangeQueryContext},
+        ApiErrorKind,
     },
-    shared::v1::{Directory, RangeQueryContext},
+    error::SeaplaneError,
 };
 use serde_json::json;
 
@@ -59,6 +73,153 @@ fn acquire_lock() {
     assert_eq!(lock, resp);
 }
 
+fn conflict(then: Then) -> Then {
+    then.status(409)
+        .header("content-type", "application/json")
+        .json_body(json!({"title": "Conflict", "status": 409, "detail": "lock is held"}))
+}
+
+fn lock_info(when: When, encoded_name: &str) -> When {
+    when.method(GET)
+        .path(format!("/v1/locks/base64:{encoded_name}"))
+        .header("authorization", "Bearer abc123")
+}
+
+fn lock_info_json(encoded_name: &str) -> serde_json::Value {
+    json!({
+        "name": encoded_name,
+        "id": "D4lbVpdBE_U",
+        "info": {"ttl": 5, "client-id": "other-client", "ip": "10.0.0.1"}
+    })
+}
+
+#[test]
+fn acquire_blocking_waits() {
+    let mut held = MOCK_SERVER.mock(|w, t| {
+        when(w, POST, "/v1/locks/base64:d2FpdA").query_param("ttl", "10");
+        conflict(t);
+    });
+    let info = MOCK_SERVER.mock(|w, t| {
+        lock_info(w, "d2FpdA");
+        then(t, lock_info_json("d2FpdA"));
+    });
+
+    // Release the lock after a few retries
+    let releaser = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(500));
+        held.delete();
+        MOCK_SERVER.mock(|w, t| {
+            when(w, POST, "/v1/locks/base64:d2FpdA").query_param("ttl", "10");
+            then(t, json!({"id": "D4lbVpdBE_V", "sequencer": 5}));
+        })
+    });
+
+    let mut holders = Vec::new();
+    let resp = LocksRequest::acquire_blocking(
+        || partial_build().encoded_lock_name("d2FpdA").build(),
+        10,
+        "test-client",
+        Some(Duration::from_secs(10)),
+        |info| holders.push(info.info.client_id.clone()),
+    )
+    .unwrap();
+    let acquired = releaser.join().unwrap();
+
+    acquired.assert();
+    assert!(info.hits() >= 2);
+    // The same holder is only reported once
+    assert_eq!(holders, vec!["other-client".to_string()]);
+    assert_eq!(resp.sequencer(), 5);
+}
+
+#[test]
+fn acquire_blocking_timeout() {
+    let held = MOCK_SERVER.mock(|w, t| {
+        when(w, POST, "/v1/locks/base64:dGltZW91dA").query_param("ttl", "10");
+        conflict(t);
+    });
+    let _info = MOCK_SERVER.mock(|w, t| {
+        lock_info(w, "dGltZW91dA");
+        then(t, lock_info_json("dGltZW91dA"));
+    });
+
+    let resp = LocksRequest::acquire_blocking(
+        || partial_build().encoded_lock_name("dGltZW91dA").build(),
+        10,
+        "test-client",
+        Some(Duration::from_millis(300)),
+        |_| (),
+    );
+
+    assert_eq!(resp, Err(SeaplaneError::LockWaitTimeout));
+    assert!(held.hits() >= 2);
+}
+
+fn unauthorized(then: Then) -> Then {
+    then.status(401)
+        .header("content-type", "application/json")
+        .json_body(json!({"title": "Unauthorized", "status": 401, "detail": "token expired"}))
+}
+
+// Builds requests for `encoded_name`, the first with a token which has since expired
+fn expiring_requests(
+    encoded_name: &'static str,
+    built: Arc<AtomicUsize>,
+) -> impl FnMut() -> seaplane::error::Result<LocksRequest> + Send + 'static {
+    move || {
+        let token = match built.fetch_add(1, Ordering::SeqCst) {
+            0 => "expired",
+            _ => "abc123",
+        };
+        LocksRequestBuilder::new()
+            .token(token)
+            .base_url(MOCK_SERVER.base_url())
+            .encoded_lock_name(encoded_name)
+            .build()
+    }
+}
+
+#[test]
+fn acquire_blocking_refreshes_token() {
+    // The lock is held when first tried, and the token expires while waiting on it
+    let _held = MOCK_SERVER.mock(|w, t| {
+        w.method(POST)
+            .path("/v1/locks/base64:ZXhwaXJl")
+            .header("authorization", "Bearer expired");
+        conflict(t);
+    });
+    let expired = MOCK_SERVER.mock(|w, t| {
+        w.method(GET)
+            .path("/v1/locks/base64:ZXhwaXJl")
+            .header("authorization", "Bearer expired");
+        unauthorized(t);
+    });
+    let info = MOCK_SERVER.mock(|w, t| {
+        lock_info(w, "ZXhwaXJl");
+        then(t, lock_info_json("ZXhwaXJl"));
+    });
+    let acquire = MOCK_SERVER.mock(|w, t| {
+        when(w, POST, "/v1/locks/base64:ZXhwaXJl");
+        then(t, json!({"id": "D4lbVpdBE_V", "sequencer": 5}));
+    });
+
+    let built = Arc::new(AtomicUsize::new(0));
+    let resp = LocksRequest::acquire_blocking(
+        expiring_requests("ZXhwaXJl", Arc::clone(&built)),
+        10,
+        "test-client",
+        Some(Duration::from_secs(10)),
+        |_| (),
+    )
+    .unwrap();
+
+    expired.assert();
+    info.assert();
+    acquire.assert();
+    assert_eq!(built.load(Ordering::SeqCst), 2);
+    assert_eq!(resp.sequencer(), 5);
+}
+
 // PATCH /locks/base64:{key}?id={id}&ttl={ttl}
 #[test]
 fn renew_lock() {
@@ -105,6 +266,335 @@ fn release_lock() {
     assert!(resp.is_ok())
 }
 
+#[test]
+fn lock_guard_renews_and_releases() {
+    let acquire = MOCK_SERVER.mock(|w, t| {
+        when(w, POST, "/v1/locks/base64:Z3VhcmQ")
+            .query_param("ttl", "1")
+            .query_param("client-id", "test-client");
+        then(t, json!({"id": "D4lbVpdBE_U", "sequencer": 3}));
+    });
+    let renew = MOCK_SERVER.mock(|w, t| {
+        when(w, Method::PATCH, "/v1/locks/base64:Z3VhcmQ")
+            .query_param("id", "D4lbVpdBE_U")
+            .query_param("ttl", "1");
+        then(t, json!({"status": 200, "title": "Ok"}));
+    });
+    let release = MOCK_SERVER.mock(|w, t| {
+        when(w, Method::DELETE, "/v1/locks/base64:Z3VhcmQ").query_param("id", "D4lbVpdBE_U");
+        then(t, json!({"status": 200, "title": "Ok"}));
+    });
+
+    let guard = LockGuard::acquire(
+        || partial_build().encoded_lock_name("Z3VhcmQ").build(),
+        1,
+        "test-client",
+    )
+    .unwrap();
+    acquire.assert();
+    assert_eq!(guard.sequencer(), 3);
+
+    // Renewed every third of the TTL
+    thread::sleep(Duration::from_millis(800));
+    assert!(renew.hits() >= 1);
+    assert!(!guard.is_lost());
+    release.assert_hits(0);
+
+    drop(guard);
+    release.assert();
+}
+
+#[test]
+fn lock_guard_signals_loss() {
+    let _acquire = MOCK_SERVER.mock(|w, t| {
+        when(w, POST, "/v1/locks/base64:bG9zdA")
+            .query_param("ttl", "1")
+            .query_param("client-id", "test-client");
+        then(t, json!({"id": "D4lbVpdBE_U", "sequencer": 4}));
+    });
+    let renew = MOCK_SERVER.mock(|w, t| {
+        when(w, Method::PATCH, "/v1/locks/base64:bG9zdA").query_param("id", "D4lbVpdBE_U");
+        t.status(409)
+            .header("content-type", "application/json")
+            .json_body(json!({"title": "Conflict", "status": 409, "detail": "lock is held"}));
+    });
+    let release = MOCK_SERVER.mock(|w, t| {
+        when(w, Method::DELETE, "/v1/locks/base64:bG9zdA").query_param("id", "D4lbVpdBE_U");
+        then(t, json!({"status": 200, "title": "Ok"}));
+    });
+
+    let mut guard = LockGuard::acquire(
+        || partial_build().encoded_lock_name("bG9zdA").build(),
+        1,
+        "test-client",
+    )
+    .unwrap();
+    let lost = guard.take_loss_receiver().unwrap();
+    assert!(guard.take_loss_receiver().is_none());
+
+    assert!(lost.recv_timeout(Duration::from_secs(5)).is_ok());
+    renew.assert();
+    assert!(guard.is_lost());
+
+    // A lost lock isn't released since someone else may hold it now
+    guard.release().unwrap();
+    release.assert_hits(0);
+}
+
+#[test]
+fn lock_guard_refreshes_token() {
+    // The token is still good when acquiring, but expires before the first renewal
+    let acquire = MOCK_SERVER.mock(|w, t| {
+        w.method(POST)
+            .path("/v1/locks/base64:cmVuZXc")
+            .header("authorization", "Bearer expired");
+        then(t, json!({"id": "D4lbVpdBE_U", "sequencer": 6}));
+    });
+    let expired = MOCK_SERVER.mock(|w, t| {
+        w.method(Method::PATCH)
+            .path("/v1/locks/base64:cmVuZXc")
+            .header("authorization", "Bearer expired");
+        unauthorized(t);
+    });
+    let renew = MOCK_SERVER.mock(|w, t| {
+        when(w, Method::PATCH, "/v1/locks/base64:cmVuZXc").query_param("id", "D4lbVpdBE_U");
+        then(t, json!({"status": 200, "title": "Ok"}));
+    });
+    let release = MOCK_SERVER.mock(|w, t| {
+        when(w, Method::DELETE, "/v1/locks/base64:cmVuZXc").query_param("id", "D4lbVpdBE_U");
+        then(t, json!({"status": 200, "title": "Ok"}));
+    });
+
+    let built = Arc::new(AtomicUsize::new(0));
+    let guard =
+        LockGuard::acquire(expiring_requests("cmVuZXc", Arc::clone(&built)), 1, "test-client")
+            .unwrap();
+    acquire.assert();
+
+    thread::sleep(Duration::from_millis(800));
+    expired.assert();
+    assert!(renew.hits() >= 1);
+    assert!(!guard.is_lost());
+
+    // Released with the new token
+    guard.release().unwrap();
+    release.assert();
+    assert_eq!(built.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn leader_election() {
+    let mut held = MOCK_SERVER.mock(|w, t| {
+        when(w, POST, "/v1/locks/base64:bGVhZGVy").query_param("client-id", "replica-1");
+        conflict(t);
+    });
+
+    let election = LeaderElection::campaign(
+        || partial_build().encoded_lock_name("bGVhZGVy").build(),
+        1,
+        "replica-1",
+    )
+    .unwrap();
+
+    // Another replica is the leader
+    thread::sleep(Duration::from_millis(500));
+    assert!(!election.is_leader());
+    assert!(held.hits() >= 1);
+    assert!(election.events().try_recv().is_err());
+
+    // The other replica steps down
+    held.delete();
+    let acquire = MOCK_SERVER.mock(|w, t| {
+        when(w, POST, "/v1/locks/base64:bGVhZGVy").query_param("client-id", "replica-1");
+        then(t, json!({"id": "D4lbVpdBE_U", "sequencer": 7}));
+    });
+    let mut renew = MOCK_SERVER.mock(|w, t| {
+        when(w, Method::PATCH, "/v1/locks/base64:bGVhZGVy").query_param("id", "D4lbVpdBE_U");
+        then(t, json!({"status": 200, "title": "Ok"}));
+    });
+    let release = MOCK_SERVER.mock(|w, t| {
+        when(w, Method::DELETE, "/v1/locks/base64:bGVhZGVy").query_param("id", "D4lbVpdBE_U");
+        then(t, json!({"status": 200, "title": "Ok"}));
+    });
+
+    let event = election
+        .events()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap();
+    assert_eq!(
+        event,
+        LeadershipEvent::Elected(HeldLock::new(
+            LockName::from_encoded("bGVhZGVy"),
+            LockId::from_encoded("D4lbVpdBE_U"),
+            7
+        ))
+    );
+    assert!(election.is_leader());
+    acquire.assert();
+
+    // Leadership is lost when the lock can't be renewed
+    renew.delete();
+    let _lost = MOCK_SERVER.mock(|w, t| {
+        when(w, Method::PATCH, "/v1/locks/base64:bGVhZGVy").query_param("id", "D4lbVpdBE_U");
+        conflict(t);
+    });
+    let event = election
+        .events()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap();
+    assert!(matches!(event, LeadershipEvent::Lost(_)));
+
+    // And regained since the lock is free again
+    let event = election
+        .events()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap();
+    assert!(matches!(event, LeadershipEvent::Elected(_)));
+    assert!(election.is_leader());
+
+    release.assert_hits(0);
+    election.step_down().unwrap();
+    release.assert();
+}
+
+#[test]
+fn leader_election_refreshes_token() {
+    let expired = MOCK_SERVER.mock(|w, t| {
+        w.method(POST)
+            .path("/v1/locks/base64:cm90YXRl")
+            .header("authorization", "Bearer expired");
+        unauthorized(t);
+    });
+    let acquire = MOCK_SERVER.mock(|w, t| {
+        when(w, POST, "/v1/locks/base64:cm90YXRl");
+        then(t, json!({"id": "D4lbVpdBE_U", "sequencer": 1}));
+    });
+    let release = MOCK_SERVER.mock(|w, t| {
+        when(w, Method::DELETE, "/v1/locks/base64:cm90YXRl").query_param("id", "D4lbVpdBE_U");
+        then(t, json!({"status": 200, "title": "Ok"}));
+    });
+
+    // The first request carries a token which has since expired
+    let built = Arc::new(AtomicUsize::new(0));
+    let election = LeaderElection::campaign(
+        expiring_requests("cm90YXRl", Arc::clone(&built)),
+        30,
+        "replica-1",
+    )
+    .unwrap();
+
+    let event = election
+        .events()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap();
+    assert!(matches!(event, LeadershipEvent::Elected(_)));
+    assert_eq!(built.load(Ordering::SeqCst), 2);
+    expired.assert();
+    acquire.assert();
+
+    election.step_down().unwrap();
+    release.assert();
+}
+
+#[test]
+fn leader_election_failed() {
+    let denied = MOCK_SERVER.mock(|w, t| {
+        when(w, POST, "/v1/locks/base64:ZGVuaWVk");
+        t.status(403)
+            .header("content-type", "application/json")
+            .json_body(json!({"title": "Forbidden", "status": 403, "detail": "access denied"}));
+    });
+
+    let election = LeaderElection::campaign(
+        || partial_build().encoded_lock_name("ZGVuaWVk").build(),
+        30,
+        "replica-1",
+    )
+    .unwrap();
+
+    // Unlike the lock being held, other errors are passed on
+    let event = election
+        .events()
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap();
+    match event {
+        LeadershipEvent::Failed(SeaplaneError::ApiResponse(ae)) => {
+            assert_eq!(ae.kind, ApiErrorKind::Forbidden)
+        }
+        e => panic!("unexpected event {e:?}"),
+    }
+    assert!(!election.is_leader());
+    denied.assert();
+
+    election.step_down().unwrap();
+}
+
+fn held_slot(encoded_name: &str) -> serde_json::Value {
+    json!({
+        "name": encoded_name,
+        "id": "D4lbVpdBE_U",
+        "info": {"ttl": 5, "client-id": "other-client", "ip": "10.0.0.1"}
+    })
+}
+
+#[test]
+fn semaphore_acquire() {
+    // Slot 0 is held, and "sem/other" isn't a slot at all
+    let list = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/locks/base64:c2Vt/");
+        then(t, json!({"next": null, "infos": [held_slot("c2VtLzA"), held_slot("c2VtL290aGVy")]}));
+    });
+    // Slot 1 was taken after the listing
+    let slot1 = MOCK_SERVER.mock(|w, t| {
+        when(w, POST, "/v1/locks/base64:c2VtLzE");
+        conflict(t);
+    });
+    let slot2 = MOCK_SERVER.mock(|w, t| {
+        when(w, POST, "/v1/locks/base64:c2VtLzI").query_param("ttl", "10");
+        then(t, json!({"id": "D4lbVpdBE_V", "sequencer": 1}));
+    });
+
+    let req = partial_build().encoded_lock_name("c2Vt").build().unwrap();
+    let semaphore = Semaphore::new(&req, 3).unwrap();
+    assert_eq!(semaphore.slot_name(2), LockName::from_unencoded("sem/2"));
+    assert_eq!(semaphore.held_slots().unwrap().len(), 1);
+
+    let lock = semaphore.try_acquire(10, "test-client").unwrap();
+    assert_eq!(
+        lock,
+        HeldLock::new(LockName::from_encoded("c2VtLzI"), LockId::from_encoded("D4lbVpdBE_V"), 1)
+    );
+    list.assert_hits(2);
+    slot1.assert();
+    slot2.assert();
+
+    assert_eq!(Semaphore::new(&req, 0).unwrap_err(), SeaplaneError::EmptySemaphore);
+}
+
+#[test]
+fn semaphore_full() {
+    let list = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/locks/base64:ZnVsbA/");
+        then(t, json!({"next": null, "infos": [held_slot("ZnVsbC8w"), held_slot("ZnVsbC8x")]}));
+    });
+    let acquire = MOCK_SERVER.mock(|w, t| {
+        w.method(POST).path_contains("ZnVsbC8");
+        then(t, json!({"id": "D4lbVpdBE_V", "sequencer": 1}));
+    });
+
+    let req = partial_build().encoded_lock_name("ZnVsbA").build().unwrap();
+    let semaphore = Semaphore::new(&req, 2).unwrap();
+    assert_eq!(
+        semaphore
+            .acquire(|| partial_build().encoded_lock_name("ZnVsbA").build(), 10, "test-client")
+            .unwrap_err(),
+        SeaplaneError::SemaphoreFull(2)
+    );
+
+    list.assert();
+    acquire.assert_hits(0);
+}
+
 // GET /locks/base64:{key}
 #[test]
 fn list_single_lock() {
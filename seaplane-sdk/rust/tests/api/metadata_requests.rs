@@ -1,8 +1,11 @@
 use httpmock::{prelude::*, Method, Then, When};
 use once_cell::sync::Lazy;
-use seaplane::api::{
-    metadata::v1::{Key, KeyValue, MetadataRequestBuilder, Value},
-    shared::v1::{Directory, RangeQueryContext},
+use seaplane::{
+    api::{
+        metadata::v1::{Key, KeyValue, MetadataRequestBuilder, MetadataValidationError, Value},
+        shared::v1::{Directory, RangeQueryContext},
+    },
+    error::SeaplaneError,
 };
 use serde_json::json;
 
@@ -133,6 +136,27 @@ fn put_value_unencoded() {
     assert!(resp.is_ok())
 }
 
+// PUT /config/base64:{key}
+#[test]
+fn put_value_invalid_key() {
+    let mock = MOCK_SERVER.mock(|w, t| {
+        // foo/
+        when(w, PUT, "/v1/config/base64:Zm9vLw");
+        then(t, json!({"status": 200, "title": "Ok"}));
+    });
+
+    let req = partial_build().encoded_key("Zm9vLw").build().unwrap();
+    let resp = req.put_value_unencoded("bar");
+
+    // The request is rejected before it's sent
+    mock.assert_hits(0);
+
+    assert_eq!(
+        resp.unwrap_err(),
+        SeaplaneError::InvalidMetadata(MetadataValidationError::TrailingSeparator)
+    );
+}
+
 // DELETE /config/base64:{key}
 #[test]
 fn delete_value() {
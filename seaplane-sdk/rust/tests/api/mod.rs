@@ -7,6 +7,8 @@ mod formation_requests;
 mod locks_requests;
 #[cfg(feature = "metadata_api_v1")]
 mod metadata_requests;
+#[cfg(feature = "registry_api_v2")]
+mod registry_requests;
 #[cfg(feature = "restrict_api_v1")]
 mod restrict_requests;
 mod token_requests;
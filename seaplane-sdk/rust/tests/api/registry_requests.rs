@@ -0,0 +1,230 @@
+use httpmock::{prelude::*, Method};
+use once_cell::sync::Lazy;
+use seaplane::{
+    api::registry::v2::{
+        ImageManifest, Platform, PlatformManifest, RegistryRequest, RegistryRequestBuilder,
+        MEDIA_TYPE_OCI_INDEX, MEDIA_TYPE_OCI_MANIFEST,
+    },
+    error::SeaplaneError,
+};
+use serde_json::json;
+
+// To be used with httpmock standalone server for dev testing
+// MockServer::connect("127.0.0.1:5000")
+// static MOCK_SERVER: Lazy<MockServer> = Lazy::new(|| MockServer::connect("127.0.0.1:5000"));
+static MOCK_SERVER: Lazy<MockServer> = Lazy::new(MockServer::start);
+
+const DIGEST: &str = "sha256:7cc4b5aefd1d0cadf8d97d4350462ba51c694ebca145b08d7d41b41acc8db5aa";
+
+fn build(reference: &str) -> RegistryRequest {
+    RegistryRequestBuilder::new()
+        .reference(reference.parse().unwrap())
+        .base_url(MOCK_SERVER.base_url())
+        .build()
+        .unwrap()
+}
+
+// HEAD /v2/{name}/manifests/{reference}
+#[test]
+fn resolve_digest() {
+    let mock = MOCK_SERVER.mock(|w, t| {
+        w.method(Method::HEAD)
+            .path("/v2/resolve/foo/manifests/v1")
+            .header_exists("accept");
+        t.status(200).header("docker-content-digest", DIGEST);
+    });
+
+    let mut req = build("localhost:5000/resolve/foo:v1");
+    assert_eq!(req.resolve_digest().unwrap(), DIGEST);
+    mock.assert();
+}
+
+#[test]
+fn resolve_digest_defaults_to_latest() {
+    let mock = MOCK_SERVER.mock(|w, t| {
+        w.method(Method::HEAD)
+            .path("/v2/resolve/latest/manifests/latest");
+        t.status(200).header("docker-content-digest", DIGEST);
+    });
+
+    let mut req = build("localhost/resolve/latest");
+    assert_eq!(req.resolve_digest().unwrap(), DIGEST);
+    mock.assert();
+}
+
+#[test]
+fn resolve_digest_not_found() {
+    let mock = MOCK_SERVER.mock(|w, t| {
+        w.method(Method::HEAD)
+            .path("/v2/resolve/missing/manifests/v1");
+        t.status(404);
+    });
+
+    let mut req = build("localhost/resolve/missing:v1");
+    assert!(matches!(req.resolve_digest(), Err(SeaplaneError::ApiResponse(_))));
+    mock.assert();
+}
+
+// GET /v2/{name}/tags/list
+#[test]
+fn get_tags_paginated() {
+    let first = MOCK_SERVER.mock(|w, t| {
+        w.method(GET)
+            .path("/v2/tags/foo/tags/list")
+            .matches(|req| !req.query_params.iter().flatten().any(|(k, _)| k == "last"));
+        t.status(200)
+            .header("link", r#"</v2/tags/foo/tags/list?n=2&last=v2>; rel="next""#)
+            .json_body(json!({"name": "tags/foo", "tags": ["v1", "v2"]}));
+    });
+    let second = MOCK_SERVER.mock(|w, t| {
+        w.method(GET)
+            .path("/v2/tags/foo/tags/list")
+            .query_param("last", "v2");
+        t.status(200)
+            .json_body(json!({"name": "tags/foo", "tags": ["v3"]}));
+    });
+
+    let mut req = build("localhost/tags/foo");
+    assert_eq!(req.get_tags().unwrap(), vec!["v1", "v2", "v3"]);
+    first.assert();
+    second.assert();
+}
+
+// Registries may hand out tokens for anonymous pulls
+#[test]
+fn get_tags_token_challenge() {
+    let challenge = MOCK_SERVER.mock(|w, t| {
+        w.method(GET).path("/v2/auth/foo/tags/list").matches(|req| {
+            !req.headers
+                .iter()
+                .flatten()
+                .any(|(k, _)| k == "authorization")
+        });
+        t.status(401).header(
+            "www-authenticate",
+            format!(
+                r#"Bearer realm="{}",service="registry",scope="repository:auth/foo:pull""#,
+                MOCK_SERVER.url("/token")
+            ),
+        );
+    });
+    let token = MOCK_SERVER.mock(|w, t| {
+        w.method(GET)
+            .path("/token")
+            .query_param("service", "registry")
+            .query_param("scope", "repository:auth/foo:pull");
+        t.status(200).json_body(json!({"token": "tkn"}));
+    });
+    let tags = MOCK_SERVER.mock(|w, t| {
+        w.method(GET)
+            .path("/v2/auth/foo/tags/list")
+            .header("authorization", "Bearer tkn");
+        t.status(200)
+            .json_body(json!({"name": "auth/foo", "tags": ["latest"]}));
+    });
+
+    let mut req = build("localhost/auth/foo");
+    assert_eq!(req.get_tags().unwrap(), vec!["latest"]);
+    challenge.assert();
+    token.assert();
+    tags.assert();
+}
+
+// GET /v2/{name}/manifests/{reference}
+#[test]
+fn get_manifest_index() {
+    let mock = MOCK_SERVER.mock(|w, t| {
+        w.method(GET).path("/v2/manifest/index/manifests/latest");
+        t.status(200)
+            .header("content-type", MEDIA_TYPE_OCI_INDEX)
+            .header("docker-content-digest", DIGEST)
+            .json_body(json!({
+                "schemaVersion": 2,
+                "mediaType": MEDIA_TYPE_OCI_INDEX,
+                "manifests": [
+                    {
+                        "mediaType": MEDIA_TYPE_OCI_MANIFEST,
+                        "digest": "sha256:aaa",
+                        "size": 1,
+                        "platform": {"architecture": "amd64", "os": "linux"}
+                    },
+                    {
+                        "mediaType": MEDIA_TYPE_OCI_MANIFEST,
+                        "digest": "sha256:bbb",
+                        "size": 1,
+                        "platform": {"architecture": "arm64", "os": "linux", "variant": "v8"}
+                    }
+                ]
+            }));
+    });
+
+    let mut req = build("localhost/manifest/index:latest");
+    let manifest = req.get_manifest().unwrap();
+    mock.assert();
+    assert_eq!(
+        manifest,
+        ImageManifest {
+            digest: DIGEST.into(),
+            media_type: MEDIA_TYPE_OCI_INDEX.into(),
+            manifests: vec![
+                PlatformManifest {
+                    digest: "sha256:aaa".into(),
+                    platform: Platform {
+                        architecture: "amd64".into(),
+                        os: "linux".into(),
+                        variant: None
+                    }
+                },
+                PlatformManifest {
+                    digest: "sha256:bbb".into(),
+                    platform: Platform {
+                        architecture: "arm64".into(),
+                        os: "linux".into(),
+                        variant: Some("v8".into())
+                    }
+                },
+            ]
+        }
+    );
+    assert_eq!(manifest.architectures(), vec!["amd64", "arm64"]);
+}
+
+#[test]
+fn get_manifest_single_image() {
+    let manifest = MOCK_SERVER.mock(|w, t| {
+        w.method(GET).path("/v2/manifest/single/manifests/v1");
+        t.status(200)
+            .header("content-type", MEDIA_TYPE_OCI_MANIFEST)
+            .header("docker-content-digest", DIGEST)
+            .json_body(json!({
+                "schemaVersion": 2,
+                "config": {
+                    "mediaType": "application/vnd.oci.image.config.v1+json",
+                    "digest": "sha256:ccc",
+                    "size": 1
+                },
+                "layers": []
+            }));
+    });
+    let config = MOCK_SERVER.mock(|w, t| {
+        w.method(GET).path("/v2/manifest/single/blobs/sha256:ccc");
+        t.status(200)
+            .json_body(json!({"architecture": "arm64", "os": "linux", "rootfs": {}}));
+    });
+
+    let mut req = build("localhost/manifest/single:v1");
+    let resp = req.get_manifest().unwrap();
+    manifest.assert();
+    config.assert();
+    assert!(!resp.is_index());
+    assert_eq!(resp.digest, DIGEST);
+    assert_eq!(resp.architectures(), vec!["arm64"]);
+}
+
+#[test]
+fn build_requires_reference() {
+    assert_eq!(
+        RegistryRequestBuilder::new().build().unwrap_err(),
+        SeaplaneError::MissingImageReference
+    );
+}
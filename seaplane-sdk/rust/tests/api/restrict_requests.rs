@@ -1,8 +1,15 @@
+use std::{thread, time::Duration};
+
 use httpmock::{prelude::*, Method, Then, When};
 use once_cell::sync::Lazy;
-use seaplane::api::{
-    restrict::v1::{RestrictRequestBuilder, RestrictionDetails},
-    shared::v1::RangeQueryContext,
+use seaplane::{
+    api::{
+        restrict::v1::{
+            RestrictRequest, RestrictRequestBuilder, RestrictionDetails, RestrictionState,
+        },
+        shared::v1::RangeQueryContext,
+    },
+    error::SeaplaneError,
 };
 use serde_json::json;
 
@@ -63,6 +70,111 @@ fn get_restriction() {
     assert_eq!(resp, serde_json::from_value(resp_json).unwrap());
 }
 
+fn restriction_json(directory: &str, state: &str) -> serde_json::Value {
+    json!({
+        "api": "Config",
+        "directory": directory,
+        "details": {
+            "regions_allowed": ["XE"],
+            "regions_denied": [],
+            "providers_allowed": [],
+            "providers_denied": []
+        },
+        "state": state
+    })
+}
+
+#[test]
+fn wait_until_enforced() {
+    let mut pending = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/restrict/config/base64:d2FpdA/");
+        then(t, restriction_json("d2FpdA", "Pending"));
+    });
+
+    // Enforce the restriction after a few polls
+    let enforcer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(500));
+        let polls = pending.hits();
+        pending.delete();
+        let enforced = MOCK_SERVER.mock(|w, t| {
+            when(w, GET, "/v1/restrict/config/base64:d2FpdA/");
+            then(t, restriction_json("d2FpdA", "Enforced"));
+        });
+        (polls, enforced)
+    });
+
+    let resp = RestrictRequest::wait_until_enforced(
+        || {
+            partial_build()
+                .single_restriction("config", "d2FpdA")
+                .build()
+        },
+        Some(Duration::from_secs(10)),
+    )
+    .unwrap();
+    let (polls, enforced) = enforcer.join().unwrap();
+
+    enforced.assert();
+    assert!(polls >= 2);
+    assert_eq!(resp.state, RestrictionState::Enforced);
+}
+
+#[test]
+fn wait_until_enforced_timeout() {
+    let pending = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/restrict/config/base64:dGltZW91dA/");
+        then(t, restriction_json("dGltZW91dA", "Pending"));
+    });
+
+    let resp = RestrictRequest::wait_until_enforced(
+        || {
+            partial_build()
+                .single_restriction("config", "dGltZW91dA")
+                .build()
+        },
+        Some(Duration::from_millis(300)),
+    );
+
+    assert_eq!(resp, Err(SeaplaneError::RestrictionWaitTimeout));
+    assert!(pending.hits() >= 2);
+}
+
+#[test]
+fn wait_until_enforced_refreshes_token() {
+    // The first request carries a token which has since expired
+    let expired = MOCK_SERVER.mock(|w, t| {
+        w.method(GET)
+            .path("/v1/restrict/config/base64:ZXhwaXJl/")
+            .header("authorization", "Bearer expired");
+        t.status(401)
+            .header("content-type", "application/json")
+            .json_body(json!({"title": "Unauthorized", "status": 401, "detail": "token expired"}));
+    });
+    let enforced = MOCK_SERVER.mock(|w, t| {
+        when(w, GET, "/v1/restrict/config/base64:ZXhwaXJl/");
+        then(t, restriction_json("ZXhwaXJl", "Enforced"));
+    });
+
+    let mut built = 0;
+    let resp = RestrictRequest::wait_until_enforced(
+        || {
+            built += 1;
+            RestrictRequestBuilder::new()
+                .token(if built == 1 { "expired" } else { "abc123" })
+                .base_url(MOCK_SERVER.base_url())
+                .single_restriction("config", "ZXhwaXJl")
+                .build()
+        },
+        Some(Duration::from_secs(10)),
+    )
+    .unwrap();
+
+    expired.assert();
+    enforced.assert();
+    assert_eq!(built, 2);
+    assert_eq!(resp.state, RestrictionState::Enforced);
+}
+
 // PUT /restrict/{api}/base64:{key}/
 #[test]
 fn set_restriction() {